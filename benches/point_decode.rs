@@ -0,0 +1,25 @@
+//! Decoding of standalone points, as read from `geometry(Point)` columns.
+//!
+//! Compares the generic EWKB reader with the `FromSql` fast path for simple 2D points.
+
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+use postgis_butmaintained::ewkb::{AsEwkbPoint, EwkbRead, EwkbWrite, Point};
+use postgres_types::{FromSql, Kind, Type};
+
+fn point_decode(c: &mut Criterion) {
+    let ty = Type::new("geometry".into(), 0, Kind::Simple, "public".into());
+    for (name, srid) in [("no srid", None), ("srid", Some(4326))] {
+        let ewkb = Point::new(10.0, -20.0, srid).as_ewkb().to_ewkb_bytes();
+        let mut group = c.benchmark_group(format!("point decode ({})", name));
+        group.bench_function("read_ewkb", |b| {
+            b.iter(|| Point::read_ewkb(&mut black_box(ewkb.as_slice())).unwrap())
+        });
+        group.bench_function("from_sql", |b| {
+            b.iter(|| Point::from_sql(&ty, black_box(&ewkb)).unwrap())
+        });
+        group.finish();
+    }
+}
+
+criterion_group!(benches, point_decode);
+criterion_main!(benches);
@@ -1,16 +1,26 @@
 //! Read and write geometries in [OGC WKB](http://www.opengeospatial.org/standards/sfa) format.
 //!
 //! Support for SRID information according to [PostGIS EWKB extensions](https://git.osgeo.org/gitea/postgis/postgis/src/branch/master/doc/ZMSgeoms.txt)
+//!
+//! The `srid` fields are `None` if the SRID flag isn't set and `Some(0)` if the flag is set
+//! with the value 0, the PostGIS sentinel for an unknown SRID. Both are written back as they
+//! were read. PostGIS itself omits the flag for SRID 0, so geometries read from the
+//! database have `None` instead of `Some(0)`.
+//!
+//! SRIDs outside the range accepted by PostGIS (0 to 999999) are rejected when reading, as
+//! they usually indicate an SRID written with the wrong byte order.
 
-mod encoding;
+pub(crate) mod encoding;
 use crate::{error::Error, types as postgis};
 use byteorder::{LittleEndian, ReadBytesExt, WriteBytesExt};
+#[cfg(feature = "postgres")]
+use bytes::{BufMut, BytesMut};
 use encoding::*;
 use std;
 use std::fmt;
 use std::io::prelude::*;
 use std::iter::FromIterator;
-use std::slice::Iter;
+use std::slice::{Iter, IterMut};
 
 // Re-export point types
 pub mod point;
@@ -19,22 +29,156 @@ pub mod container;
 pub use container::point::*;
 mod geometry;
 pub use geometry::*;
+mod curve;
+pub use curve::*;
+#[cfg(feature = "tokio")]
+mod async_read;
+mod clip;
+mod compare;
+mod ewkt;
+pub use ewkt::{FloatFormat, WktWrite};
+#[cfg(feature = "geo-types")]
+mod geo_convert;
+#[cfg(feature = "geojson")]
+mod geojson;
+#[cfg(feature = "geo")]
+mod coords_iter;
+mod indexed;
+pub use indexed::IndexedMultiPolygon;
+mod linear;
+mod measure;
+pub use measure::{Orientation, Spheroid};
+mod merge;
+mod normalize;
+mod order;
+mod parts;
+mod peek;
+pub use peek::{
+    GeomKind, GeometryKind, RawGeometry, TypedGeometryReader, peek_geom_type, peek_srid,
+};
+mod relate;
+pub use relate::winding_number;
+mod simplify;
+mod srid;
+pub use srid::Srid;
+mod stats;
+pub use stats::GeomStats;
+mod transform;
+pub use transform::CoordTransform;
+mod triangulate;
+mod validate;
+pub use validate::ValidationError;
 
 // --- Traits
 
+/// Maximum number of trailing NUL bytes accepted by [`EwkbRead::read_ewkb_trim`].
+pub const MAX_TRAILING_PADDING: usize = 8;
+
+/// Maximum nesting depth of geometry collections accepted by [`EwkbRead::read_ewkb`].
+pub const MAX_NESTING_DEPTH: usize = 100;
+
 pub trait EwkbRead: fmt::Debug + Sized {
     fn point_type() -> PointType;
 
+    /// Read a geometry in EWKB format.
+    ///
+    /// Read errors include the position in the geometry where reading stopped,
+    /// e.g. `MultiPolygon.polygons[2].rings[0].points[14]`. Geometry collections nested
+    /// deeper than [`MAX_NESTING_DEPTH`] are rejected, see
+    /// [`read_ewkb_with_max_depth`](Self::read_ewkb_with_max_depth).
+    ///
+    /// Dimensions missing in the point type are dropped. Dimensions missing in the data are
+    /// set to 0, or to NaN for empty points.
     fn read_ewkb<R: Read>(raw: &mut R) -> Result<Self, Error> {
-        let byte_order = raw.read_i8()?;
-        let is_be = byte_order == 0i8;
+        let mut ctx = ReadContext::default();
+        Self::read_ewkb_with_context(raw, &mut ctx).map_err(|e| ctx.annotate(e))
+    }
+
+    /// Read a geometry, rejecting geometry collections nested deeper than `max_depth`.
+    ///
+    /// A collection directly containing only non-collections has depth 1. Reading recurses
+    /// for every level of nesting, so the limit protects against stack overflows on
+    /// untrusted input.
+    fn read_ewkb_with_max_depth<R: Read>(raw: &mut R, max_depth: usize) -> Result<Self, Error> {
+        let mut ctx = ReadContext {
+            max_depth: Some(max_depth),
+            ..Default::default()
+        };
+        Self::read_ewkb_with_context(raw, &mut ctx).map_err(|e| ctx.annotate(e))
+    }
 
-        let type_id = read_u32(raw, is_be)?;
-        let mut srid: Option<i32> = None;
-        if type_id & 0x20000000 == 0x20000000 {
-            srid = Some(read_i32(raw, is_be)?);
+    /// Read a geometry from `raw`, tolerating a few trailing NUL padding bytes.
+    ///
+    /// Up to [`MAX_TRAILING_PADDING`] NUL bytes after the geometry are ignored. Any other
+    /// trailing data is rejected with an error.
+    fn read_ewkb_trim(raw: &[u8]) -> Result<Self, Error> {
+        let mut rdr = raw;
+        let geom = Self::read_ewkb(&mut rdr)?;
+        if rdr.len() > MAX_TRAILING_PADDING || rdr.iter().any(|&b| b != 0) {
+            return Err(Error::Read(format!(
+                "unexpected {} bytes of trailing data after geometry",
+                rdr.len()
+            )));
+        }
+        Ok(geom)
+    }
+
+    /// Read a geometry, requiring the Z and M flags of every (sub-)geometry to match the point
+    /// type of `Self`.
+    ///
+    /// [`read_ewkb`](Self::read_ewkb) drops dimensions missing in the point type and sets
+    /// dimensions missing in the data to 0, e.g. reading a 2D line as `LineStringT<PointZ>`
+    /// results in an elevation of 0 for every point. This variant returns an error instead.
+    fn read_ewkb_strict<R: Read>(raw: &mut R) -> Result<Self, Error> {
+        let mut ctx = ReadContext {
+            strict_point_type: Some(Self::point_type()),
+            ..Default::default()
+        };
+        Self::read_ewkb_with_context(raw, &mut ctx).map_err(|e| ctx.annotate(e))
+    }
+
+    /// Read a geometry, rejecting NaN and infinite coordinates.
+    ///
+    /// Points with all coordinates NaN are accepted as long as they are points on their own,
+    /// e.g. a `POINT EMPTY` or an empty member of a multipoint, but not as vertices of lines
+    /// or rings.
+    fn read_ewkb_reject_nonfinite<R: Read>(raw: &mut R) -> Result<Self, Error> {
+        let mut ctx = ReadContext {
+            reject_nonfinite: true,
+            ..Default::default()
+        };
+        Self::read_ewkb_with_context(raw, &mut ctx).map_err(|e| ctx.annotate(e))
+    }
+
+    /// Read a geometry from base64 encoded EWKB.
+    #[cfg(feature = "base64")]
+    fn from_base64_ewkb(s: &str) -> Result<Self, Error> {
+        use base64::Engine;
+        let raw = base64::engine::general_purpose::STANDARD
+            .decode(s.trim())
+            .map_err(|e| Error::Read(format!("invalid base64: {}", e)))?;
+        Self::read_ewkb(&mut raw.as_slice())
+    }
+
+    /// Read a geometry in EWKB format from an async reader, like [`read_ewkb`](Self::read_ewkb).
+    ///
+    /// The bytes of the geometry are read without reading past its end and then decoded in
+    /// memory, so the runtime isn't blocked while waiting for data.
+    #[cfg(feature = "tokio")]
+    fn read_ewkb_async<R>(raw: &mut R) -> impl Future<Output = Result<Self, Error>> + Send
+    where
+        R: tokio::io::AsyncRead + Unpin + Send,
+    {
+        async move {
+            let buf = async_read::read_ewkb_bytes(raw).await?;
+            Self::read_ewkb(&mut buf.as_slice())
         }
-        Self::read_ewkb_body(raw, is_be, type_id, srid)
+    }
+
+    #[doc(hidden)]
+    fn read_ewkb_with_context<R: Read>(raw: &mut R, ctx: &mut ReadContext) -> Result<Self, Error> {
+        let (is_be, type_id, srid) = read_ewkb_header(raw, ctx)?;
+        Self::read_ewkb_body(raw, is_be, type_id, srid, ctx)
     }
 
     #[doc(hidden)]
@@ -43,9 +187,148 @@ pub trait EwkbRead: fmt::Debug + Sized {
         is_be: bool,
         type_id: u32,
         srid: Option<i32>,
+        ctx: &mut ReadContext,
     ) -> Result<Self, Error>;
 }
 
+/// State of a running `read_ewkb`, used for error context.
+#[doc(hidden)]
+#[derive(Debug, Default)]
+pub struct ReadContext {
+    root: Option<&'static str>,
+    // Recorded on error while unwinding, innermost item first
+    path: Vec<(&'static str, usize)>,
+    // Point type required by `read_ewkb_strict`
+    strict_point_type: Option<PointType>,
+    // Dimension flags of all headers read so far
+    seen_z: bool,
+    seen_m: bool,
+    // SRID of the outermost header, once it has been read
+    root_srid: Option<Option<i32>>,
+    // Set by `read_ewkb_reject_nonfinite`
+    reject_nonfinite: bool,
+    // Number of geometry collections enclosing the geometry being read
+    depth: usize,
+    // Set by `read_ewkb_with_max_depth`, `MAX_NESTING_DEPTH` otherwise
+    max_depth: Option<usize>,
+}
+
+impl ReadContext {
+    /// Record that reading item `index` of `field` failed with `err`.
+    pub(crate) fn at(&mut self, field: &'static str, index: usize, err: Error) -> Error {
+        self.path.push((field, index));
+        err
+    }
+
+    /// Enter a geometry collection, failing if it is nested too deep.
+    pub(crate) fn enter_collection(&mut self) -> Result<(), Error> {
+        self.depth += 1;
+        if self.depth > self.max_depth.unwrap_or(MAX_NESTING_DEPTH) {
+            return Err(Error::Read("max nesting depth exceeded".to_string()));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn leave_collection(&mut self) {
+        self.depth -= 1;
+    }
+
+    fn annotate(&self, err: Error) -> Error {
+        match (err, self.root) {
+            (Error::Read(msg), Some(root)) => {
+                let mut path = root.to_string();
+                for (field, index) in self.path.iter().rev() {
+                    path += &format!(".{}[{}]", field, index);
+                }
+                Error::Read(format!("{} at {}", msg, path))
+            }
+            (err, _) => err,
+        }
+    }
+}
+
+/// Largest SRID accepted by PostGIS.
+pub(crate) const SRID_MAXIMUM: i32 = 999_999;
+
+/// Read byte order, type id and SRID of a geometry.
+fn read_ewkb_header<R: Read>(
+    raw: &mut R,
+    ctx: &mut ReadContext,
+) -> Result<(bool, u32, Option<i32>), Error> {
+    let byte_order = raw.read_i8()?;
+    let is_be = byte_order == 0i8;
+
+    let type_id = normalize_type_id(read_u32(raw, is_be)?);
+    ctx.root.get_or_insert(geometry_type_name(type_id));
+    ctx.seen_z |= has_z(type_id);
+    ctx.seen_m |= has_m(type_id);
+    if let Some(expected) = ctx.strict_point_type {
+        let found = PointType::from_type_id(type_id);
+        if found != expected {
+            return Err(Error::Read(format!(
+                "expected {:?} coordinates, found {:?}",
+                expected, found
+            )));
+        }
+    }
+    let mut srid: Option<i32> = None;
+    if type_id & 0x20000000 == 0x20000000 {
+        let value = read_i32(raw, is_be)?;
+        // Usually an SRID written with the wrong byte order, e.g. 4326 read as 0xE6100000
+        if !(0..=SRID_MAXIMUM).contains(&value) {
+            return Err(Error::Read(format!(
+                "implausible SRID value {}, possible endianness mismatch",
+                value
+            )));
+        }
+        srid = Some(value);
+    }
+    // Cached bounding box as written by liblwgeom: float32 min/max pairs for X, Y and,
+    // if present, Z and M. It is not needed for decoding.
+    if type_id & 0x10000000 == 0x10000000 {
+        let mut bbox = [0u8; 32];
+        let size = PointType::from_type_id(type_id).num_coords() * 8;
+        raw.read_exact(&mut bbox[..size])?;
+    }
+    // Sub-geometries don't have an SRID in standard EWKB. Some writers set it anyway, in
+    // which case the SRID of the outermost geometry takes precedence, if it has one.
+    match ctx.root_srid {
+        None => ctx.root_srid = Some(srid),
+        Some(root_srid) => srid = srid.and(root_srid).or(srid),
+    }
+    Ok((is_be, type_id, srid))
+}
+
+/// Convert ISO WKB type codes (e.g. 1001 for Point Z) to EWKB type flags.
+fn normalize_type_id(type_id: u32) -> u32 {
+    let code = type_id & 0x0fffffff;
+    if !(1000..4000).contains(&code) {
+        return type_id;
+    }
+    let (z, m) = match code / 1000 {
+        1 => (0x80000000, 0),
+        2 => (0, 0x40000000),
+        _ => (0x80000000, 0x40000000),
+    };
+    (type_id & 0xf0000000) | z | m | (code % 1000)
+}
+
+pub(crate) fn geometry_type_name(type_id: u32) -> &'static str {
+    match type_id & 0xff {
+        0x01 => "Point",
+        0x02 => "LineString",
+        0x03 => "Polygon",
+        0x04 => "MultiPoint",
+        0x05 => "MultiLineString",
+        0x06 => "MultiPolygon",
+        0x07 => "GeometryCollection",
+        0x08 => "CircularString",
+        0x09 => "CompoundCurve",
+        0x0a => "CurvePolygon",
+        _ => "Geometry",
+    }
+}
+
 pub trait EwkbWrite: fmt::Debug + Sized {
     fn opt_srid(&self) -> Option<i32> {
         None
@@ -56,10 +339,10 @@ pub trait EwkbWrite: fmt::Debug + Sized {
         if srid.is_some() {
             type_ |= 0x20000000;
         }
-        if *point_type == PointType::PointZ || *point_type == PointType::PointZM {
+        if point_type.has_z() {
             type_ |= 0x80000000;
         }
-        if *point_type == PointType::PointM || *point_type == PointType::PointZM {
+        if point_type.has_m() {
             type_ |= 0x40000000;
         }
         type_
@@ -72,17 +355,54 @@ pub trait EwkbWrite: fmt::Debug + Sized {
         w.write_u8(0x01)?;
         let type_id = self.type_id();
         w.write_u32::<LittleEndian>(type_id)?;
-        self.opt_srid()
-            .map(|srid| w.write_i32::<LittleEndian>(srid));
+        if let Some(srid) = self.opt_srid() {
+            w.write_i32::<LittleEndian>(srid)?;
+        }
         self.write_ewkb_body(w)?;
         Ok(())
     }
+    /// Write the geometry like `write_ewkb` and return the number of bytes written, e.g. for
+    /// framing geometries in a binary protocol.
+    fn write_ewkb_counted<W: Write + ?Sized>(&self, w: &mut W) -> Result<usize, Error> {
+        let mut counter = CountingWriter { inner: w, count: 0 };
+        self.write_ewkb(&mut counter)?;
+        Ok(counter.count)
+    }
+
     #[doc(hidden)]
     fn write_ewkb_body<W: Write + ?Sized>(&self, w: &mut W) -> Result<(), Error>;
 
-    fn to_hex_ewkb(&self) -> String {
-        let mut buf: Vec<u8> = Vec::new();
+    /// Number of bytes written by `write_ewkb`.
+    ///
+    /// Computed by walking the geometry without serializing it. Exact for all adapters of
+    /// this crate.
+    fn ewkb_size_hint(&self) -> usize {
+        let srid_size = if self.opt_srid().is_some() { 4 } else { 0 };
+        1 + 4 + srid_size + self.ewkb_body_size_hint()
+    }
+
+    #[doc(hidden)]
+    fn ewkb_body_size_hint(&self) -> usize {
+        0
+    }
+
+    /// Append the EWKB representation to `out`, reserving `ewkb_size_hint()` bytes up front.
+    ///
+    /// Allows reusing a buffer across calls without reallocations while writing.
+    #[cfg(feature = "postgres")]
+    fn write_ewkb_to_bytes_mut(&self, out: &mut BytesMut) -> Result<(), Error> {
+        out.reserve(self.ewkb_size_hint());
+        self.write_ewkb(&mut out.writer())
+    }
+
+    fn to_ewkb_bytes(&self) -> Vec<u8> {
+        let mut buf: Vec<u8> = Vec::with_capacity(self.ewkb_size_hint());
         self.write_ewkb(&mut buf).unwrap();
+        buf
+    }
+
+    fn to_hex_ewkb(&self) -> String {
+        let buf = self.to_ewkb_bytes();
         let hex: String = buf
             .iter()
             .fold(String::new(), |s, &b| s + &format!("{:02X}", b));
@@ -92,6 +412,23 @@ pub trait EwkbWrite: fmt::Debug + Sized {
 
 // --- helpers
 
+/// Writer counting the bytes passed to `inner`.
+struct CountingWriter<'a, W: Write + ?Sized> {
+    inner: &'a mut W,
+    count: usize,
+}
+
+impl<W: Write + ?Sized> Write for CountingWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.count += written;
+        Ok(written)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
 impl From<std::io::Error> for Error {
     fn from(e: std::io::Error) -> Error {
         Error::Read(format!("error while reading: {:?}", e))
@@ -195,6 +532,119 @@ fn test_multipolygon_write() {
     let poly2 = PolygonT::<Point> {srid: Some(4326), rings: vec![line]};
     let multipoly = MultiPolygonT::<Point> {srid: Some(4326), polygons: vec![poly1, poly2]};
     assert_eq!(multipoly.as_ewkb().to_hex_ewkb(), "0106000020E610000002000000010300000001000000050000000000000000000000000000000000000000000000000000400000000000000000000000000000004000000000000000400000000000000000000000000000004000000000000000000000000000000000010300000001000000050000000000000000002440000000000000244000000000000000C0000000000000244000000000000000C000000000000000C0000000000000244000000000000000C000000000000024400000000000002440");
+
+    let from_slice = EwkbMultiPolygonFromSlice::new(&multipoly.polygons, Some(4326));
+    assert_eq!(from_slice.to_hex_ewkb(), multipoly.as_ewkb().to_hex_ewkb());
+    assert_eq!(from_slice.ewkb_size_hint(), from_slice.to_ewkb_bytes().len());
+    assert_eq!(EwkbMultiPolygonFromSlice::<Point>::new(&[], None).to_hex_ewkb(), "010600000000000000");
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_ewkb_size_hint() {
+    let p = |x, y| Point::new(x, y, Some(4326));
+    let point = p(10.0, -20.0);
+    assert_eq!(point.as_ewkb().ewkb_size_hint(), 25);
+    assert_eq!(point.as_ewkb().ewkb_size_hint(), point.as_ewkb().to_ewkb_bytes().len());
+
+    let pz = |x, y, z| PointZ { x, y, z, srid: None };
+    let points = MultiPointT::<PointZ> {srid: None, points: vec![pz(10.0, -20.0, 100.0), pz(0., -0.5, 101.0)]};
+    assert_eq!(points.as_ewkb().ewkb_size_hint(), points.as_ewkb().to_ewkb_bytes().len());
+
+    let line = LineStringT::<Point> {srid: Some(4326), points: vec![p(0., 0.), p(2., 0.), p(2., 2.), p(0., 2.), p(0., 0.)]};
+    assert_eq!(line.as_ewkb().ewkb_size_hint(), line.as_ewkb().to_ewkb_bytes().len());
+    let poly = PolygonT::<Point> {srid: Some(4326), rings: vec![line.clone(), line.clone()]};
+    assert_eq!(poly.as_ewkb().ewkb_size_hint(), poly.as_ewkb().to_ewkb_bytes().len());
+    let multiline = MultiLineStringT::<Point> {srid: None, lines: vec![line.clone(), LineStringT::new()]};
+    assert_eq!(multiline.as_ewkb().ewkb_size_hint(), multiline.as_ewkb().to_ewkb_bytes().len());
+    let multipoly = MultiPolygonT::<Point> {srid: Some(4326), polygons: vec![poly.clone(), PolygonT::new()]};
+    assert_eq!(multipoly.as_ewkb().ewkb_size_hint(), multipoly.as_ewkb().to_ewkb_bytes().len());
+
+    let collection = GeometryCollectionT::<Point> {srid: Some(4326), geometries: vec![
+        GeometryT::Point(point),
+        GeometryT::LineString(line),
+        GeometryT::MultiPolygon(multipoly),
+        GeometryT::GeometryCollection(GeometryCollectionT::new()),
+    ]};
+    assert_eq!(collection.as_ewkb().ewkb_size_hint(), collection.as_ewkb().to_ewkb_bytes().len());
+    let geom = GeometryT::GeometryCollection(collection);
+    assert_eq!(geom.as_ewkb().ewkb_size_hint(), geom.as_ewkb().to_ewkb_bytes().len());
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_write_ewkb_counted() {
+    let point = Point::new(10.0, -20.0, Some(4326));
+    let line = LineStringT::<PointZ> {srid: None, points: vec![PointZ::new(0., 0., 1., None), PointZ::new(2., 0., 1., None)]};
+    let mut buf = vec![0xFF];
+    assert_eq!(point.as_ewkb().write_ewkb_counted(&mut buf).unwrap(), 25);
+    assert_eq!(line.as_ewkb().write_ewkb_counted(&mut buf).unwrap(), line.as_ewkb().ewkb_size_hint());
+    assert_eq!(buf.len(), 1 + 25 + line.as_ewkb().ewkb_size_hint());
+    assert_eq!(&buf[1..26], &point.as_ewkb().to_ewkb_bytes()[..]);
+
+    let mut full = ChunkRecorder {chunks: Vec::new(), limit: 10};
+    assert!(point.as_ewkb().write_ewkb_counted(&mut full).is_err());
+}
+
+#[test]
+#[cfg(feature = "postgres")]
+#[rustfmt::skip]
+fn test_write_to_bytes_mut() {
+    let point = Point::new(10.0, -20.0, Some(4326));
+    let mut buf = BytesMut::new();
+    point.as_ewkb().write_ewkb_to_bytes_mut(&mut buf).unwrap();
+    point.as_ewkb().write_ewkb_to_bytes_mut(&mut buf).unwrap();
+    let mut expected = Vec::new();
+    point.as_ewkb().write_ewkb(&mut expected).unwrap();
+    point.as_ewkb().write_ewkb(&mut expected).unwrap();
+    assert_eq!(&buf[..], &expected[..]);
+}
+
+/// Writer recording the size of each write and failing once more than `limit` bytes are written.
+#[cfg(test)]
+#[derive(Debug)]
+struct ChunkRecorder {
+    chunks: Vec<usize>,
+    limit: usize,
+}
+
+#[cfg(test)]
+impl Write for ChunkRecorder {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if buf.len() > self.limit {
+            return Err(std::io::Error::other("limit reached"));
+        }
+        self.limit -= buf.len();
+        self.chunks.push(buf.len());
+        Ok(buf.len())
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_write_incremental() {
+    // 200 polygons with 1000 points each
+    let ring = |i: usize| LineStringT::<Point> {srid: None, points: (0..1000).map(|j| Point::new(i as f64, j as f64, None)).collect()};
+    let multi = MultiPolygonT::<Point> {srid: Some(4326), polygons: (0..200).map(|i| PolygonT {srid: None, rings: vec![ring(i)]}).collect()};
+    let size = multi.as_ewkb().ewkb_size_hint();
+
+    // The geometry is passed on in buffer sized chunks, never as a whole
+    let mut w = std::io::BufWriter::with_capacity(8192, ChunkRecorder {chunks: Vec::new(), limit: usize::MAX});
+    multi.as_ewkb().write_ewkb(&mut w).unwrap();
+    let recorder = w.into_inner().unwrap();
+    assert_eq!(recorder.chunks.iter().sum::<usize>(), size);
+    assert!(recorder.chunks.len() >= size / 8192);
+    assert!(recorder.chunks.iter().all(|&len| len <= 8192));
+
+    // Write errors abort the serialization, including errors writing the SRID
+    for limit in [6, 1000, size - 1] {
+        let mut w = ChunkRecorder {chunks: Vec::new(), limit};
+        assert!(multi.as_ewkb().write_ewkb(&mut w).is_err());
+        assert!(w.chunks.iter().sum::<usize>() <= limit);
+    }
 }
 
 #[test]
@@ -208,7 +658,7 @@ fn test_ewkb_adapters() {
 
 #[cfg(test)]
 #[rustfmt::skip]
-fn hex_to_vec(hexstr: &str) -> Vec<u8> {
+pub(crate) fn hex_to_vec(hexstr: &str) -> Vec<u8> {
     hexstr.as_bytes().chunks(2).map(|chars| {
         let hb = if chars[0] <= 57 { chars[0] - 48 } else { chars[0] - 55 };
         let lb = if chars[1] <= 57 { chars[1] - 48 } else { chars[1] - 55 };
@@ -216,6 +666,13 @@ fn hex_to_vec(hexstr: &str) -> Vec<u8> {
     }).collect::<Vec<_>>()
 }
 
+/// 2D line without SRIDs from `(x, y)` tuples.
+#[cfg(test)]
+#[rustfmt::skip]
+pub(crate) fn line_xy(coords: &[(f64, f64)]) -> LineStringT<Point> {
+    LineStringT {srid: None, points: coords.iter().map(|&(x, y)| Point::new(x, y, None)).collect()}
+}
+
 #[test]
 #[rustfmt::skip]
 fn test_point_read() {
@@ -285,6 +742,254 @@ fn test_multipoint_read() {
     assert_eq!(points, MultiPointT::<PointZ> {srid: Some(4326), points: vec![p(10.0, -20.0, 100.0), p(0., -0.5, 101.0)]});
 }
 
+#[test]
+#[rustfmt::skip]
+fn test_srid_m_read() {
+    let p = |x, y, m| PointM { x, y, m, srid: Some(4326) };
+    // SELECT 'SRID=4326;LINESTRINGM (10 -20 1, 0 -0.5 2)'::geometry
+    let ewkb = hex_to_vec("0102000060E610000002000000000000000000244000000000000034C0000000000000F03F0000000000000000000000000000E0BF0000000000000040");
+    let line = LineStringT::<PointM>::read_ewkb(&mut ewkb.as_slice()).unwrap();
+    assert_eq!(line, LineStringT::<PointM> {srid: Some(4326), points: vec![p(10.0, -20.0, 1.0), p(0., -0.5, 2.0)]});
+    assert_eq!(line.as_ewkb().to_ewkb_bytes(), ewkb);
+
+    let p = |x, y, z, m| PointZM { x, y, z, m, srid: Some(4326) };
+    // SELECT 'SRID=4326;LINESTRING ZM (10 -20 100 1, 0 -0.5 101 2)'::geometry
+    let ewkb = hex_to_vec("01020000E0E610000002000000000000000000244000000000000034C00000000000005940000000000000F03F0000000000000000000000000000E0BF00000000004059400000000000000040");
+    let line = LineStringT::<PointZM>::read_ewkb(&mut ewkb.as_slice()).unwrap();
+    assert_eq!(line, LineStringT::<PointZM> {srid: Some(4326), points: vec![p(10.0, -20.0, 100.0, 1.0), p(0., -0.5, 101.0, 2.0)]});
+    assert_eq!(line.as_ewkb().to_ewkb_bytes(), ewkb);
+
+    let p = |x, y, m| PointM { x, y, m, srid: None };
+    // SELECT 'SRID=4326;MULTIPOINTM ((10 -20 1), (0 -0.5 2))'::geometry
+    let ewkb = hex_to_vec("0104000060E6100000020000000101000040000000000000244000000000000034C0000000000000F03F01010000400000000000000000000000000000E0BF0000000000000040");
+    let points = MultiPointT::<PointM>::read_ewkb(&mut ewkb.as_slice()).unwrap();
+    assert_eq!(points, MultiPointT::<PointM> {srid: Some(4326), points: vec![p(10.0, -20.0, 1.0), p(0., -0.5, 2.0)]});
+    assert_eq!(points.as_ewkb().to_ewkb_bytes(), ewkb);
+
+    let p = |x, y, z, m| PointZM { x, y, z, m, srid: None };
+    // SELECT 'SRID=4326;MULTIPOINT ZM ((10 -20 100 1), (0 -0.5 101 2))'::geometry
+    let ewkb = hex_to_vec("01040000E0E61000000200000001010000C0000000000000244000000000000034C00000000000005940000000000000F03F01010000C00000000000000000000000000000E0BF00000000004059400000000000000040");
+    let points = MultiPointT::<PointZM>::read_ewkb(&mut ewkb.as_slice()).unwrap();
+    assert_eq!(points, MultiPointT::<PointZM> {srid: Some(4326), points: vec![p(10.0, -20.0, 100.0, 1.0), p(0., -0.5, 101.0, 2.0)]});
+    assert_eq!(points.as_ewkb().to_ewkb_bytes(), ewkb);
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_m_roundtrip() {
+    // Same geometries as in `test_srid_m_read` without SRID, through the type aliases and
+    // read back as generic geometries, which must keep M apart from Z
+    let p = |x, y, m| PointM::new(x, y, m, None);
+    // SELECT 'LINESTRINGM (10 -20 1, 0 -0.5 2)'::geometry
+    let ewkb = hex_to_vec("010200004002000000000000000000244000000000000034C0000000000000F03F0000000000000000000000000000E0BF0000000000000040");
+    let line = LineStringM {srid: None, points: vec![p(10.0, -20.0, 1.0), p(0., -0.5, 2.0)]};
+    assert_eq!(line.as_ewkb().to_ewkb_bytes(), ewkb);
+    assert_eq!(LineStringM::read_ewkb(&mut ewkb.as_slice()).unwrap(), line);
+    let geom = GeometryT::<PointZM>::read_ewkb(&mut ewkb.as_slice()).unwrap();
+    assert!(matches!(geom, GeometryT::LineString(ref l) if l.points[1] == PointZM::new(0., -0.5, 0., 2.0, None)));
+
+    // SELECT 'MULTIPOINTM ((10 -20 1), (0 -0.5 2))'::geometry
+    let ewkb = hex_to_vec("0104000040020000000101000040000000000000244000000000000034C0000000000000F03F01010000400000000000000000000000000000E0BF0000000000000040");
+    let multi = MultiPointM {srid: None, points: line.points.clone()};
+    assert_eq!(multi.as_ewkb().to_ewkb_bytes(), ewkb);
+    assert_eq!(MultiPointM::read_ewkb(&mut ewkb.as_slice()).unwrap(), multi);
+    let geom = GeometryT::<PointM>::read_ewkb(&mut ewkb.as_slice()).unwrap();
+    assert_eq!(geom.coord_type(), PointType::PointM);
+    assert_eq!(geom.as_ewkb().to_ewkb_bytes(), ewkb);
+
+    let p = |x, y, z, m| PointZM::new(x, y, z, m, None);
+    // SELECT 'LINESTRING ZM (10 -20 100 1, 0 -0.5 101 2)'::geometry
+    let ewkb = hex_to_vec("01020000C002000000000000000000244000000000000034C00000000000005940000000000000F03F0000000000000000000000000000E0BF00000000004059400000000000000040");
+    let line = LineStringZM {srid: None, points: vec![p(10.0, -20.0, 100.0, 1.0), p(0., -0.5, 101.0, 2.0)]};
+    assert_eq!(line.as_ewkb().to_ewkb_bytes(), ewkb);
+    assert_eq!(LineStringZM::read_ewkb(&mut ewkb.as_slice()).unwrap(), line);
+
+    // SELECT 'MULTIPOINT ZM ((10 -20 100 1), (0 -0.5 101 2))'::geometry
+    let ewkb = hex_to_vec("01040000C00200000001010000C0000000000000244000000000000034C00000000000005940000000000000F03F01010000C00000000000000000000000000000E0BF00000000004059400000000000000040");
+    let multi = MultiPointZM {srid: None, points: line.points.clone()};
+    assert_eq!(multi.as_ewkb().to_ewkb_bytes(), ewkb);
+    assert_eq!(MultiPointZM::read_ewkb(&mut ewkb.as_slice()).unwrap(), multi);
+    let geom = GeometryT::<PointZM>::read_ewkb(&mut ewkb.as_slice()).unwrap();
+    assert_eq!(geom.coord_type(), PointType::PointZM);
+    assert_eq!(geom.as_ewkb().to_ewkb_bytes(), ewkb);
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_read_xdr() {
+    // SELECT ST_AsEWKB('SRID=4326;POINT (10 -20)'::geometry, 'XDR')
+    let ewkb = hex_to_vec("0020000001000010E64024000000000000C034000000000000");
+    assert_eq!(Point::read_ewkb(&mut ewkb.as_slice()).unwrap(), Point::new(10.0, -20.0, Some(4326)));
+    let geom = GeometryT::<Point>::read_ewkb(&mut ewkb.as_slice()).unwrap();
+    assert!(matches!(geom, GeometryT::Point(p) if p == Point::new(10.0, -20.0, Some(4326))));
+
+    // SELECT ST_AsEWKB('SRID=4326;LINESTRING (10 -20 100, 0 -0.5 101)'::geometry, 'XDR')
+    let ewkb = hex_to_vec("00A0000002000010E6000000024024000000000000C03400000000000040590000000000000000000000000000BFE00000000000004059400000000000");
+    let line = LineStringT::<PointZ>::read_ewkb(&mut ewkb.as_slice()).unwrap();
+    let p = |x, y, z| PointZ::new(x, y, z, Some(4326));
+    assert_eq!(line, LineStringT::<PointZ> {srid: Some(4326), points: vec![p(10.0, -20.0, 100.0), p(0., -0.5, 101.0)]});
+    // Written back as NDR
+    assert_eq!(line.as_ewkb().to_hex_ewkb(), "01020000A0E610000002000000000000000000244000000000000034C000000000000059400000000000000000000000000000E0BF0000000000405940");
+
+    // SELECT ST_AsEWKB('SRID=3857;POLYGON ((0 0, 2 0, 2 2, 0 2, 0 0), (0.5 0.5, 0.5 1, 1 1, 0.5 0.5))'::geometry, 'XDR')
+    let ewkb = hex_to_vec("002000000300000F1100000002000000050000000000000000000000000000000040000000000000000000000000000000400000000000000040000000000000000000000000000000400000000000000000000000000000000000000000000000000000043FE00000000000003FE00000000000003FE00000000000003FF00000000000003FF00000000000003FF00000000000003FE00000000000003FE0000000000000");
+    let poly = PolygonT::<Point>::read_ewkb(&mut ewkb.as_slice()).unwrap();
+    assert_eq!(poly.srid, Some(3857));
+    assert_eq!(poly.rings.len(), 2);
+    assert_eq!(poly.rings[1].points[1], Point::new(0.5, 1.0, Some(3857)));
+    assert_eq!(poly.to_ewkt(), "SRID=3857;POLYGON ((0 0, 2 0, 2 2, 0 2, 0 0), (0.5 0.5, 0.5 1, 1 1, 0.5 0.5))");
+
+    // SELECT ST_AsEWKB('MULTIPOINTM ((10 -20 1), (0 -0.5 2))'::geometry, 'XDR')
+    let ewkb = hex_to_vec("00400000040000000200400000014024000000000000C0340000000000003FF000000000000000400000010000000000000000BFE00000000000004000000000000000");
+    let multi = MultiPointT::<PointM>::read_ewkb(&mut ewkb.as_slice()).unwrap();
+    assert_eq!(multi.to_ewkt(), "MULTIPOINTM ((10 -20 1), (0 -0.5 2))");
+
+    // SELECT ST_AsEWKB('SRID=4326;GEOMETRYCOLLECTION (POINT (10 -20), LINESTRING (0 0, 1 1))'::geometry, 'XDR')
+    let ewkb = hex_to_vec("0020000007000010E60000000200000000014024000000000000C034000000000000000000000200000002000000000000000000000000000000003FF00000000000003FF0000000000000");
+    let geom = GeometryT::<Point>::read_ewkb(&mut ewkb.as_slice()).unwrap();
+    assert_eq!(geom.to_ewkt(), "SRID=4326;GEOMETRYCOLLECTION (POINT (10 -20), LINESTRING (0 0, 1 1))");
+
+    // Each sub-geometry has its own byte order: XDR collection with an NDR point
+    let ewkb = hex_to_vec("0000000007000000020101000000000000000000244000000000000034C0000000000200000002000000000000000000000000000000003FF00000000000003FF0000000000000");
+    let geom = GeometryT::<Point>::read_ewkb(&mut ewkb.as_slice()).unwrap();
+    assert_eq!(geom.to_ewkt(), "GEOMETRYCOLLECTION (POINT (10 -20), LINESTRING (0 0, 1 1))");
+
+    // SELECT ST_AsEWKB('COMPOUNDCURVE (CIRCULARSTRING (0 0, 1 1, 2 0), (2 0, 3 0))'::geometry, 'XDR')
+    let ewkb = hex_to_vec("000000000900000002000000000800000003000000000000000000000000000000003FF00000000000003FF0000000000000400000000000000000000000000000000000000002000000024000000000000000000000000000000040080000000000000000000000000000");
+    let geom = GeometryT::<Point>::read_ewkb(&mut ewkb.as_slice()).unwrap();
+    assert_eq!(geom.to_ewkt(), "COMPOUNDCURVE (CIRCULARSTRING (0 0, 1 1, 2 0), (2 0, 3 0))");
+
+    // SELECT ST_AsEWKB('SRID=4326;CURVEPOLYGON (CIRCULARSTRING (0 0, 2 0, 0 0))'::geometry, 'XDR')
+    let ewkb = hex_to_vec("002000000A000010E600000001000000000800000003000000000000000000000000000000004000000000000000000000000000000000000000000000000000000000000000");
+    let geom = GeometryT::<Point>::read_ewkb(&mut ewkb.as_slice()).unwrap();
+    assert_eq!(geom.to_ewkt(), "SRID=4326;CURVEPOLYGON (CIRCULARSTRING (0 0, 2 0, 0 0))");
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_flag_combinations_roundtrip() {
+    // Write every geometry type with all Z/M and SRID flag combinations, read it back
+    // as generic geometry and check that it is written again unchanged.
+    macro_rules! check_roundtrip {
+        ($ptype:ident, $flags:expr) => {
+            for srid in [None, Some(4326)] {
+                let p = |x, y| $ptype::new_from_opt_vals(x, y, Some(100.0), Some(1.0), None);
+                let line = LineStringT::<$ptype> {srid: None, points: vec![p(0., 0.), p(2., 0.), p(2., 2.), p(0., 0.)]};
+                let poly = PolygonT::<$ptype> {srid: None, rings: vec![line.clone()]};
+                let geoms = vec![
+                    GeometryT::Point($ptype::new_from_opt_vals(1.0, 2.0, Some(100.0), Some(1.0), srid)),
+                    GeometryT::LineString(LineStringT {srid, ..line.clone()}),
+                    GeometryT::Polygon(PolygonT {srid, ..poly.clone()}),
+                    GeometryT::MultiPoint(MultiPointT {srid, points: line.points.clone()}),
+                    GeometryT::MultiLineString(MultiLineStringT {srid, lines: vec![line.clone(), line.clone()]}),
+                    GeometryT::MultiPolygon(MultiPolygonT {srid, polygons: vec![poly.clone()]}),
+                    GeometryT::GeometryCollection(GeometryCollectionT {srid, geometries: vec![
+                        GeometryT::Point(p(1.0, 2.0)), GeometryT::Polygon(poly.clone())]}),
+                ];
+                for (i, geom) in geoms.iter().enumerate() {
+                    let ewkb = geom.as_ewkb().to_ewkb_bytes();
+                    let type_id = u32::from_le_bytes([ewkb[1], ewkb[2], ewkb[3], ewkb[4]]);
+                    let srid_flag = if srid.is_some() { 0x20000000 } else { 0 };
+                    assert_eq!(type_id, $flags | srid_flag | (i as u32 + 1));
+                    let read = GeometryT::<$ptype>::read_ewkb(&mut ewkb.as_slice()).unwrap();
+                    assert_eq!(read.coord_type(), PointType::$ptype);
+                    assert_eq!(read.as_ewkb().to_ewkb_bytes(), ewkb, "{:?}", geom);
+                }
+            }
+        };
+    }
+    check_roundtrip!(Point, 0);
+    check_roundtrip!(PointZ, 0x80000000);
+    check_roundtrip!(PointM, 0x40000000);
+    check_roundtrip!(PointZM, 0xC0000000);
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_empty_roundtrip() {
+    macro_rules! check_empty {
+        ($geotype:ty, $ptype:ty, $hex:expr) => {
+            let ewkb = hex_to_vec($hex);
+            let geom = <$geotype>::read_ewkb(&mut ewkb.as_slice()).unwrap();
+            assert_eq!(geom.as_ewkb().to_ewkb_bytes(), ewkb, "{:?}", geom);
+            let geom = GeometryT::<$ptype>::read_ewkb(&mut ewkb.as_slice()).unwrap();
+            assert_eq!(geom.as_ewkb().to_ewkb_bytes(), ewkb, "{:?}", geom);
+        };
+    }
+    // SELECT ST_AsEWKB('POINT EMPTY'::geometry)
+    check_empty!(Point, Point, "0101000000000000000000F87F000000000000F87F");
+    // SELECT ST_AsEWKB('SRID=4326;POINT Z EMPTY'::geometry)
+    check_empty!(PointZ, PointZ, "01010000A0E6100000000000000000F87F000000000000F87F000000000000F87F");
+    // SELECT ST_AsEWKB('POINT M EMPTY'::geometry)
+    check_empty!(PointM, PointM, "0101000040000000000000F87F000000000000F87F000000000000F87F");
+    // SELECT ST_AsEWKB('POINT ZM EMPTY'::geometry)
+    check_empty!(PointZM, PointZM, "01010000C0000000000000F87F000000000000F87F000000000000F87F000000000000F87F");
+    // SELECT ST_AsEWKB('LINESTRING EMPTY'::geometry)
+    check_empty!(LineString, Point, "010200000000000000");
+    // SELECT ST_AsEWKB('POLYGON EMPTY'::geometry)
+    check_empty!(Polygon, Point, "010300000000000000");
+    // SELECT ST_AsEWKB('SRID=4326;POLYGON M EMPTY'::geometry)
+    check_empty!(PolygonM, PointM, "0103000060E610000000000000");
+    // SELECT ST_AsEWKB('MULTIPOINT EMPTY'::geometry)
+    check_empty!(MultiPoint, Point, "010400000000000000");
+    // SELECT ST_AsEWKB('MULTILINESTRING EMPTY'::geometry)
+    check_empty!(MultiLineString, Point, "010500000000000000");
+    // SELECT ST_AsEWKB('MULTIPOLYGON EMPTY'::geometry)
+    check_empty!(MultiPolygon, Point, "010600000000000000");
+    // SELECT ST_AsEWKB('SRID=4326;MULTIPOLYGON ZM EMPTY'::geometry)
+    check_empty!(MultiPolygonZM, PointZM, "01060000E0E610000000000000");
+    // SELECT ST_AsEWKB('GEOMETRYCOLLECTION EMPTY'::geometry)
+    check_empty!(GeometryCollection, Point, "010700000000000000");
+    // SELECT ST_AsEWKB('CIRCULARSTRING EMPTY'::geometry)
+    check_empty!(CircularString, Point, "010800000000000000");
+    // SELECT ST_AsEWKB('COMPOUNDCURVE EMPTY'::geometry)
+    check_empty!(CompoundCurve, Point, "010900000000000000");
+    // SELECT ST_AsEWKB('CURVEPOLYGON EMPTY'::geometry)
+    check_empty!(CurvePolygon, Point, "010A00000000000000");
+
+    // Empty sub-geometries
+    // SELECT ST_AsEWKB('GEOMETRYCOLLECTION (POLYGON EMPTY, MULTIPOINT EMPTY)'::geometry)
+    check_empty!(GeometryCollection, Point, "010700000002000000010300000000000000010400000000000000");
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_empty_point_dimensions() {
+    // SELECT ST_AsEWKB('POINT EMPTY'::geometry), read with more dimensions than stored
+    let ewkb = hex_to_vec("0101000000000000000000F87F000000000000F87F");
+    let point = PointZ::read_ewkb(&mut ewkb.as_slice()).unwrap();
+    assert!(point.x.is_nan() && point.y.is_nan() && point.z.is_nan());
+    assert!(PointM::read_ewkb(&mut ewkb.as_slice()).unwrap().m.is_nan());
+    let point = PointZM::read_ewkb(&mut ewkb.as_slice()).unwrap();
+    assert!(point.z.is_nan() && point.m.is_nan());
+    assert_eq!(point.as_ewkb().to_hex_ewkb(), "01010000C0000000000000F87F000000000000F87F000000000000F87F000000000000F87F");
+
+    // SELECT ST_AsEWKB('MULTIPOINT (EMPTY, 1 2)'::geometry)
+    let ewkb = hex_to_vec("0104000000020000000101000000000000000000F87F000000000000F87F0101000000000000000000F03F0000000000000040");
+    let multi = MultiPointT::<PointM>::read_ewkb(&mut ewkb.as_slice()).unwrap();
+    assert!(multi.points[0].m.is_nan());
+    assert_eq!(multi.points[1], PointM::new(1., 2., 0., None));
+
+    // Only points with both X and Y NaN are empty
+    assert_eq!(PointZ::new_from_opt_vals(f64::NAN, 1., None, None, None).z, 0.);
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_points_as_ref() {
+    fn first_x<P: postgis::Point, C: AsRef<[P]>>(container: &C) -> Option<f64> {
+        container.as_ref().first().map(|p| p.x())
+    }
+    let p = |x, y| Point::new(x, y, None);
+    let mut line = LineStringT::<Point> {srid: None, points: vec![p(1., 2.), p(3., 4.)]};
+    assert_eq!(first_x(&line), Some(1.));
+    line.as_mut().reverse();
+    assert_eq!(line.as_ref(), &[p(3., 4.), p(1., 2.)]);
+    let multi = MultiPointT::<Point> {srid: None, points: vec![p(5., 6.)]};
+    assert_eq!(first_x(&multi), Some(5.));
+    assert_eq!(first_x(&CircularStringT::<Point>::new()), None);
+}
+
 #[test]
 #[rustfmt::skip]
 fn test_multiline_read() {
@@ -355,6 +1060,54 @@ fn test_geometrycollection_read() {
     }
 }
 
+#[test]
+#[rustfmt::skip]
+fn test_curve_read() {
+    let p = |x, y| Point::new(x, y, None);
+    // SELECT 'CIRCULARSTRING(0 0, 1 1, 2 0)'::geometry
+    let circ_hex = "01080000000300000000000000000000000000000000000000000000000000F03F000000000000F03F00000000000000400000000000000000";
+    let ewkb = hex_to_vec(circ_hex);
+    let circ = CircularStringT::<Point>::read_ewkb(&mut ewkb.as_slice()).unwrap();
+    assert_eq!(circ.points, vec![p(0., 0.), p(1., 1.), p(2., 0.)]);
+    assert_eq!(circ.as_ewkb().to_hex_ewkb(), circ_hex);
+
+    // SELECT 'SRID=4326;COMPOUNDCURVE(CIRCULARSTRING(0 0, 1 1, 2 0), (2 0, 3 0))'::geometry
+    let hex = "0109000020E61000000200000001080000000300000000000000000000000000000000000000000000000000F03F000000000000F03F000000000000004000000000000000000102000000020000000000000000000040000000000000000000000000000008400000000000000000";
+    let ewkb = hex_to_vec(hex);
+    let curve = CompoundCurveT::<Point>::read_ewkb(&mut ewkb.as_slice()).unwrap();
+    assert_eq!(curve, CompoundCurveT {srid: Some(4326), segments: vec![
+        CurveSegment::CircularString(circ.clone()),
+        CurveSegment::LineString(LineStringT {srid: None, points: vec![p(2., 0.), p(3., 0.)]}),
+    ]});
+    assert_eq!(curve.as_ewkb().to_hex_ewkb(), hex);
+    assert_eq!(curve.as_ewkb().ewkb_size_hint(), ewkb.len());
+
+    // SELECT 'CURVEPOLYGON(CIRCULARSTRING(0 0, 4 0, 4 4, 0 4, 0 0), (1 1, 3 3, 3 1, 1 1))'::geometry
+    let hex = "010A000000020000000108000000050000000000000000000000000000000000000000000000000010400000000000000000000000000000104000000000000010400000000000000000000000000000104000000000000000000000000000000000010200000004000000000000000000F03F000000000000F03F000000000000084000000000000008400000000000000840000000000000F03F000000000000F03F000000000000F03F";
+    let ewkb = hex_to_vec(hex);
+    let geom = GeometryT::<Point>::read_ewkb(&mut ewkb.as_slice()).unwrap();
+    match &geom {
+        GeometryT::CurvePolygon(poly) => {
+            assert_eq!(poly.rings.len(), 2);
+            assert!(matches!(&poly.rings[0], Curve::CircularString(ring) if ring.points.len() == 5));
+            assert!(matches!(&poly.rings[1], Curve::LineString(ring) if ring.points[1] == p(3., 3.)));
+        }
+        _ => panic!("wrong geometry type"),
+    }
+    assert_eq!(geom.as_ewkb().to_hex_ewkb(), hex);
+
+    // Curves nested in a collection
+    let collection = GeometryCollectionT::<Point> {srid: None, geometries: vec![geom, GeometryT::CircularString(circ)]};
+    let ewkb = collection.as_ewkb().to_ewkb_bytes();
+    assert_eq!(collection.as_ewkb().ewkb_size_hint(), ewkb.len());
+    let read = GeometryCollectionT::<Point>::read_ewkb(&mut ewkb.as_slice()).unwrap();
+    assert!(read.geom_eq(&collection));
+
+    // Multi curves are not supported yet
+    let ewkb = hex_to_vec("010B00000000000000");
+    assert!(GeometryT::<Point>::read_ewkb(&mut ewkb.as_slice()).is_err());
+}
+
 #[test]
 #[rustfmt::skip]
 fn test_geometry_read() {
@@ -526,6 +1279,112 @@ fn test_geometry_read() {
     };
 }
 
+#[test]
+#[rustfmt::skip]
+fn test_ewkb_and_wkb_read() {
+    // Remove the SRID from the header of an EWKB geometry
+    fn strip_srid(ewkb: &[u8]) -> Vec<u8> {
+        let type_id = u32::from_le_bytes([ewkb[1], ewkb[2], ewkb[3], ewkb[4]]) & !0x20000000;
+        [&ewkb[..1], &type_id.to_le_bytes()[..], &ewkb[9..]].concat()
+    }
+    macro_rules! check_fixtures {
+        ($ptype:ident, $fixtures:expr) => {
+            for (ewkb_hex, wkb_hex) in $fixtures {
+                let ewkb = hex_to_vec(ewkb_hex);
+                let geom = GeometryT::<$ptype>::read_ewkb(&mut ewkb.as_slice()).unwrap();
+                assert_eq!(geom.as_ewkb().to_ewkb_bytes(), ewkb);
+                let wkb = hex_to_vec(wkb_hex);
+                let geom = GeometryT::<$ptype>::from_wkb(&wkb).unwrap();
+                assert_eq!(geom.as_ewkb().opt_srid(), None);
+                assert_eq!(geom.as_ewkb().to_ewkb_bytes(), strip_srid(&ewkb), "{}", wkb_hex);
+            }
+        };
+    }
+    // SELECT ST_AsEWKB(ST_SetSRID(geom, 4326)), ST_AsBinary(geom)
+    check_fixtures!(Point, [
+        // POINT (10 -20)
+        ("0101000020E6100000000000000000244000000000000034C0",
+         "0101000000000000000000244000000000000034C0"),
+        // LINESTRING (10 -20, 0 -0.5)
+        ("0102000020E610000002000000000000000000244000000000000034C00000000000000000000000000000E0BF",
+         "010200000002000000000000000000244000000000000034C00000000000000000000000000000E0BF"),
+        // POLYGON ((0 0, 2 0, 2 2, 0 0))
+        ("0103000020E6100000010000000400000000000000000000000000000000000000000000000000004000000000000000000000000000000040000000000000004000000000000000000000000000000000",
+         "0103000000010000000400000000000000000000000000000000000000000000000000004000000000000000000000000000000040000000000000004000000000000000000000000000000000"),
+        // MULTIPOINT ((10 -20), (0 -0.5))
+        ("0104000020E6100000020000000101000000000000000000244000000000000034C001010000000000000000000000000000000000E0BF",
+         "0104000000020000000101000000000000000000244000000000000034C001010000000000000000000000000000000000E0BF"),
+        // MULTILINESTRING ((10 -20, 0 -0.5))
+        ("0105000020E610000001000000010200000002000000000000000000244000000000000034C00000000000000000000000000000E0BF",
+         "010500000001000000010200000002000000000000000000244000000000000034C00000000000000000000000000000E0BF"),
+        // MULTIPOLYGON (((0 0, 2 0, 2 2, 0 0)))
+        ("0106000020E6100000010000000103000000010000000400000000000000000000000000000000000000000000000000004000000000000000000000000000000040000000000000004000000000000000000000000000000000",
+         "0106000000010000000103000000010000000400000000000000000000000000000000000000000000000000004000000000000000000000000000000040000000000000004000000000000000000000000000000000"),
+        // GEOMETRYCOLLECTION (POINT (10 -20), LINESTRING (10 -20, 0 -0.5))
+        ("0107000020E6100000020000000101000000000000000000244000000000000034C0010200000002000000000000000000244000000000000034C00000000000000000000000000000E0BF",
+         "0107000000020000000101000000000000000000244000000000000034C0010200000002000000000000000000244000000000000034C00000000000000000000000000000E0BF"),
+    ]);
+    // ST_AsBinary writes ISO WKB type codes for 3D geometries
+    check_fixtures!(PointZ, [
+        // POINT Z (10 -20 7)
+        ("01010000A0E6100000000000000000244000000000000034C00000000000001C40",
+         "01E9030000000000000000244000000000000034C00000000000001C40"),
+        // LINESTRING Z (10 -20 7, 0 -0.5 7)
+        ("01020000A0E610000002000000000000000000244000000000000034C00000000000001C400000000000000000000000000000E0BF0000000000001C40",
+         "01EA03000002000000000000000000244000000000000034C00000000000001C400000000000000000000000000000E0BF0000000000001C40"),
+        // POLYGON Z ((0 0 7, 2 0 7, 2 2 7, 0 0 7))
+        ("01030000A0E61000000100000004000000000000000000000000000000000000000000000000001C40000000000000004000000000000000000000000000001C40000000000000004000000000000000400000000000001C40000000000000000000000000000000000000000000001C40",
+         "01EB0300000100000004000000000000000000000000000000000000000000000000001C40000000000000004000000000000000000000000000001C40000000000000004000000000000000400000000000001C40000000000000000000000000000000000000000000001C40"),
+        // MULTIPOINT Z ((10 -20 7), (0 -0.5 7))
+        ("01040000A0E6100000020000000101000080000000000000244000000000000034C00000000000001C4001010000800000000000000000000000000000E0BF0000000000001C40",
+         "01EC0300000200000001E9030000000000000000244000000000000034C00000000000001C4001E90300000000000000000000000000000000E0BF0000000000001C40"),
+        // MULTILINESTRING Z ((10 -20 7, 0 -0.5 7))
+        ("01050000A0E610000001000000010200008002000000000000000000244000000000000034C00000000000001C400000000000000000000000000000E0BF0000000000001C40",
+         "01ED0300000100000001EA03000002000000000000000000244000000000000034C00000000000001C400000000000000000000000000000E0BF0000000000001C40"),
+        // MULTIPOLYGON Z (((0 0 7, 2 0 7, 2 2 7, 0 0 7)))
+        ("01060000A0E61000000100000001030000800100000004000000000000000000000000000000000000000000000000001C40000000000000004000000000000000000000000000001C40000000000000004000000000000000400000000000001C40000000000000000000000000000000000000000000001C40",
+         "01EE0300000100000001EB0300000100000004000000000000000000000000000000000000000000000000001C40000000000000004000000000000000000000000000001C40000000000000004000000000000000400000000000001C40000000000000000000000000000000000000000000001C40"),
+        // GEOMETRYCOLLECTION Z (POINT Z (10 -20 7), LINESTRING Z (10 -20 7, 0 -0.5 7))
+        ("01070000A0E6100000020000000101000080000000000000244000000000000034C00000000000001C40010200008002000000000000000000244000000000000034C00000000000001C400000000000000000000000000000E0BF0000000000001C40",
+         "01EF0300000200000001E9030000000000000000244000000000000034C00000000000001C4001EA03000002000000000000000000244000000000000034C00000000000001C400000000000000000000000000000E0BF0000000000001C40"),
+    ]);
+
+    let ewkb = hex_to_vec("0101000000000000000000244000000000000034C0");
+    assert_eq!(Point::read_ewkb(&mut ewkb.as_slice()).unwrap(), Point::new(10.0, -20.0, None));
+    // ISO WKB POINT ZM (10 -20 100 1)
+    let wkb = hex_to_vec("01B90B0000000000000000244000000000000034C00000000000005940000000000000F03F");
+    let point = PointZM::read_ewkb(&mut wkb.as_slice()).unwrap();
+    assert_eq!(point, PointZM { x: 10.0, y: -20.0, z: 100.0, m: 1.0, srid: None });
+    // ISO WKB POINT M (10 -20 1)
+    let wkb = hex_to_vec("01D1070000000000000000244000000000000034C0000000000000F03F");
+    let point = PointM::read_ewkb(&mut wkb.as_slice()).unwrap();
+    assert_eq!(point, PointM { x: 10.0, y: -20.0, m: 1.0, srid: None });
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_from_wkb() {
+    let expected = PointZM { x: 10.0, y: -20.0, z: 100.0, m: 1.0, srid: None };
+    // POINT ZM (10 -20 100 1) as EWKB and as ISO WKB
+    for hex in ["01010000C0000000000000244000000000000034C00000000000005940000000000000F03F",
+                "01B90B0000000000000000244000000000000034C00000000000005940000000000000F03F",
+                // EWKB Z flag with ISO M offset
+                "01D1070080000000000000244000000000000034C00000000000005940000000000000F03F"] {
+        match GeometryT::<PointZM>::from_wkb(&hex_to_vec(hex)).unwrap() {
+            GeometryT::Point(point) => assert_eq!(point, expected),
+            _ => panic!("wrong geometry type"),
+        }
+    }
+    // ISO WKB LINESTRING Z (10 -20 7, 0 -0.5 7) read into a 2D line
+    let wkb = hex_to_vec("01EA03000002000000000000000000244000000000000034C00000000000001C400000000000000000000000000000E0BF0000000000001C40");
+    match GeometryT::<Point>::from_wkb(&wkb).unwrap() {
+        GeometryT::LineString(line) => assert_eq!(line.points, vec![Point::new(10.0, -20.0, None), Point::new(0.0, -0.5, None)]),
+        _ => panic!("wrong geometry type"),
+    }
+    // Unknown ISO code 4001
+    assert!(GeometryT::<Point>::from_wkb(&hex_to_vec("01A10F0000000000000000244000000000000034C0")).is_err());
+}
+
 #[test]
 #[rustfmt::skip]
 fn test_read_error() {
@@ -533,6 +1392,45 @@ fn test_read_error() {
     let ewkb = hex_to_vec("010200000002000000000000000000244000000000000034C00000000000000000000000000000E0BF");
     let poly = PolygonT::<Point>::read_ewkb(&mut ewkb.as_slice());
     assert!(poly.is_err()); // UnexpectedEof "failed to fill whole buffer"
+
+    // Truncated within point 14 of the third polygon
+    let p = |x, y| Point::new(x, y, None);
+    let ring = LineStringT::<Point> {srid: None, points: (0..20).map(|i| p(i as f64, 0.)).collect()};
+    let poly = PolygonT::<Point> {srid: None, rings: vec![ring]};
+    let multipoly = MultiPolygonT::<Point> {srid: Some(4326), polygons: vec![poly.clone(), poly.clone(), poly]};
+    let ewkb = multipoly.as_ewkb().to_ewkb_bytes();
+    let truncated = &ewkb[..ewkb.len() - 6 * 16 + 8];
+    let err = MultiPolygonT::<Point>::read_ewkb(&mut &truncated[..]).unwrap_err();
+    match err {
+        Error::Read(msg) => assert!(msg.ends_with(" at MultiPolygon.polygons[2].rings[0].points[14]"), "{}", msg),
+        _ => panic!("unexpected error {:?}", err),
+    }
+
+    // Context of nested collections
+    let collection = GeometryCollectionT::<Point> {srid: None, geometries: vec![
+        GeometryT::Point(p(0., 0.)), GeometryT::MultiPolygon(multipoly)]};
+    let ewkb = collection.as_ewkb().to_ewkb_bytes();
+    let err = GeometryT::<Point>::read_ewkb(&mut &ewkb[..ewkb.len() - 1]).unwrap_err();
+    assert!(err.to_string().contains(" at GeometryCollection.geometries[1].polygons[2].rings[0].points[19]"), "{}", err);
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_read_ewkb_trim() {
+    // SELECT 'POINT(10 -20)'::geometry
+    let mut ewkb = hex_to_vec("0101000000000000000000244000000000000034C0");
+    assert_eq!(Point::read_ewkb_trim(&ewkb).unwrap(), Point::new(10.0, -20.0, None));
+    ewkb.push(0);
+    assert_eq!(Point::read_ewkb_trim(&ewkb).unwrap(), Point::new(10.0, -20.0, None));
+    ewkb.extend_from_slice(&[0; MAX_TRAILING_PADDING - 1]);
+    assert!(Point::read_ewkb_trim(&ewkb).is_ok());
+    ewkb.push(0);
+    assert!(Point::read_ewkb_trim(&ewkb).is_err());
+
+    let mut ewkb = hex_to_vec("0101000000000000000000244000000000000034C0");
+    ewkb.push(1);
+    assert!(Point::read_ewkb_trim(&ewkb).is_err());
+    assert!(Point::read_ewkb_trim(&ewkb[..10]).is_err());
 }
 
 #[test]
@@ -547,6 +1445,393 @@ fn test_iterators() {
     assert_eq!(last_point.x(), 0.);
     assert_eq!(last_point.y(), -0.5);
     assert_eq!(last_point.srid, None);
+
+    let ring = |x: f64| LineStringT::<Point> {srid: None, points: vec![p(x, 0.), p(x, 1.), p(x, 0.)]};
+    let multi = MultiPolygonT::<Point> {srid: None, polygons: vec![
+        PolygonT {srid: None, rings: vec![ring(0.), ring(1.)]},
+        PolygonT {srid: None, rings: vec![]},
+        PolygonT {srid: None, rings: vec![ring(2.)]},
+    ]};
+    let indexed: Vec<_> = multi.indexed_points().map(|(i, j, p)| (i, j, p.x(), p.y())).collect();
+    assert_eq!(indexed.len(), 9);
+    assert_eq!(indexed[..4], [(0, 0, 0., 0.), (0, 0, 0., 1.), (0, 0, 0., 0.), (0, 1, 1., 0.)]);
+    assert_eq!(indexed[8], (2, 0, 2., 0.));
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_line_end_points() {
+    let p = |x, y| Point::new(x, y, None);
+    let line = LineStringT::<Point> {srid: Some(4326), points: vec![p(10.0, -20.0), p(0., -0.5)]};
+    assert_eq!(line.start_point(), Some(&p(10.0, -20.0)));
+    assert_eq!(line.end_point(), Some(&p(0., -0.5)));
+    assert!(!line.is_ring());
+    let ring = LineStringT::<Point> {srid: None, points: vec![p(0., 0.), p(2., 0.), p(2., 2.), Point::new(0., 0., Some(4326))]};
+    assert!(ring.is_ring());
+    let empty = LineStringT::<Point>::new();
+    assert_eq!(empty.start_point(), None);
+    assert_eq!(empty.end_point(), None);
+    assert!(!empty.is_ring());
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_coord_type() {
+    assert_eq!(PointType::Point.num_coords(), 2);
+    assert!(!PointType::Point.has_z() && !PointType::Point.has_m());
+    assert!(PointType::PointZ.has_z() && !PointType::PointZ.has_m());
+    assert!(!PointType::PointM.has_z() && PointType::PointM.has_m());
+    assert_eq!(PointType::PointZM.num_coords(), 4);
+
+    // SELECT 'POINT(10 -20 100 1)'::geometry
+    let ewkb = hex_to_vec("01010000C0000000000000244000000000000034C00000000000005940000000000000F03F");
+    let geom = GeometryT::<PointZM>::read_ewkb(&mut ewkb.as_slice()).unwrap();
+    assert_eq!(geom.coord_type(), PointType::PointZM);
+    assert_eq!(GeometryT::Point(Point::new(1., 2., None)).coord_type(), PointType::Point);
+
+    assert_eq!(PointType::from_type_id(0x01), PointType::Point);
+    assert_eq!(PointType::from_type_id(0xA0000002), PointType::PointZ);
+    assert_eq!(PointType::from_type_id(0x40000006), PointType::PointM);
+    assert_eq!(PointType::from_type_id(u32::from_le_bytes(ewkb[1..5].try_into().unwrap())), PointType::PointZM);
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_point_dimension_conversion() {
+    let zm = PointZM::new(1., 2., 3., 4., Some(4326));
+    assert_eq!(zm.to_m(), PointM::new(1., 2., 4., Some(4326)));
+    assert_eq!(zm.to_z(), PointZ::new(1., 2., 3., Some(4326)));
+    assert_eq!(PointM::new(1., 2., 4., Some(4326)).to_zm(3.), zm);
+    assert_eq!(PointZ::new(1., 2., 3., Some(4326)).to_zm(4.), zm);
+
+    let line = LineStringM {srid: Some(4326), points: vec![PointM::new(1., 2., 4., Some(4326)), PointM::new(5., 6., 7., None)]};
+    let line_zm = line.to_zm(0.);
+    assert_eq!(line_zm.srid, Some(4326));
+    assert_eq!(line_zm.points[1], PointZM::new(5., 6., 0., 7., None));
+    assert_eq!(line_zm.to_m(), line);
+    assert_eq!(line_zm.to_z().to_zm(9.).points[0], PointZM::new(1., 2., 0., 9., Some(4326)));
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_point_tuples() {
+    assert_eq!(Point::new(1., 2., Some(4326)).to_tuple(), (1., 2.));
+    assert_eq!(Point::from_tuple((1., 2.)), Point::new(1., 2., None));
+    assert_eq!(PointZ::from_tuple((1., 2., 3.)).to_tuple(), (1., 2., 3.));
+    assert_eq!(PointM::from_tuple((1., 2., 4.)), PointM::new(1., 2., 4., None));
+    assert_eq!(PointZM::new(1., 2., 3., 4., Some(4326)).to_tuple(), (1., 2., 3., 4.));
+    let (x, y, z, m): (f64, f64, f64, f64) = PointZM::from((1., 2., 3., 4.)).into();
+    assert_eq!((x, y, z, m), (1., 2., 3., 4.));
+    let xy: (f64, f64) = Point::from((1., 2.)).into();
+    assert_eq!(xy, (1., 2.));
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_from_coord_str() {
+    assert_eq!(Point::from_coord_str("10,-20").unwrap(), Point::new(10.0, -20.0, None));
+    assert_eq!(Point::from_coord_str(" 10, -20 ").unwrap(), Point::new(10.0, -20.0, None));
+    assert_eq!(PointZ::from_coord_str("10 -20 100").unwrap(), PointZ::new(10.0, -20.0, 100.0, None));
+    assert_eq!(PointM::from_coord_str("10\t-20,1e2").unwrap(), PointM::new(10.0, -20.0, 100.0, None));
+    assert_eq!(PointZM::from_coord_str("1 2 3 4").unwrap(), PointZM::new(1., 2., 3., 4., None));
+
+    let err = PointZ::from_coord_str("10,-20").unwrap_err();
+    assert!(matches!(err, Error::Other(msg) if msg == "expected 3 coordinates, found 2 in \"10,-20\""));
+    assert!(Point::from_coord_str("10,abc").is_err());
+    assert!(Point::from_coord_str("").is_err());
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_mixed_dimension_collection() {
+    // GEOMETRYCOLLECTION (POINT (1 2), LINESTRING Z (0 0 5, 1 1 6)), not produced by PostGIS
+    let ewkb = hex_to_vec("0107000000020000000101000000000000000000F03F0000000000000040010200008002000000000000000000000000000000000000000000000000001440000000000000F03F000000000000F03F0000000000001840");
+    match DynGeometry::read_ewkb(&mut ewkb.as_slice()).unwrap() {
+        DynGeometry::Xy(GeometryT::GeometryCollection(_)) => (),
+        geom => panic!("unexpected {:?}", geom),
+    }
+    let geom = DynGeometry::read_ewkb_widest(&ewkb).unwrap();
+    assert_eq!(geom.dimension(), PointType::PointZ);
+    match geom {
+        DynGeometry::Xyz(GeometryT::GeometryCollection(collection)) => {
+            assert!(matches!(collection.geometries[0], GeometryT::Point(p) if p == PointZ::new(1., 2., 0., None)));
+            match &collection.geometries[1] {
+                GeometryT::LineString(line) => assert_eq!(line.points[1], PointZ::new(1., 1., 6., None)),
+                geom => panic!("unexpected {:?}", geom),
+            }
+        }
+        geom => panic!("unexpected {:?}", geom),
+    }
+    assert!(GeometryCollectionT::<Point>::read_ewkb_strict(&mut ewkb.as_slice()).is_err());
+    assert!(DynGeometry::read_ewkb_widest(&ewkb[..40]).is_err());
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_dyn_geometry_cache() {
+    // SELECT 'SRID=4326;MULTIPOINT ((10 -20 100), (0 -0.5 101))'::geometry
+    let multi = hex_to_vec("01040000A0E6100000020000000101000080000000000000244000000000000034C0000000000000594001010000800000000000000000000000000000E0BF0000000000405940");
+    // SELECT 'LINESTRINGM (10 -20 1, 0 -0.5 2)'::geometry
+    let line = hex_to_vec("010200004002000000000000000000244000000000000034C0000000000000F03F0000000000000000000000000000E0BF0000000000000040");
+    let cache = [&multi, &line].map(|ewkb| DynGeometry::read_ewkb(&mut ewkb.as_slice()).unwrap());
+    assert_eq!(cache.iter().map(DynGeometry::dimension).collect::<Vec<_>>(), vec![PointType::PointZ, PointType::PointM]);
+    assert!(matches!(&cache[0], DynGeometry::Xyz(GeometryT::MultiPoint(m)) if m.points[1].z == 101.0 && m.srid == Some(4326)));
+    match &cache[1] {
+        DynGeometry::Xym(geom) => assert_eq!(geom.as_ewkb().to_ewkb_bytes(), line),
+        geom => panic!("unexpected {:?}", geom),
+    }
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_srid_zero() {
+    // POINT (10 -20) with the SRID flag set and SRID 0
+    let ewkb = hex_to_vec("010100002000000000000000000000244000000000000034C0");
+    let point = Point::read_ewkb(&mut ewkb.as_slice()).unwrap();
+    assert_eq!(point.srid, Some(0));
+    assert_eq!(peek_srid(&ewkb).unwrap(), Some(0));
+    assert_eq!(point.as_ewkb().to_ewkb_bytes(), ewkb);
+    assert_eq!(point.to_ewkt(), "SRID=0;POINT (10 -20)");
+    let geom = GeometryT::<Point>::read_ewkb(&mut ewkb.as_slice()).unwrap();
+    assert!(matches!(geom, GeometryT::Point(p) if p.srid == Some(0)));
+
+    // SELECT 'SRID=0;POINT (10 -20)'::geometry, without flag
+    let ewkb = hex_to_vec("0101000000000000000000244000000000000034C0");
+    let point = Point::read_ewkb(&mut ewkb.as_slice()).unwrap();
+    assert_eq!(point.srid, None);
+    assert_eq!(point.as_ewkb().to_ewkb_bytes(), ewkb);
+
+    // The SRID 0 of a container is propagated like any other SRID
+    let ewkb = hex_to_vec("010200002000000000020000000000000000002440000000000000000000000000000000000000000000000000");
+    let line = LineStringT::<Point>::read_ewkb(&mut ewkb.as_slice()).unwrap();
+    assert_eq!(line.srid, Some(0));
+    assert_eq!(line.points[0].srid, Some(0));
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_implausible_srid() {
+    // POINT (10 -20) in little endian with SRID 4326 written in big endian
+    let ewkb = hex_to_vec("0101000020000010E6000000000000244000000000000034C0");
+    assert_eq!(Point::read_ewkb(&mut ewkb.as_slice()).unwrap_err().to_string(),
+               r#"Read("implausible SRID value -435159040, possible endianness mismatch at Point")"#);
+    assert!(peek_srid(&ewkb).is_err());
+    // SRID 3857 in big endian
+    let ewkb = hex_to_vec("010200002000000F11000000000000000000000000");
+    assert_eq!(LineStringT::<Point>::read_ewkb(&mut ewkb.as_slice()).unwrap_err().to_string(),
+               r#"Read("implausible SRID value 286195712, possible endianness mismatch at LineString")"#);
+
+    // POINT (10 -20) with the SRID given in little endian hex
+    let point = |srid: &str| hex_to_vec(&format!("0101000020{}000000000000244000000000000034C0", srid));
+    assert_eq!(Point::read_ewkb(&mut point("3F420F00").as_slice()).unwrap().srid, Some(999_999));
+    assert!(Point::read_ewkb(&mut point("40420F00").as_slice()).is_err());
+    assert!(Point::read_ewkb(&mut point("FFFFFFFF").as_slice()).is_err());
+    assert_eq!(Point::new(10.0, -20.0, Some(999_999)).as_ewkb().to_ewkb_bytes(), point("3F420F00"));
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_max_nesting_depth() {
+    // `depth` geometry collections nested in each other, the innermost one empty
+    let nested = |depth: usize| {
+        let mut ewkb = hex_to_vec(&"010700000001000000".repeat(depth - 1));
+        ewkb.extend(hex_to_vec("010700000000000000"));
+        ewkb
+    };
+    let geom = GeometryT::<Point>::read_ewkb(&mut nested(MAX_NESTING_DEPTH).as_slice()).unwrap();
+    assert_eq!(geom.stats().depth, MAX_NESTING_DEPTH + 1);
+    let err = GeometryCollectionT::<Point>::read_ewkb(&mut nested(MAX_NESTING_DEPTH + 1).as_slice()).unwrap_err();
+    assert!(err.to_string().starts_with(r#"Read("max nesting depth exceeded at GeometryCollection.geometries[0].geometries[0]"#), "{}", err);
+
+    // Deep nesting fails instead of overflowing the stack
+    assert!(GeometryT::<Point>::read_ewkb(&mut nested(100_000).as_slice()).is_err());
+
+    assert!(GeometryT::<Point>::read_ewkb_with_max_depth(&mut nested(3).as_slice(), 3).is_ok());
+    assert!(GeometryT::<Point>::read_ewkb_with_max_depth(&mut nested(4).as_slice(), 3).is_err());
+    assert!(GeometryT::<Point>::read_ewkb_with_max_depth(&mut nested(1).as_slice(), 0).is_err());
+    // Multi geometries don't count as nesting
+    let multi = MultiPointT::<Point> {srid: None, points: vec![Point::new(1., 2., None)]}.as_ewkb().to_ewkb_bytes();
+    assert!(GeometryT::<Point>::read_ewkb_with_max_depth(&mut multi.as_slice(), 0).is_ok());
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_subgeometry_srid_read() {
+    // SRID=4326;GEOMETRYCOLLECTION (SRID=3857;POINT (1 2), SRID=3857;LINESTRING (0 0, 1 1), POINT (3 4)),
+    // with the non-standard SRID flag on the first two sub-geometries
+    let ewkb = hex_to_vec("0107000020E6100000030000000101000020110F0000000000000000F03F00000000000000400102000020110F00000200000000000000000000000000000000000000000000000000F03F000000000000F03F010100000000000000000008400000000000001040");
+    let collection = GeometryCollectionT::<Point>::read_ewkb(&mut ewkb.as_slice()).unwrap();
+    assert_eq!(collection.srid, Some(4326));
+    assert!(matches!(collection.geometries[0], GeometryT::Point(p) if p == Point::new(1., 2., Some(4326))));
+    match &collection.geometries[1] {
+        GeometryT::LineString(line) => {
+            assert_eq!(line.srid, Some(4326));
+            assert_eq!(line.points[1], Point::new(1., 1., Some(4326)));
+        }
+        geom => panic!("unexpected {:?}", geom),
+    }
+    assert!(matches!(collection.geometries[2], GeometryT::Point(p) if p == Point::new(3., 4., None)));
+
+    // Without SRID of the collection, the SRID of the sub-geometry is kept
+    let ewkb = hex_to_vec("0107000000010000000101000020110F0000000000000000F03F0000000000000040");
+    let collection = GeometryCollectionT::<Point>::read_ewkb(&mut ewkb.as_slice()).unwrap();
+    assert_eq!(collection.srid, None);
+    assert!(matches!(collection.geometries[0], GeometryT::Point(p) if p == Point::new(1., 2., Some(3857))));
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_read_bbox_flag() {
+    // SRID=4326;POINT (10 -20) with cached bbox
+    let ewkb = hex_to_vec("0101000030E610000000002041000020410000A0C10000A0C1000000000000244000000000000034C0");
+    let point = Point::read_ewkb(&mut ewkb.as_slice()).unwrap();
+    assert_eq!(point, Point::new(10.0, -20.0, Some(4326)));
+
+    // LINESTRING Z (1 2 3, 4 5 6) with cached 3D bbox
+    let ewkb = hex_to_vec("01020000900000803F00008040000000400000A040000040400000C04002000000000000000000F03F00000000000000400000000000000840000000000000104000000000000014400000000000001840");
+    let line = LineStringT::<PointZ>::read_ewkb(&mut ewkb.as_slice()).unwrap();
+    assert_eq!(line.points, vec![PointZ::new(1.0, 2.0, 3.0, None), PointZ::new(4.0, 5.0, 6.0, None)]);
+    assert_eq!(peek_geom_type(&ewkb).unwrap().kind, GeometryKind::LineString);
+
+    // Truncated bbox
+    assert!(Point::read_ewkb(&mut &ewkb[..12]).is_err());
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_read_reject_nonfinite() {
+    // POINT EMPTY
+    let ewkb = hex_to_vec("0101000000000000000000F87F000000000000F87F");
+    assert!(Point::read_ewkb_reject_nonfinite(&mut ewkb.as_slice()).unwrap().x().is_nan());
+
+    // LINESTRING (0 0, 1 Infinity)
+    let ewkb = hex_to_vec("01020000000200000000000000000000000000000000000000000000000000F03F000000000000F07F");
+    assert!(LineString::read_ewkb(&mut ewkb.as_slice()).is_ok());
+    let err = LineString::read_ewkb_reject_nonfinite(&mut ewkb.as_slice()).unwrap_err();
+    assert_eq!(err.to_string(), r#"Read("non-finite coordinate inf at LineString.points[1]")"#);
+
+    // All-NaN vertex of a line
+    let ewkb = hex_to_vec("01020000000200000000000000000000000000000000000000000000000000F87F000000000000F87F");
+    assert!(GeometryT::<Point>::read_ewkb_reject_nonfinite(&mut ewkb.as_slice()).is_err());
+
+    // MULTIPOINT (EMPTY, 1 NaN)
+    let ewkb = hex_to_vec("0104000000020000000101000000000000000000F87F000000000000F87F0101000000000000000000F03F000000000000F87F");
+    let err = MultiPoint::read_ewkb_reject_nonfinite(&mut ewkb.as_slice()).unwrap_err();
+    assert_eq!(err.to_string(), r#"Read("non-finite coordinate NaN at MultiPoint.points[1]")"#);
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_read_strict() {
+    // SELECT 'LINESTRING (10 -20, 0 -0.5)'::geometry
+    let ewkb = hex_to_vec("010200000002000000000000000000244000000000000034C00000000000000000000000000000E0BF");
+    let line = LineStringT::<Point>::read_ewkb_strict(&mut ewkb.as_slice()).unwrap();
+    assert_eq!(line.points.len(), 2);
+    assert_eq!(LineStringT::<PointZ>::read_ewkb(&mut ewkb.as_slice()).unwrap().points[0].z, 0.0);
+    let err = LineStringT::<PointZ>::read_ewkb_strict(&mut ewkb.as_slice()).unwrap_err();
+    assert_eq!(err.to_string(), r#"Read("expected PointZ coordinates, found Point at LineString")"#);
+
+    // Dimensions of sub-geometries are checked as well
+    // MultiPoint Z header containing a 2D point
+    let ewkb = hex_to_vec("0104000080010000000101000000000000000000244000000000000034C0");
+    let err = MultiPointT::<PointZ>::read_ewkb_strict(&mut ewkb.as_slice()).unwrap_err();
+    assert_eq!(err.to_string(), r#"Read("expected PointZ coordinates, found Point at MultiPoint.points[0]")"#);
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_collection_from_iter() {
+    let points = (0..3).map(|i| GeometryT::Point(Point::new(i as f64, 0.0, None)));
+    let mut collection: GeometryCollectionT<Point> = points.collect();
+    assert_eq!(collection.srid, None);
+    assert_eq!(collection.geometries.len(), 3);
+    collection.extend(vec![GeometryT::LineString(LineStringT::new())]);
+    assert_eq!(collection.geometries.len(), 4);
+    assert!(matches!(collection.geometries[2], GeometryT::Point(p) if p.x() == 2.0));
+    assert!(matches!(collection.geometries[3], GeometryT::LineString(_)));
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_mut_accessors() {
+    let p = |x, y| Point::new(x, y, None);
+    let mut line = LineStringT::<Point> {srid: None, points: vec![p(0.4, 1.6), p(2.2, -0.7)]};
+    line.points_mut().for_each(|p| *p = Point::new(p.x().round(), p.y().round(), p.srid));
+    assert_eq!(line.points, vec![p(0., 2.), p(2., -1.)]);
+
+    let mut poly = PolygonT::<Point> {srid: None, rings: vec![line.clone(), line]};
+    poly.rings_mut().for_each(|ring| ring.points_mut().for_each(|p| *p = Point::new(p.x() + 1., p.y(), None)));
+    assert_eq!(poly.rings[1].points, vec![p(1., 2.), p(3., -1.)]);
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_coord_buffer() {
+    let p = |x, y| Point::new(x, y, Some(4326));
+    let line = LineStringT::<Point> {srid: Some(4326), points: vec![p(10.0, -20.0), p(0., -0.5)]};
+    let buf = line.to_coord_buffer();
+    assert_eq!(buf, vec![10.0, -20.0, 0., -0.5]);
+    assert_eq!(LineStringT::<Point>::from_coord_buffer(&buf, 2, Some(4326)).unwrap(), line);
+
+    let line = LineStringT::<PointZM> {srid: None, points: vec![PointZM::new(1., 2., 3., 4., None)]};
+    assert_eq!(line.to_coord_buffer(), vec![1., 2., 3., 4.]);
+    assert_eq!(LineStringT::<PointZM>::from_coord_buffer(&[1., 2., 3., 4.], 4, None).unwrap(), line);
+
+    let line = LineStringT::<PointM>::from_coord_buffer(&[1., 2., 3., 4., 5., 6.], 3, None).unwrap();
+    assert_eq!(line.points, vec![PointM::new(1., 2., 3., None), PointM::new(4., 5., 6., None)]);
+    let line = LineStringT::<PointZ>::from_coord_buffer(&[1., 2., 3.], 3, None).unwrap();
+    assert_eq!(line.to_coord_buffer(), vec![1., 2., 3.]);
+    // Dropped and missing dimensions
+    let line = LineStringT::<Point>::from_coord_buffer(&[1., 2., 3.], 3, None).unwrap();
+    assert_eq!(line.to_coord_buffer(), vec![1., 2.]);
+    let line = LineStringT::<PointZ>::from_coord_buffer(&[1., 2.], 2, None).unwrap();
+    assert_eq!(line.to_coord_buffer(), vec![1., 2., 0.]);
+
+    assert!(matches!(LineStringT::<Point>::from_coord_buffer(&[1., 2., 3.], 2, None), Err(Error::Other(_))));
+    assert!(matches!(LineStringT::<Point>::from_coord_buffer(&[1., 2.], 1, None), Err(Error::Other(_))));
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_ewkb_geometry_owned() {
+    fn build() -> EwkbGeometryOwned<Point> {
+        let p = |x, y| Point::new(x, y, None);
+        let line = LineStringT::<Point> {srid: Some(4326), points: vec![p(10.0, -20.0), p(0., -0.5)]};
+        GeometryT::LineString(line).into_ewkb()
+    }
+    let owned = build();
+    let expected = owned.geom.as_ewkb().to_hex_ewkb();
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || tx.send(owned).unwrap());
+    let owned = rx.recv().unwrap();
+    assert_eq!(owned.to_hex_ewkb(), "0102000020E610000002000000000000000000244000000000000034C00000000000000000000000000000E0BF");
+    assert_eq!(owned.to_hex_ewkb(), expected);
+    assert_eq!(owned.ewkb_size_hint(), owned.to_ewkb_bytes().len());
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_coord_z() {
+    assert_eq!(std::mem::size_of::<CoordZ>(), 24);
+    let line = LineStringZ {srid: Some(4326), points: vec![PointZ::new(1., 2., 3., Some(4326)), PointZ::new(4., 5., 6., Some(4326))]};
+    let coords = line.to_coords();
+    assert_eq!(coords, vec![CoordZ {x: 1., y: 2., z: 3.}, CoordZ {x: 4., y: 5., z: 6.}]);
+    assert_eq!(PointZ::from(coords[1]), PointZ::new(4., 5., 6., None));
+}
+
+#[cfg(all(test, feature = "base64"))]
+#[test]
+#[rustfmt::skip]
+fn test_from_base64_ewkb() {
+    // SELECT encode(ST_AsEWKB('SRID=4326;POINT(10 -20)'::geometry), 'base64')
+    let point = Point::from_base64_ewkb("AQEAACDmEAAAAAAAAAAAJEAAAAAAAAA0wA==").unwrap();
+    assert_eq!(point, Point::new(10.0, -20.0, Some(4326)));
+    let geom = GeometryT::<Point>::from_base64_ewkb("AQEAACDmEAAAAAAAAAAAJEAAAAAAAAA0wA==\n").unwrap();
+    match geom {
+        GeometryT::Point(p) => assert_eq!(p, point),
+        _ => panic!("Geometry is not a Point"),
+    }
+    assert!(matches!(Point::from_base64_ewkb("not base64!"), Err(Error::Read(_))));
 }
 
 #[cfg(all(test, feature = "serde"))]
@@ -592,4 +1877,43 @@ mod serde_tests {
             _ => panic!("Deserialized to wrong variant"),
         }
     }
+
+    fn roundtrip<T: serde::Serialize + serde::de::DeserializeOwned>(geom: &T) -> T {
+        serde_json::from_str(&serde_json::to_string(geom).unwrap()).unwrap()
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn test_serde_containers() {
+        let p = |x, y| Point::new(x, y, Some(4326));
+        let line = LineStringT::<Point> {srid: Some(4326), points: vec![p(0., 0.), p(2., 0.), p(2., 2.), p(0., 0.)]};
+        assert_eq!(roundtrip(&line), line);
+        let poly = PolygonT::<Point> {srid: Some(4326), rings: vec![line.clone(), line.clone()]};
+        assert_eq!(roundtrip(&poly), poly);
+        let multi = MultiPolygonT::<Point> {srid: None, polygons: vec![poly.clone(), PolygonT::new()]};
+        assert_eq!(roundtrip(&multi), multi);
+
+        let collection = GeometryCollectionT::<Point> {srid: Some(4326), geometries: vec![
+            GeometryT::Point(p(1., 2.)), GeometryT::LineString(line.clone()), GeometryT::MultiPolygon(multi),
+            GeometryT::GeometryCollection(GeometryCollectionT::new()),
+        ]};
+        let deserialized = roundtrip(&collection);
+        assert!(deserialized.geom_eq(&collection));
+        assert_eq!(format!("{:?}", deserialized), format!("{:?}", collection));
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn test_serde_missing_srid() {
+        let point: PointZ = serde_json::from_str(r#"{"x": 1.0, "y": 2.0, "z": 3.0}"#).unwrap();
+        assert_eq!(point, PointZ::new(1., 2., 3., None));
+        let point: Point = serde_json::from_str(r#"{"y": 2.0, "x": 1.0}"#).unwrap();
+        assert_eq!(point, Point::new(1., 2., None));
+        let line: LineStringT<Point> = serde_json::from_str(r#"{"points": [{"x": 1.0, "y": 2.0}]}"#).unwrap();
+        assert_eq!(line, LineStringT {srid: None, points: vec![Point::new(1., 2., None)]});
+        let poly: PolygonT<Point> = serde_json::from_str(r#"{"rings": [{"points": []}]}"#).unwrap();
+        assert_eq!(poly, PolygonT {srid: None, rings: vec![LineStringT::new()]});
+        let collection: GeometryCollectionT<Point> = serde_json::from_str(r#"{"geometries": []}"#).unwrap();
+        assert_eq!(collection.srid, None);
+    }
 }
@@ -0,0 +1,123 @@
+//! Reading EWKB from `tokio` async readers.
+
+use crate::ewkb::*;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// Append exactly `len` bytes from `raw` to `buf`.
+///
+/// The buffer grows with the data read, so a corrupt length doesn't allocate up front.
+async fn copy_exact<R>(raw: &mut R, buf: &mut Vec<u8>, len: u64) -> Result<(), Error>
+where
+    R: AsyncRead + Unpin,
+{
+    let read = raw.take(len).read_to_end(buf).await?;
+    if (read as u64) < len {
+        return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+    }
+    Ok(())
+}
+
+/// Copy a `u32` from `raw` to `buf` and return its value.
+async fn copy_u32<R>(raw: &mut R, buf: &mut Vec<u8>, is_be: bool) -> Result<u32, Error>
+where
+    R: AsyncRead + Unpin,
+{
+    copy_exact(raw, buf, 4).await?;
+    read_u32(&mut &buf[buf.len() - 4..], is_be)
+}
+
+/// Read the bytes of one EWKB geometry from `raw` without reading past its end.
+///
+/// Only headers and counts are decoded to find the end of the geometry. Nested geometries
+/// are walked iteratively, so deep nesting doesn't grow the stack.
+pub(super) async fn read_ewkb_bytes<R>(raw: &mut R) -> Result<Vec<u8>, Error>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut buf = Vec::new();
+    // Number of geometries with their own header still to be read
+    let mut pending: u64 = 1;
+    while pending > 0 {
+        pending -= 1;
+        copy_exact(raw, &mut buf, 1).await?;
+        let is_be = buf[buf.len() - 1] == 0;
+        let type_id = normalize_type_id(copy_u32(raw, &mut buf, is_be).await?);
+        let point_size = PointType::from_type_id(type_id).num_coords() as u64 * 8;
+        if type_id & 0x20000000 == 0x20000000 {
+            copy_exact(raw, &mut buf, 4).await?;
+        }
+        if type_id & 0x10000000 == 0x10000000 {
+            copy_exact(raw, &mut buf, point_size).await?;
+        }
+        match type_id & 0xff {
+            0x01 => copy_exact(raw, &mut buf, point_size).await?,
+            0x02 | 0x08 => {
+                let points = copy_u32(raw, &mut buf, is_be).await? as u64;
+                copy_exact(raw, &mut buf, points * point_size).await?;
+            }
+            0x03 => {
+                let rings = copy_u32(raw, &mut buf, is_be).await?;
+                for _ in 0..rings {
+                    let points = copy_u32(raw, &mut buf, is_be).await? as u64;
+                    copy_exact(raw, &mut buf, points * point_size).await?;
+                }
+            }
+            0x04..=0x07 | 0x09 | 0x0a => {
+                pending += copy_u32(raw, &mut buf, is_be).await? as u64;
+            }
+            _ => {
+                return Err(Error::Read(format!(
+                    "Error reading generic geometry type - unsupported type id {}.",
+                    type_id
+                )));
+            }
+        }
+    }
+    Ok(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    #[rustfmt::skip]
+    async fn test_read_ewkb_async() {
+        let p = |x, y| Point::new(x, y, Some(4326));
+        let line = LineStringT::<Point> {srid: Some(4326), points: vec![p(0., 0.), p(2., 0.), p(2., 2.), p(0., 0.)]};
+        let collection = GeometryCollectionT::<Point> {srid: Some(4326), geometries: vec![
+            GeometryT::Point(p(10., -20.)),
+            GeometryT::Polygon(PolygonT {srid: Some(4326), rings: vec![line.clone(), line.clone()]}),
+            GeometryT::MultiLineString(MultiLineStringT {srid: Some(4326), lines: vec![line.clone()]}),
+            GeometryT::GeometryCollection(GeometryCollectionT::new()),
+        ]};
+        let ewkb = collection.as_ewkb().to_ewkb_bytes();
+
+        // Two geometries in a row, the first one must not consume the second
+        let mut stream = [ewkb.clone(), line.as_ewkb().to_ewkb_bytes()].concat();
+        stream.push(0xFF);
+        let mut rdr = stream.as_slice();
+        let read = GeometryCollectionT::<Point>::read_ewkb_async(&mut rdr).await.unwrap();
+        assert_eq!(read.as_ewkb().to_ewkb_bytes(), ewkb);
+        let read = LineStringT::<Point>::read_ewkb_async(&mut rdr).await.unwrap();
+        assert_eq!(read, line);
+        assert_eq!(rdr, &[0xFF]);
+
+        // SELECT ST_AsEWKB('COMPOUNDCURVE (CIRCULARSTRING (0 0, 1 1, 2 0), (2 0, 3 0))'::geometry, 'XDR')
+        let xdr = hex_to_vec("000000000900000002000000000800000003000000000000000000000000000000003FF00000000000003FF0000000000000400000000000000000000000000000000000000002000000024000000000000000000000000000000040080000000000000000000000000000");
+        let mut rdr = xdr.as_slice();
+        let curve = GeometryT::<Point>::read_ewkb_async(&mut rdr).await.unwrap();
+        assert!(matches!(curve, GeometryT::CompoundCurve(ref c) if c.segments.len() == 2));
+        assert!(rdr.is_empty());
+
+        // POINT Z (1 2 3) with the ISO type code 1001
+        let iso = hex_to_vec("01E9030000000000000000F03F00000000000000400000000000000840");
+        let point = PointZ::read_ewkb_async(&mut iso.as_slice()).await.unwrap();
+        assert_eq!((point.x, point.y, point.z), (1., 2., 3.));
+
+        assert!(GeometryCollectionT::<Point>::read_ewkb_async(&mut &ewkb[..ewkb.len() - 1]).await.is_err());
+        // A huge point count fails on the missing data
+        let mut rdr = &hex_to_vec("0102000000FFFFFFFF")[..];
+        assert!(LineStringT::<Point>::read_ewkb_async(&mut rdr).await.is_err());
+    }
+}
@@ -0,0 +1,224 @@
+//! Clipping to rectangular windows.
+
+use crate::ewkb::compare::coords_eq;
+use crate::ewkb::*;
+
+/// Point at parameter `t` of the segment a-b, with interpolated Z and M values.
+fn interpolate<P: PointCoords + Clone>(a: &P, b: &P, t: f64) -> P {
+    if t <= 0.0 {
+        return a.clone();
+    }
+    if t >= 1.0 {
+        return b.clone();
+    }
+    let lerp = |u: f64, v: f64| u + t * (v - u);
+    let z = a.opt_z().zip(b.opt_z()).map(|(u, v)| lerp(u, v));
+    let m = a.opt_m().zip(b.opt_m()).map(|(u, v)| lerp(u, v));
+    P::new_from_opt_vals(lerp(a.x(), b.x()), lerp(a.y(), b.y()), z, m, a.opt_srid())
+}
+
+/// Liang-Barsky clipping of the segment a-b, returns the parameter range inside the box.
+fn clip_segment<P: postgis::Point>(a: &P, b: &P, bbox: (f64, f64, f64, f64)) -> Option<(f64, f64)> {
+    let (min_x, min_y, max_x, max_y) = bbox;
+    let (dx, dy) = (b.x() - a.x(), b.y() - a.y());
+    let (mut t0, mut t1) = (0.0f64, 1.0f64);
+    for (p, q) in [
+        (-dx, a.x() - min_x),
+        (dx, max_x - a.x()),
+        (-dy, a.y() - min_y),
+        (dy, max_y - a.y()),
+    ] {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+        } else {
+            let r = q / p;
+            if p < 0.0 {
+                t0 = t0.max(r);
+            } else {
+                t1 = t1.min(r);
+            }
+        }
+    }
+    if t0 > t1 { None } else { Some((t0, t1)) }
+}
+
+/// Sutherland-Hodgman clipping of a ring against one edge of the box.
+fn clip_ring_edge<P: PointCoords + Clone>(
+    ring: &[P],
+    inside: impl Fn(&P) -> bool,
+    crossing: impl Fn(&P, &P) -> f64,
+) -> Vec<P> {
+    let mut out = Vec::with_capacity(ring.len() + 1);
+    for (i, b) in ring.iter().enumerate() {
+        let a = &ring[(i + ring.len() - 1) % ring.len()];
+        match (inside(a), inside(b)) {
+            (true, true) => out.push(b.clone()),
+            (true, false) => out.push(interpolate(a, b, crossing(a, b))),
+            (false, true) => {
+                out.push(interpolate(a, b, crossing(a, b)));
+                out.push(b.clone());
+            }
+            (false, false) => {}
+        }
+    }
+    out
+}
+
+fn clip_ring<P: PointCoords + Clone>(
+    ring: &LineStringT<P>,
+    bbox: (f64, f64, f64, f64),
+) -> Option<LineStringT<P>> {
+    let (min_x, min_y, max_x, max_y) = bbox;
+    let mut points = ring.points.as_slice();
+    if points.len() > 1
+        && points.first().map(|p| (p.x(), p.y())) == points.last().map(|p| (p.x(), p.y()))
+    {
+        points = &points[..points.len() - 1];
+    }
+    let mut points = points.to_vec();
+    let t = |c: f64, u: f64, v: f64| (c - u) / (v - u);
+    points = clip_ring_edge(&points, |p| p.x() >= min_x, |a, b| t(min_x, a.x(), b.x()));
+    points = clip_ring_edge(&points, |p| p.x() <= max_x, |a, b| t(max_x, a.x(), b.x()));
+    points = clip_ring_edge(&points, |p| p.y() >= min_y, |a, b| t(min_y, a.y(), b.y()));
+    points = clip_ring_edge(&points, |p| p.y() <= max_y, |a, b| t(max_y, a.y(), b.y()));
+    if points.len() < 3 {
+        return None;
+    }
+    points.push(points[0].clone());
+    Some(LineStringT {
+        points,
+        srid: ring.srid,
+    })
+}
+
+impl<P> LineStringT<P>
+where
+    P: PointCoords + Clone,
+{
+    /// Clip the line to a rectangle with the Liang-Barsky algorithm.
+    ///
+    /// Parts of the line outside the rectangle are dropped, so the result can consist of
+    /// several lines. New vertices are inserted where the line crosses the border, with
+    /// interpolated Z and M values. The SRID is preserved.
+    pub fn clip_to_bbox(
+        &self,
+        min_x: f64,
+        min_y: f64,
+        max_x: f64,
+        max_y: f64,
+    ) -> MultiLineStringT<P> {
+        let bbox = (min_x, min_y, max_x, max_y);
+        let mut lines = Vec::new();
+        let mut run: Vec<P> = Vec::new();
+        let flush = |run: &mut Vec<P>, lines: &mut Vec<LineStringT<P>>| {
+            // Runs of identical points, e.g. repeated points on the border, have no length
+            if run.iter().any(|p| !coords_eq(p, &run[0])) {
+                lines.push(LineStringT {
+                    points: std::mem::take(run),
+                    srid: self.srid,
+                });
+            }
+            run.clear();
+        };
+        for seg in self.points.windows(2) {
+            let (a, b) = (&seg[0], &seg[1]);
+            match clip_segment(a, b, bbox) {
+                Some((t0, t1)) => {
+                    if t0 > 0.0 || run.is_empty() {
+                        flush(&mut run, &mut lines);
+                        run.push(interpolate(a, b, t0));
+                    }
+                    // t0 == t1 if the segment only touches a corner of the box
+                    if t1 > t0 {
+                        run.push(interpolate(a, b, t1));
+                    }
+                    if t1 < 1.0 {
+                        flush(&mut run, &mut lines);
+                    }
+                }
+                None => flush(&mut run, &mut lines),
+            }
+        }
+        flush(&mut run, &mut lines);
+        MultiLineStringT {
+            lines,
+            srid: self.srid,
+        }
+    }
+}
+
+impl<P> PolygonT<P>
+where
+    P: PointCoords + Clone,
+{
+    /// Clip the polygon to a rectangle with the Sutherland-Hodgman algorithm.
+    ///
+    /// Rings lying completely outside the rectangle are removed, if the exterior ring is
+    /// removed the result has no rings. Concave polygons which are split into several parts
+    /// by the clipping are returned as one ring, connected by edges along the border of
+    /// the rectangle. The SRID is preserved.
+    pub fn clip_to_bbox(&self, min_x: f64, min_y: f64, max_x: f64, max_y: f64) -> PolygonT<P> {
+        let bbox = (min_x, min_y, max_x, max_y);
+        let mut rings = Vec::new();
+        for (i, ring) in self.rings.iter().enumerate() {
+            match clip_ring(ring, bbox) {
+                Some(ring) => rings.push(ring),
+                None if i == 0 => break,
+                None => {}
+            }
+        }
+        PolygonT {
+            rings,
+            srid: self.srid,
+        }
+    }
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_clip_line() {
+    let p = |x, y| Point::new(x, y, None);
+    let line = LineStringT::<Point> {srid: Some(3857), points: vec![p(-5., 5.), p(5., 5.), p(5., 15.), p(8., 15.), p(8., 5.), p(15., 5.)]};
+    let clipped = line.clip_to_bbox(0., 0., 10., 10.);
+    assert_eq!(clipped.srid, Some(3857));
+    assert_eq!(clipped.lines, vec![
+        LineStringT {srid: Some(3857), points: vec![p(0., 5.), p(5., 5.), p(5., 10.)]},
+        LineStringT {srid: Some(3857), points: vec![p(8., 10.), p(8., 5.), p(10., 5.)]},
+    ]);
+
+    // Inside and outside
+    assert_eq!(line.clip_to_bbox(-100., -100., 100., 100.).lines, vec![line.clone()]);
+    assert!(line.clip_to_bbox(20., 20., 30., 30.).lines.is_empty());
+
+    // Segments touching the box only at a corner
+    let line = LineStringT::<Point> {srid: None, points: vec![p(-5., 5.), p(5., -5.), p(5., 5.), p(15., 5.)]};
+    assert_eq!(line.clip_to_bbox(0., 0., 10., 10.).lines, vec![LineStringT {srid: None, points: vec![p(5., 0.), p(5., 5.), p(10., 5.)]}]);
+    let line = LineStringT::<Point> {srid: None, points: vec![p(-5., -5.), p(0., 0.), p(5., 5.)]};
+    assert_eq!(line.clip_to_bbox(0., 0., 10., 10.).lines, vec![LineStringT {srid: None, points: vec![p(0., 0.), p(5., 5.)]}]);
+    let line = LineStringT::<Point> {srid: None, points: vec![p(-5., 5.), p(5., -5.)]};
+    assert!(line.clip_to_bbox(0., 0., 10., 10.).lines.is_empty());
+    let line = LineStringT::<Point> {srid: None, points: vec![p(0., -5.), p(0., 0.), p(0., 0.), p(-5., 0.)]};
+    assert!(line.clip_to_bbox(0., 0., 10., 10.).lines.is_empty());
+
+    // Z and M are interpolated
+    let line = LineStringT::<PointZM> {srid: None, points: vec![PointZM::new(0., 0., 0., 10., None), PointZM::new(20., 0., 20., 30., None)]};
+    let clipped = line.clip_to_bbox(5., -1., 10., 1.);
+    assert_eq!(clipped.lines[0].points, vec![PointZM::new(5., 0., 5., 15., None), PointZM::new(10., 0., 10., 20., None)]);
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_clip_polygon() {
+    let p = |x, y| Point::new(x, y, None);
+    let square = |x0: f64, y0: f64, x1: f64, y1: f64| LineStringT::<Point> {srid: None, points: vec![p(x0, y0), p(x1, y0), p(x1, y1), p(x0, y1), p(x0, y0)]};
+    let poly = PolygonT::<Point> {srid: Some(4326), rings: vec![square(-5., -5., 5., 5.), square(-4., -4., -2., -2.), square(2., 2., 4., 4.)]};
+    let clipped = poly.clip_to_bbox(0., 0., 10., 10.);
+    assert_eq!(clipped.srid, Some(4326));
+    assert_eq!(clipped.rings.len(), 2);
+    assert_eq!(clipped.rings[0].points, vec![p(0., 0.), p(5., 0.), p(5., 5.), p(0., 5.), p(0., 0.)]);
+    assert_eq!(clipped.rings[1], square(2., 2., 4., 4.));
+
+    assert!(poly.clip_to_bbox(20., 20., 30., 30.).rings.is_empty());
+}
@@ -0,0 +1,218 @@
+//! Structural comparison ignoring the SRID of sub-geometries.
+
+use crate::ewkb::measure::ring_signed_area;
+use crate::ewkb::*;
+use std::cmp::Ordering;
+
+pub(super) fn coords_eq<P: postgis::Point>(a: &P, b: &P) -> bool {
+    a.x() == b.x() && a.y() == b.y() && a.opt_z() == b.opt_z() && a.opt_m() == b.opt_m()
+}
+
+fn points_eq<P: postgis::Point>(a: &[P], b: &[P]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| coords_eq(a, b))
+}
+
+fn lines_eq<P: PointCoords>(a: &[LineStringT<P>], b: &[LineStringT<P>]) -> bool {
+    a.len() == b.len()
+        && a.iter()
+            .zip(b)
+            .all(|(a, b)| points_eq(&a.points, &b.points))
+}
+
+fn polygons_eq<P: PointCoords>(a: &[PolygonT<P>], b: &[PolygonT<P>]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| lines_eq(&a.rings, &b.rings))
+}
+
+fn segments_eq<P: PointCoords>(a: &[CurveSegment<P>], b: &[CurveSegment<P>]) -> bool {
+    use CurveSegment as S;
+    a.len() == b.len()
+        && a.iter().zip(b).all(|pair| match pair {
+            (S::LineString(a), S::LineString(b)) => points_eq(&a.points, &b.points),
+            (S::CircularString(a), S::CircularString(b)) => points_eq(&a.points, &b.points),
+            _ => false,
+        })
+}
+
+fn curves_eq<P: PointCoords>(a: &[Curve<P>], b: &[Curve<P>]) -> bool {
+    use Curve as C;
+    a.len() == b.len()
+        && a.iter().zip(b).all(|pair| match pair {
+            (C::LineString(a), C::LineString(b)) => points_eq(&a.points, &b.points),
+            (C::CircularString(a), C::CircularString(b)) => points_eq(&a.points, &b.points),
+            (C::CompoundCurve(a), C::CompoundCurve(b)) => segments_eq(&a.segments, &b.segments),
+            _ => false,
+        })
+}
+
+/// Compare geometries ignoring all SRIDs.
+fn geometries_eq<P: PointCoords>(a: &GeometryT<P>, b: &GeometryT<P>) -> bool {
+    use GeometryT as G;
+    match (a, b) {
+        (G::Point(a), G::Point(b)) => coords_eq(a, b),
+        (G::LineString(a), G::LineString(b)) => points_eq(&a.points, &b.points),
+        (G::Polygon(a), G::Polygon(b)) => lines_eq(&a.rings, &b.rings),
+        (G::MultiPoint(a), G::MultiPoint(b)) => points_eq(&a.points, &b.points),
+        (G::MultiLineString(a), G::MultiLineString(b)) => lines_eq(&a.lines, &b.lines),
+        (G::MultiPolygon(a), G::MultiPolygon(b)) => polygons_eq(&a.polygons, &b.polygons),
+        (G::GeometryCollection(a), G::GeometryCollection(b)) => {
+            collections_eq(&a.geometries, &b.geometries)
+        }
+        (G::CircularString(a), G::CircularString(b)) => points_eq(&a.points, &b.points),
+        (G::CompoundCurve(a), G::CompoundCurve(b)) => segments_eq(&a.segments, &b.segments),
+        (G::CurvePolygon(a), G::CurvePolygon(b)) => curves_eq(&a.rings, &b.rings),
+        _ => false,
+    }
+}
+
+fn collections_eq<P: PointCoords>(a: &[GeometryT<P>], b: &[GeometryT<P>]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| geometries_eq(a, b))
+}
+
+macro_rules! impl_geom_eq {
+    ($geotype:ident, |$a:ident, $b:ident| $body:expr) => {
+        impl<P: PointCoords> $geotype<P> {
+            /// Compare the SRID and the coordinates, ignoring the SRIDs of sub-geometries.
+            ///
+            /// PostGIS doesn't store SRIDs of sub-geometries, so this compares a geometry
+            /// constructed with SRIDs on its parts equal to the one read from the database.
+            pub fn geom_eq(&self, other: &Self) -> bool {
+                let ($a, $b) = (self, other);
+                $a.srid == $b.srid && $body
+            }
+        }
+    };
+}
+
+impl_geom_eq!(LineStringT, |a, b| points_eq(&a.points, &b.points));
+impl_geom_eq!(MultiPointT, |a, b| points_eq(&a.points, &b.points));
+impl_geom_eq!(PolygonT, |a, b| lines_eq(&a.rings, &b.rings));
+impl_geom_eq!(MultiLineStringT, |a, b| lines_eq(&a.lines, &b.lines));
+impl_geom_eq!(MultiPolygonT, |a, b| polygons_eq(&a.polygons, &b.polygons));
+impl_geom_eq!(GeometryCollectionT, |a, b| collections_eq(
+    &a.geometries,
+    &b.geometries
+));
+impl_geom_eq!(CircularStringT, |a, b| points_eq(&a.points, &b.points));
+impl_geom_eq!(CompoundCurveT, |a, b| segments_eq(&a.segments, &b.segments));
+impl_geom_eq!(CurvePolygonT, |a, b| curves_eq(&a.rings, &b.rings));
+
+impl<P: PointCoords> GeometryT<P> {
+    /// Compare the SRID and the coordinates, ignoring the SRIDs of sub-geometries.
+    pub fn geom_eq(&self, other: &Self) -> bool {
+        let srid = |g: &Self| match g {
+            GeometryT::Point(p) => p.opt_srid(),
+            GeometryT::LineString(g) => g.srid,
+            GeometryT::Polygon(g) => g.srid,
+            GeometryT::MultiPoint(g) => g.srid,
+            GeometryT::MultiLineString(g) => g.srid,
+            GeometryT::MultiPolygon(g) => g.srid,
+            GeometryT::GeometryCollection(g) => g.srid,
+            GeometryT::CircularString(g) => g.srid,
+            GeometryT::CompoundCurve(g) => g.srid,
+            GeometryT::CurvePolygon(g) => g.srid,
+        };
+        srid(self) == srid(other) && geometries_eq(self, other)
+    }
+}
+
+/// Order of vertices by X, Y, Z and M.
+pub(super) fn vertex_cmp<P: postgis::Point>(a: &P, b: &P) -> Ordering {
+    let cmp = |u: f64, v: f64| u.partial_cmp(&v).unwrap_or(Ordering::Equal);
+    cmp(a.x(), b.x())
+        .then(cmp(a.y(), b.y()))
+        .then(cmp(a.opt_z().unwrap_or(0.0), b.opt_z().unwrap_or(0.0)))
+        .then(cmp(a.opt_m().unwrap_or(0.0), b.opt_m().unwrap_or(0.0)))
+}
+
+/// Vertices of a ring without closing point, counter-clockwise and starting at the smallest
+/// vertex. If the smallest vertex occurs more than once, the smallest rotation is used.
+fn normalized_ring<P: postgis::Point>(points: &[P]) -> Vec<&P> {
+    let mut ring: Vec<&P> = points.iter().collect();
+    if ring.len() > 1 && coords_eq(ring[0], ring[ring.len() - 1]) {
+        ring.pop();
+    }
+    if ring_signed_area(points) < 0.0 {
+        ring.reverse();
+    }
+    let rotation = |start: usize| ring[start..].iter().chain(&ring[..start]);
+    let rotation_cmp = |i: usize, j: usize| {
+        rotation(i)
+            .zip(rotation(j))
+            .map(|(a, b)| vertex_cmp(*a, *b))
+            .find(|ord| ord.is_ne())
+            .unwrap_or(Ordering::Equal)
+    };
+    let min = ring.iter().copied().min_by(|a, b| vertex_cmp(*a, *b));
+    let start = (0..ring.len())
+        .filter(|&i| min.is_some_and(|min| coords_eq(ring[i], min)))
+        .min_by(|&i, &j| rotation_cmp(i, j));
+    if let Some(start) = start {
+        ring.rotate_left(start);
+    }
+    ring
+}
+
+impl<P: PointCoords> PolygonT<P> {
+    /// Compare the SRID and the shape of the rings, independent of the start vertex and
+    /// orientation of each ring.
+    ///
+    /// Each ring is normalized by removing its closing point, reversing it if it is clockwise
+    /// and rotating it to start at the lexicographically smallest vertex, ordered by X, Y, Z
+    /// and M. The rings are compared in their order, i.e. holes must be listed in the same
+    /// order. SRIDs of sub-geometries are ignored like in [`geom_eq`](Self::geom_eq).
+    pub fn same_shape(&self, other: &Self) -> bool {
+        self.srid == other.srid
+            && self.rings.len() == other.rings.len()
+            && self.rings.iter().zip(&other.rings).all(|(a, b)| {
+                let (a, b) = (normalized_ring(&a.points), normalized_ring(&b.points));
+                a.len() == b.len() && a.iter().zip(&b).all(|(a, b)| coords_eq(*a, *b))
+            })
+    }
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_same_shape() {
+    let square = PolygonT::<Point> {srid: Some(4326), rings: vec![line_xy(&[(0., 0.), (2., 0.), (2., 2.), (0., 2.), (0., 0.)])]};
+    let rotated = PolygonT::<Point> {srid: Some(4326), rings: vec![line_xy(&[(2., 2.), (0., 2.), (0., 0.), (2., 0.), (2., 2.)])]};
+    let clockwise = PolygonT::<Point> {srid: Some(4326), rings: vec![line_xy(&[(2., 0.), (0., 0.), (0., 2.), (2., 2.), (2., 0.)])]};
+    assert_ne!(square, rotated);
+    assert!(square.same_shape(&rotated));
+    assert!(square.same_shape(&clockwise));
+    assert!(!square.same_shape(&PolygonT {srid: None, ..rotated.clone()}));
+    let other = PolygonT::<Point> {srid: Some(4326), rings: vec![line_xy(&[(0., 0.), (2., 0.), (2., 3.), (0., 2.), (0., 0.)])]};
+    assert!(!square.same_shape(&other));
+
+    // Ring touching itself in a vertex
+    let touching = [(0., 0.), (4., 0.), (2., 2.), (4., 4.), (0., 4.), (2., 2.), (0., 0.)];
+    let a = PolygonT::<Point> {srid: None, rings: vec![line_xy(&touching)]};
+    let b = PolygonT::<Point> {srid: None, rings: vec![line_xy(&[(2., 2.), (4., 4.), (0., 4.), (2., 2.), (0., 0.), (4., 0.), (2., 2.)])]};
+    assert!(a.same_shape(&b));
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_geom_eq() {
+    let p = |x, y, srid| Point::new(x, y, srid);
+    let ring = |srid| LineStringT::<Point> {srid, points: vec![p(0., 0., srid), p(2., 0., srid), p(2., 2., srid), p(0., 0., srid)]};
+    let built = PolygonT::<Point> {srid: Some(4326), rings: vec![ring(Some(4326))]};
+    let read = PolygonT::<Point> {srid: Some(4326), rings: vec![ring(None)]};
+    assert_ne!(built, read);
+    assert!(built.geom_eq(&read));
+    assert!(!built.geom_eq(&PolygonT {srid: None, ..read.clone()}));
+    let mut moved = read.clone();
+    moved.rings[0].points[1] = p(3., 0., None);
+    assert!(!built.geom_eq(&moved));
+
+    let multi = |srid, poly: &PolygonT<Point>| MultiPolygonT::<Point> {srid, polygons: vec![poly.clone()]};
+    assert!(multi(Some(4326), &built).geom_eq(&multi(Some(4326), &read)));
+
+    let collection = |poly: &PolygonT<Point>| GeometryCollectionT::<Point> {srid: Some(4326), geometries: vec![
+        GeometryT::Point(p(1., 1., poly.srid)), GeometryT::Polygon(poly.clone())]};
+    assert!(collection(&built).geom_eq(&collection(&read)));
+    assert!(!collection(&built).geom_eq(&collection(&moved)));
+    let geom = GeometryT::GeometryCollection(collection(&built));
+    assert!(geom.geom_eq(&GeometryT::GeometryCollection(collection(&read))));
+    assert!(!geom.geom_eq(&GeometryT::Polygon(built)));
+    assert!(!GeometryT::Point(p(1., 1., Some(4326))).geom_eq(&GeometryT::Point(p(1., 1., None))));
+}
@@ -1,13 +1,14 @@
+use crate::ewkb::compare::coords_eq;
 use crate::ewkb::encoding::*;
 use crate::ewkb::point::*;
-use crate::ewkb::{EwkbPoint, EwkbRead, EwkbWrite};
+use crate::ewkb::{EwkbRead, EwkbWrite, ReadContext};
 use crate::{error::Error, types as postgis};
 use byteorder::LittleEndian;
 use byteorder::WriteBytesExt;
 use std::fmt;
 use std::io::{Read, Write};
 use std::iter::FromIterator;
-use std::slice::Iter;
+use std::slice::{Iter, IterMut};
 
 macro_rules! point_container_type {
     // geometries containing points
@@ -17,6 +18,7 @@ macro_rules! point_container_type {
         #[derive(PartialEq, Clone, Debug)]
         pub struct $geotype<P: postgis::Point + EwkbRead> {
             pub points: Vec<P>,
+            #[cfg_attr(feature = "serde", serde(default))]
             pub srid: Option<i32>,
         }
 
@@ -33,6 +35,11 @@ macro_rules! point_container_type {
                     srid: None,
                 }
             }
+
+            /// Mutable iterator for editing the points in place.
+            pub fn points_mut(&mut self) -> IterMut<'_, P> {
+                self.points.iter_mut()
+            }
         }
 
         impl<P> FromIterator<P> for $geotype<P>
@@ -52,6 +59,19 @@ macro_rules! point_container_type {
             }
         }
 
+        /// Slice of the points, for generic code bounded by `AsRef<[P]>`.
+        impl<P: postgis::Point + EwkbRead> AsRef<[P]> for $geotype<P> {
+            fn as_ref(&self) -> &[P] {
+                &self.points
+            }
+        }
+
+        impl<P: postgis::Point + EwkbRead> AsMut<[P]> for $geotype<P> {
+            fn as_mut(&mut self) -> &mut [P] {
+                &mut self.points
+            }
+        }
+
         impl<'a, P> postgis::$geotypetrait<'a> for $geotype<P>
         where
             P: 'a + postgis::Point + EwkbRead,
@@ -79,11 +99,14 @@ macro_rules! impl_read_for_point_container_type {
                 is_be: bool,
                 type_id: u32,
                 srid: Option<i32>,
+                ctx: &mut ReadContext,
             ) -> Result<Self, Error> {
                 let mut points: Vec<P> = vec![];
                 let size = read_u32(raw, is_be)? as usize;
-                for _ in 0..size {
-                    points.push(P::read_ewkb_body(raw, is_be, type_id, srid)?);
+                for i in 0..size {
+                    let point = P::read_ewkb_body(raw, is_be, type_id, srid, ctx)
+                        .map_err(|e| ctx.at("points", i, e))?;
+                    points.push(point);
                 }
                 Ok($geotype::<P> {
                     points,
@@ -105,11 +128,14 @@ macro_rules! impl_read_for_point_container_type {
                 is_be: bool,
                 _type_id: u32,
                 srid: Option<i32>,
+                ctx: &mut ReadContext,
             ) -> Result<Self, Error> {
                 let mut points: Vec<P> = vec![];
                 let size = read_u32(raw, is_be)? as usize;
-                for _ in 0..size {
-                    points.push(P::read_ewkb(raw)?);
+                for i in 0..size {
+                    let point = P::read_ewkb_with_context(raw, ctx)
+                        .map_err(|e| ctx.at("points", i, e))?;
+                    points.push(point);
                 }
                 Ok($geotype::<P> {
                     points,
@@ -121,7 +147,7 @@ macro_rules! impl_read_for_point_container_type {
 }
 
 macro_rules! point_container_write {
-    ($geotypetrait:ident and $asewkbtype:ident for $geotype:ident to $ewkbtype:ident with type code $typecode:expr, command $writecmd:ident) => {
+    ($geotypetrait:ident and $asewkbtype:ident for $geotype:ident to $ewkbtype:ident with type code $typecode:expr, point headers $headers:expr) => {
         pub struct $ewkbtype<'a, P, I>
         where
             P: 'a + postgis::Point,
@@ -165,16 +191,26 @@ macro_rules! point_container_write {
 
             fn write_ewkb_body<W: Write + ?Sized>(&self, w: &mut W) -> Result<(), Error> {
                 w.write_u32::<LittleEndian>(self.geom.points().len() as u32)?;
+                let point_type_id = 0x01 | Self::wkb_type_id(&self.point_type, None);
                 for geom in self.geom.points() {
-                    let wkb = EwkbPoint {
-                        geom,
-                        srid: None,
-                        point_type: self.point_type.clone(),
-                    };
-                    wkb.$writecmd(w)?;
+                    if $headers {
+                        w.write_u8(0x01)?;
+                        w.write_u32::<LittleEndian>(point_type_id)?;
+                    }
+                    write_point_coords(geom, w)?;
                 }
                 Ok(())
             }
+
+            fn ewkb_body_size_hint(&self) -> usize {
+                let header_size = if $headers { 1 + 4 } else { 0 };
+                let items: usize = self
+                    .geom
+                    .points()
+                    .map(|geom| header_size + point_coords_size(geom))
+                    .sum();
+                4 + items
+            }
         }
 
         impl<'a, P> $asewkbtype<'a> for $geotype<P>
@@ -198,7 +234,7 @@ point_container_type!(LineString for LineStringT);
 impl_read_for_point_container_type!(singletype LineStringT);
 point_container_write!(LineString and AsEwkbLineString for LineStringT
                        to EwkbLineString with type code 0x02,
-                       command write_ewkb_body);
+                       point headers false);
 
 /// OGC LineString type
 pub type LineString = LineStringT<Point>;
@@ -209,11 +245,150 @@ pub type LineStringM = LineStringT<PointM>;
 /// OGC LineStringZM type
 pub type LineStringZM = LineStringT<PointZM>;
 
+impl<P: postgis::Point + EwkbRead> LineStringT<P> {
+    /// First point of the line, `None` if the line is empty.
+    pub fn start_point(&self) -> Option<&P> {
+        self.points.first()
+    }
+
+    /// Last point of the line, `None` if the line is empty.
+    pub fn end_point(&self) -> Option<&P> {
+        self.points.last()
+    }
+
+    /// Whether the line is non-empty and its start and end point have the same coordinates.
+    ///
+    /// Unlike `ST_IsRing`, this doesn't check whether the line is simple.
+    pub fn is_ring(&self) -> bool {
+        match (self.start_point(), self.end_point()) {
+            (Some(a), Some(b)) => coords_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl<P: PointCoords> LineStringT<P> {
+    /// Flatten the coordinates into `[x0, y0, x1, y1, ...]`.
+    ///
+    /// Z and M values are included if the point type has them, in the order x, y, z, m.
+    /// Each point takes `P::point_type().num_coords()` values.
+    pub fn to_coord_buffer(&self) -> Vec<f64> {
+        let point_type = P::point_type();
+        let mut buf = Vec::with_capacity(self.points.len() * point_type.num_coords());
+        for p in &self.points {
+            buf.push(p.x());
+            buf.push(p.y());
+            if point_type.has_z() {
+                buf.push(p.opt_z().unwrap_or(0.0));
+            }
+            if point_type.has_m() {
+                buf.push(p.opt_m().unwrap_or(0.0));
+            }
+        }
+        buf
+    }
+
+    /// Create a line from a flat coordinate buffer with `dims` values per point.
+    ///
+    /// `dims` is 2 for `[x, y]`, 3 for `[x, y, z]` (or `[x, y, m]` for M point types) and
+    /// 4 for `[x, y, z, m]`. Values not supported by the point type are dropped.
+    pub fn from_coord_buffer(buf: &[f64], dims: usize, srid: Option<i32>) -> Result<Self, Error> {
+        if !(2..=4).contains(&dims) {
+            return Err(Error::Other(format!(
+                "invalid coordinate dimension {}, expected 2, 3 or 4",
+                dims
+            )));
+        }
+        if !buf.len().is_multiple_of(dims) {
+            return Err(Error::Other(format!(
+                "coordinate buffer length {} is not a multiple of {}",
+                buf.len(),
+                dims
+            )));
+        }
+        let m_only = P::point_type() == PointType::PointM;
+        let points = buf
+            .chunks_exact(dims)
+            .map(|c| {
+                let (z, m) = match dims {
+                    3 if m_only => (None, Some(c[2])),
+                    3 => (Some(c[2]), None),
+                    4 => (Some(c[2]), Some(c[3])),
+                    _ => (None, None),
+                };
+                P::new_from_opt_vals(c[0], c[1], z, m, srid)
+            })
+            .collect();
+        Ok(LineStringT { points, srid })
+    }
+}
+
+impl LineStringZ {
+    /// Copy the coordinates into a contiguous `Vec` with C layout, e.g. for FFI.
+    ///
+    /// Points can't be borrowed as a `CoordZ` slice without copying, because each `PointZ`
+    /// also stores its SRID.
+    pub fn to_coords(&self) -> Vec<CoordZ> {
+        self.points.iter().map(PointZ::coord).collect()
+    }
+
+    /// Add the measure `m` to all points, see [`PointZ::to_zm`].
+    pub fn to_zm(&self, m: f64) -> LineStringZM {
+        LineStringT {
+            points: self.points.iter().map(|p| p.to_zm(m)).collect(),
+            srid: self.srid,
+        }
+    }
+}
+
+impl LineStringM {
+    /// Add the Z coordinate `z` to all points, see [`PointM::to_zm`].
+    pub fn to_zm(&self, z: f64) -> LineStringZM {
+        LineStringT {
+            points: self.points.iter().map(|p| p.to_zm(z)).collect(),
+            srid: self.srid,
+        }
+    }
+}
+
+impl LineStringZM {
+    /// Drop the Z coordinate of all points, see [`PointZM::to_m`].
+    pub fn to_m(&self) -> LineStringM {
+        LineStringT {
+            points: self.points.iter().map(PointZM::to_m).collect(),
+            srid: self.srid,
+        }
+    }
+
+    /// Drop the measure of all points, see [`PointZM::to_z`].
+    pub fn to_z(&self) -> LineStringZ {
+        LineStringT {
+            points: self.points.iter().map(PointZM::to_z).collect(),
+            srid: self.srid,
+        }
+    }
+}
+
+point_container_type!(LineString for CircularStringT);
+impl_read_for_point_container_type!(singletype CircularStringT);
+point_container_write!(LineString and AsEwkbCircularString for CircularStringT
+                       to EwkbCircularString with type code 0x08,
+                       point headers false);
+
+/// SQL-MM CircularString type
+pub type CircularString = CircularStringT<Point>;
+/// SQL-MM CircularStringZ type
+pub type CircularStringZ = CircularStringT<PointZ>;
+/// SQL-MM CircularStringM type
+pub type CircularStringM = CircularStringT<PointM>;
+/// SQL-MM CircularStringZM type
+pub type CircularStringZM = CircularStringT<PointZM>;
+
 point_container_type!(MultiPoint for MultiPointT);
 impl_read_for_point_container_type!(multitype MultiPointT);
 point_container_write!(MultiPoint and AsEwkbMultiPoint for MultiPointT
                        to EwkbMultiPoint with type code 0x04,
-                       command write_ewkb);
+                       point headers true);
 
 /// OGC MultiPoint type
 pub type MultiPoint = MultiPointT<Point>;
@@ -0,0 +1,134 @@
+//! `geo` crate interoperability.
+//!
+//! Implements `geo::CoordsIter` for the container types, which makes the `geo` algorithms
+//! generic over it, like `ConvexHull`, `Extremes` or `MinimumRotatedRect`, usable directly.
+//! Coordinates are 2D, Z and M values are ignored.
+
+use crate::ewkb::*;
+use geo::{Coord, CoordsIter};
+use std::iter::{FlatMap, Map};
+
+type PointCoordsIter<'a, P> = Map<Iter<'a, P>, fn(&'a P) -> Coord<f64>>;
+type LinesCoordsIter<'a, P> = FlatMap<
+    Iter<'a, LineStringT<P>>,
+    PointCoordsIter<'a, P>,
+    fn(&'a LineStringT<P>) -> PointCoordsIter<'a, P>,
+>;
+type PolygonsCoordsIter<'a, P> = FlatMap<
+    Iter<'a, PolygonT<P>>,
+    LinesCoordsIter<'a, P>,
+    fn(&'a PolygonT<P>) -> LinesCoordsIter<'a, P>,
+>;
+type PolygonsExteriorIter<'a, P> = FlatMap<
+    Iter<'a, PolygonT<P>>,
+    PointCoordsIter<'a, P>,
+    fn(&'a PolygonT<P>) -> PointCoordsIter<'a, P>,
+>;
+
+fn coord<P: postgis::Point>(p: &P) -> Coord<f64> {
+    Coord { x: p.x(), y: p.y() }
+}
+
+fn points_coords<P: postgis::Point>(points: &[P]) -> PointCoordsIter<'_, P> {
+    points.iter().map(coord::<P> as fn(&P) -> Coord<f64>)
+}
+
+fn line_coords<P: postgis::Point + EwkbRead>(line: &LineStringT<P>) -> PointCoordsIter<'_, P> {
+    points_coords(&line.points)
+}
+
+fn lines_coords<P: postgis::Point + EwkbRead>(lines: &[LineStringT<P>]) -> LinesCoordsIter<'_, P> {
+    lines
+        .iter()
+        .flat_map(line_coords::<P> as fn(&LineStringT<P>) -> PointCoordsIter<'_, P>)
+}
+
+fn polygon_coords<P: postgis::Point + EwkbRead>(polygon: &PolygonT<P>) -> LinesCoordsIter<'_, P> {
+    lines_coords(&polygon.rings)
+}
+
+fn polygon_exterior<P: postgis::Point + EwkbRead>(polygon: &PolygonT<P>) -> PointCoordsIter<'_, P> {
+    points_coords(polygon.rings.first().map_or(&[], |ring| &ring.points[..]))
+}
+
+macro_rules! impl_coords_iter {
+    ($geotype:ident, $iter:ident, $exterior_iter:ident,
+     coords($s:ident) $coords:expr, exterior $exterior:expr, count $count:expr) => {
+        impl<P: postgis::Point + EwkbRead> CoordsIter for $geotype<P> {
+            type Iter<'a>
+                = $iter<'a, P>
+            where
+                Self: 'a;
+            type ExteriorIter<'a>
+                = $exterior_iter<'a, P>
+            where
+                Self: 'a;
+            type Scalar = f64;
+
+            fn coords_iter(&self) -> Self::Iter<'_> {
+                let $s = self;
+                $coords
+            }
+
+            fn coords_count(&self) -> usize {
+                let $s = self;
+                $count
+            }
+
+            fn exterior_coords_iter(&self) -> Self::ExteriorIter<'_> {
+                let $s = self;
+                $exterior
+            }
+        }
+    };
+}
+
+impl_coords_iter!(LineStringT, PointCoordsIter, PointCoordsIter,
+    coords(s) points_coords(&s.points),
+    exterior points_coords(&s.points),
+    count s.points.len());
+impl_coords_iter!(MultiPointT, PointCoordsIter, PointCoordsIter,
+    coords(s) points_coords(&s.points),
+    exterior points_coords(&s.points),
+    count s.points.len());
+impl_coords_iter!(PolygonT, LinesCoordsIter, PointCoordsIter,
+    coords(s) lines_coords(&s.rings),
+    exterior polygon_exterior(s),
+    count s.rings.iter().map(|r| r.points.len()).sum());
+impl_coords_iter!(MultiLineStringT, LinesCoordsIter, LinesCoordsIter,
+    coords(s) lines_coords(&s.lines),
+    exterior lines_coords(&s.lines),
+    count s.lines.iter().map(|l| l.points.len()).sum());
+impl_coords_iter!(MultiPolygonT, PolygonsCoordsIter, PolygonsExteriorIter,
+    coords(s) s.polygons.iter().flat_map(polygon_coords::<P> as fn(&PolygonT<P>) -> LinesCoordsIter<'_, P>),
+    exterior s.polygons.iter().flat_map(polygon_exterior::<P> as fn(&PolygonT<P>) -> PointCoordsIter<'_, P>),
+    count s.polygons.iter().flat_map(|p| &p.rings).map(|r| r.points.len()).sum());
+
+#[test]
+#[rustfmt::skip]
+fn test_coords_iter() {
+    use geo::{ConvexHull, Extremes};
+
+    let p = |x, y| Point::new(x, y, Some(4326));
+    let line = LineStringT::<Point> {srid: Some(4326), points: vec![p(10.0, -20.0), p(0., -0.5), p(3., 4.)]};
+    assert_eq!(line.coords_count(), 3);
+    assert_eq!(line.coords_iter().collect::<Vec<_>>(), vec![Coord {x: 10.0, y: -20.0}, Coord {x: 0., y: -0.5}, Coord {x: 3., y: 4.}]);
+    let extremes = line.extremes().unwrap();
+    assert_eq!(extremes.x_max.coord, Coord {x: 10.0, y: -20.0});
+    assert_eq!(extremes.y_max.coord, Coord {x: 3., y: 4.});
+
+    let ring = |x0: f64, y0: f64, x1: f64, y1: f64| LineStringT::<Point> {srid: None, points: vec![p(x0, y0), p(x1, y0), p(x1, y1), p(x0, y1), p(x0, y0)]};
+    let poly = PolygonT::<Point> {srid: Some(4326), rings: vec![ring(0., 0., 10., 10.), ring(2., 2., 4., 4.)]};
+    assert_eq!(poly.coords_count(), 10);
+    assert_eq!(poly.coords_iter().count(), 10);
+    assert_eq!(poly.exterior_coords_iter().count(), 5);
+    assert_eq!(poly.convex_hull().exterior().0.len(), 5);
+
+    let multi = MultiPolygonT::<Point> {srid: None, polygons: vec![poly.clone(), PolygonT::new(), poly]};
+    assert_eq!(multi.coords_count(), 20);
+    assert_eq!(multi.coords_iter().count(), 20);
+    assert_eq!(multi.exterior_coords_iter().count(), 10);
+
+    let lines = MultiLineStringT::<PointZ> {srid: None, lines: vec![LineStringT {srid: None, points: vec![PointZ::new(1., 2., 3., None)]}]};
+    assert_eq!(lines.coords_iter().collect::<Vec<_>>(), vec![Coord {x: 1., y: 2.}]);
+}
@@ -0,0 +1,392 @@
+//! Curved geometry types of SQL-MM Part 3, as stored by PostGIS.
+
+use crate::ewkb::*;
+
+/// Part of a [`CompoundCurveT`].
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Clone, Debug)]
+pub enum CurveSegment<P: postgis::Point + EwkbRead> {
+    LineString(LineStringT<P>),
+    CircularString(CircularStringT<P>),
+}
+
+/// Ring of a [`CurvePolygonT`].
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Clone, Debug)]
+pub enum Curve<P: postgis::Point + EwkbRead> {
+    LineString(LineStringT<P>),
+    CircularString(CircularStringT<P>),
+    CompoundCurve(CompoundCurveT<P>),
+}
+
+/// SQL-MM CompoundCurve, a connected sequence of straight and circular segments.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Clone, Debug)]
+pub struct CompoundCurveT<P: postgis::Point + EwkbRead> {
+    pub segments: Vec<CurveSegment<P>>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub srid: Option<i32>,
+}
+
+/// SQL-MM CurvePolygon, a polygon whose rings may be curves.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Clone, Debug)]
+pub struct CurvePolygonT<P: postgis::Point + EwkbRead> {
+    pub rings: Vec<Curve<P>>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub srid: Option<i32>,
+}
+
+impl<P: postgis::Point + EwkbRead> Default for CompoundCurveT<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P: postgis::Point + EwkbRead> CompoundCurveT<P> {
+    pub fn new() -> CompoundCurveT<P> {
+        CompoundCurveT {
+            segments: Vec::new(),
+            srid: None,
+        }
+    }
+}
+
+impl<P: postgis::Point + EwkbRead> Default for CurvePolygonT<P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<P: postgis::Point + EwkbRead> CurvePolygonT<P> {
+    pub fn new() -> CurvePolygonT<P> {
+        CurvePolygonT {
+            rings: Vec::new(),
+            srid: None,
+        }
+    }
+}
+
+/// CompoundCurve with 2D points
+pub type CompoundCurve = CompoundCurveT<Point>;
+/// CompoundCurve with Z coordinates
+pub type CompoundCurveZ = CompoundCurveT<PointZ>;
+/// CompoundCurve with measures
+pub type CompoundCurveM = CompoundCurveT<PointM>;
+/// CompoundCurve with Z coordinates and measures
+pub type CompoundCurveZM = CompoundCurveT<PointZM>;
+/// CurvePolygon with 2D points
+pub type CurvePolygon = CurvePolygonT<Point>;
+/// CurvePolygon with Z coordinates
+pub type CurvePolygonZ = CurvePolygonT<PointZ>;
+/// CurvePolygon with measures
+pub type CurvePolygonM = CurvePolygonT<PointM>;
+/// CurvePolygon with Z coordinates and measures
+pub type CurvePolygonZM = CurvePolygonT<PointZM>;
+
+// --- Reading
+
+impl<P> EwkbRead for CurveSegment<P>
+where
+    P: postgis::Point + EwkbRead,
+{
+    fn point_type() -> PointType {
+        P::point_type()
+    }
+    fn read_ewkb_body<R: Read>(
+        raw: &mut R,
+        is_be: bool,
+        type_id: u32,
+        srid: Option<i32>,
+        ctx: &mut ReadContext,
+    ) -> Result<Self, Error> {
+        match type_id & 0xff {
+            0x02 => Ok(CurveSegment::LineString(LineStringT::read_ewkb_body(
+                raw, is_be, type_id, srid, ctx,
+            )?)),
+            0x08 => Ok(CurveSegment::CircularString(
+                CircularStringT::read_ewkb_body(raw, is_be, type_id, srid, ctx)?,
+            )),
+            _ => Err(Error::Read(format!(
+                "Error reading compound curve segment - unsupported type id {}.",
+                type_id
+            ))),
+        }
+    }
+}
+
+impl<P> EwkbRead for Curve<P>
+where
+    P: postgis::Point + EwkbRead,
+{
+    fn point_type() -> PointType {
+        P::point_type()
+    }
+    fn read_ewkb_body<R: Read>(
+        raw: &mut R,
+        is_be: bool,
+        type_id: u32,
+        srid: Option<i32>,
+        ctx: &mut ReadContext,
+    ) -> Result<Self, Error> {
+        match type_id & 0xff {
+            0x02 => Ok(Curve::LineString(LineStringT::read_ewkb_body(
+                raw, is_be, type_id, srid, ctx,
+            )?)),
+            0x08 => Ok(Curve::CircularString(CircularStringT::read_ewkb_body(
+                raw, is_be, type_id, srid, ctx,
+            )?)),
+            0x09 => Ok(Curve::CompoundCurve(CompoundCurveT::read_ewkb_body(
+                raw, is_be, type_id, srid, ctx,
+            )?)),
+            _ => Err(Error::Read(format!(
+                "Error reading curve polygon ring - unsupported type id {}.",
+                type_id
+            ))),
+        }
+    }
+}
+
+impl<P> EwkbRead for CompoundCurveT<P>
+where
+    P: postgis::Point + EwkbRead,
+{
+    fn point_type() -> PointType {
+        P::point_type()
+    }
+    fn read_ewkb_body<R: Read>(
+        raw: &mut R,
+        is_be: bool,
+        _type_id: u32,
+        srid: Option<i32>,
+        ctx: &mut ReadContext,
+    ) -> Result<Self, Error> {
+        let mut segments = vec![];
+        let size = read_u32(raw, is_be)? as usize;
+        for i in 0..size {
+            let segment = CurveSegment::read_ewkb_with_context(raw, ctx)
+                .map_err(|e| ctx.at("segments", i, e))?;
+            segments.push(segment);
+        }
+        Ok(CompoundCurveT { segments, srid })
+    }
+}
+
+impl<P> EwkbRead for CurvePolygonT<P>
+where
+    P: postgis::Point + EwkbRead,
+{
+    fn point_type() -> PointType {
+        P::point_type()
+    }
+    fn read_ewkb_body<R: Read>(
+        raw: &mut R,
+        is_be: bool,
+        _type_id: u32,
+        srid: Option<i32>,
+        ctx: &mut ReadContext,
+    ) -> Result<Self, Error> {
+        let mut rings = vec![];
+        let size = read_u32(raw, is_be)? as usize;
+        for i in 0..size {
+            let ring =
+                Curve::read_ewkb_with_context(raw, ctx).map_err(|e| ctx.at("rings", i, e))?;
+            rings.push(ring);
+        }
+        Ok(CurvePolygonT { rings, srid })
+    }
+}
+
+// --- Writing
+
+/// Object safe writer interface of the curve types, for nesting them in other geometries.
+#[doc(hidden)]
+pub trait CurveWrite: Send + Sync {
+    fn curve_type_code(&self) -> u32;
+    fn write_curve_body(&self, w: &mut dyn Write, point_type: PointType) -> Result<(), Error>;
+    fn curve_body_size_hint(&self, point_type: PointType) -> usize;
+}
+
+fn write_points<P: postgis::Point>(points: &[P], w: &mut dyn Write) -> Result<(), Error> {
+    w.write_u32::<LittleEndian>(points.len() as u32)?;
+    for geom in points {
+        write_point_coords(geom, w)?;
+    }
+    Ok(())
+}
+
+fn points_size_hint<P: postgis::Point>(points: &[P]) -> usize {
+    4 + points.iter().map(point_coords_size).sum::<usize>()
+}
+
+fn write_parts(
+    parts: &[&dyn CurveWrite],
+    w: &mut dyn Write,
+    point_type: PointType,
+) -> Result<(), Error> {
+    w.write_u32::<LittleEndian>(parts.len() as u32)?;
+    for geom in parts {
+        let wkb = EwkbCurve {
+            geom: *geom,
+            srid: None,
+            point_type,
+        };
+        wkb.write_ewkb(w)?;
+    }
+    Ok(())
+}
+
+fn parts_size_hint(parts: &[&dyn CurveWrite], point_type: PointType) -> usize {
+    let items: usize = parts
+        .iter()
+        .map(|geom| {
+            let wkb = EwkbCurve {
+                geom: *geom,
+                srid: None,
+                point_type,
+            };
+            wkb.ewkb_size_hint()
+        })
+        .sum();
+    4 + items
+}
+
+macro_rules! impl_curve_write_for_points {
+    ($geotype:ident with type code $typecode:expr) => {
+        impl<P> CurveWrite for $geotype<P>
+        where
+            P: postgis::Point + EwkbRead,
+        {
+            fn curve_type_code(&self) -> u32 {
+                $typecode
+            }
+            fn write_curve_body(&self, w: &mut dyn Write, _: PointType) -> Result<(), Error> {
+                write_points(&self.points, w)
+            }
+            fn curve_body_size_hint(&self, _: PointType) -> usize {
+                points_size_hint(&self.points)
+            }
+        }
+    };
+}
+
+impl_curve_write_for_points!(LineStringT with type code 0x02);
+impl_curve_write_for_points!(CircularStringT with type code 0x08);
+
+impl<P> CurveSegment<P>
+where
+    P: postgis::Point + EwkbRead,
+{
+    fn as_curve(&self) -> &dyn CurveWrite {
+        match self {
+            CurveSegment::LineString(geom) => geom,
+            CurveSegment::CircularString(geom) => geom,
+        }
+    }
+}
+
+impl<P> Curve<P>
+where
+    P: postgis::Point + EwkbRead,
+{
+    fn as_curve(&self) -> &dyn CurveWrite {
+        match self {
+            Curve::LineString(geom) => geom,
+            Curve::CircularString(geom) => geom,
+            Curve::CompoundCurve(geom) => geom,
+        }
+    }
+}
+
+impl<P> CurveWrite for CompoundCurveT<P>
+where
+    P: postgis::Point + EwkbRead,
+{
+    fn curve_type_code(&self) -> u32 {
+        0x09
+    }
+    fn write_curve_body(&self, w: &mut dyn Write, point_type: PointType) -> Result<(), Error> {
+        let parts: Vec<_> = self.segments.iter().map(CurveSegment::as_curve).collect();
+        write_parts(&parts, w, point_type)
+    }
+    fn curve_body_size_hint(&self, point_type: PointType) -> usize {
+        let parts: Vec<_> = self.segments.iter().map(CurveSegment::as_curve).collect();
+        parts_size_hint(&parts, point_type)
+    }
+}
+
+impl<P> CurveWrite for CurvePolygonT<P>
+where
+    P: postgis::Point + EwkbRead,
+{
+    fn curve_type_code(&self) -> u32 {
+        0x0a
+    }
+    fn write_curve_body(&self, w: &mut dyn Write, point_type: PointType) -> Result<(), Error> {
+        let parts: Vec<_> = self.rings.iter().map(Curve::as_curve).collect();
+        write_parts(&parts, w, point_type)
+    }
+    fn curve_body_size_hint(&self, point_type: PointType) -> usize {
+        let parts: Vec<_> = self.rings.iter().map(Curve::as_curve).collect();
+        parts_size_hint(&parts, point_type)
+    }
+}
+
+/// EWKB writer for [`CompoundCurveT`] and [`CurvePolygonT`].
+pub struct EwkbCurve<'a> {
+    pub geom: &'a dyn CurveWrite,
+    pub srid: Option<i32>,
+    pub point_type: PointType,
+}
+
+impl fmt::Debug for EwkbCurve<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, stringify!(EwkbCurve))?; //TODO
+        Ok(())
+    }
+}
+
+impl EwkbWrite for EwkbCurve<'_> {
+    fn opt_srid(&self) -> Option<i32> {
+        self.srid
+    }
+
+    fn type_id(&self) -> u32 {
+        self.geom.curve_type_code() | Self::wkb_type_id(&self.point_type, self.srid)
+    }
+
+    fn write_ewkb_body<W: Write + ?Sized>(&self, w: &mut W) -> Result<(), Error> {
+        let mut w = w;
+        self.geom.write_curve_body(&mut w, self.point_type)
+    }
+
+    fn ewkb_body_size_hint(&self) -> usize {
+        self.geom.curve_body_size_hint(self.point_type)
+    }
+}
+
+impl<P> CompoundCurveT<P>
+where
+    P: postgis::Point + EwkbRead,
+{
+    pub fn as_ewkb(&self) -> EwkbCurve<'_> {
+        EwkbCurve {
+            geom: self,
+            srid: self.srid,
+            point_type: P::point_type(),
+        }
+    }
+}
+
+impl<P> CurvePolygonT<P>
+where
+    P: postgis::Point + EwkbRead,
+{
+    pub fn as_ewkb(&self) -> EwkbCurve<'_> {
+        EwkbCurve {
+            geom: self,
+            srid: self.srid,
+            point_type: P::point_type(),
+        }
+    }
+}
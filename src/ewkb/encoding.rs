@@ -2,6 +2,14 @@ use crate::error::Error;
 use byteorder::{BigEndian, LittleEndian, ReadBytesExt};
 use std::io::Read;
 
+pub fn read_u16<R: Read>(raw: &mut R, is_be: bool) -> Result<u16, Error> {
+    Ok(if is_be {
+        raw.read_u16::<BigEndian>()?
+    } else {
+        raw.read_u16::<LittleEndian>()?
+    })
+}
+
 pub fn read_u32<R: Read>(raw: &mut R, is_be: bool) -> Result<u32, Error> {
     Ok(if is_be {
         raw.read_u32::<BigEndian>()?
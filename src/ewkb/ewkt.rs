@@ -0,0 +1,442 @@
+//! Output in PostGIS EWKT format, as accepted by `ST_GeomFromEWKT`.
+
+use crate::ewkb::*;
+use std::fmt::Write as _;
+
+/// Formatting of coordinates in WKT output, see [`GeometryT::to_ewkt_with`].
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum FloatFormat {
+    /// Shortest representation which parses back to the same value, e.g. `10` or `0.1`.
+    #[default]
+    Shortest,
+    /// Fixed number of decimals, e.g. `10.000000` for `Fixed(6)`.
+    Fixed(u32),
+    /// Rounded to a number of significant digits and printed like `Shortest`, e.g. `123000`
+    /// for 123456 with `Significant(3)`. 0 is treated as 1.
+    Significant(u32),
+}
+
+/// Output of the EWKT writer, keeping the first error of the underlying writer.
+struct EwktWriter<'a> {
+    out: &'a mut dyn std::fmt::Write,
+    format: FloatFormat,
+    /// Dimension suffix of geometry tags.
+    dims: &'static str,
+    result: std::fmt::Result,
+}
+
+impl EwktWriter<'_> {
+    fn push(&mut self, c: char) {
+        if self.result.is_ok() {
+            self.result = self.out.write_char(c);
+        }
+    }
+
+    fn push_str(&mut self, s: &str) {
+        if self.result.is_ok() {
+            self.result = self.out.write_str(s);
+        }
+    }
+
+    fn coord(&mut self, v: f64) {
+        if self.result.is_err() {
+            return;
+        }
+        self.result = match self.format {
+            FloatFormat::Shortest => write!(self.out, "{}", v),
+            FloatFormat::Fixed(decimals) => write!(self.out, "{:.*}", decimals as usize, v),
+            FloatFormat::Significant(digits) => {
+                let digits = digits.max(1) as usize;
+                // Round through scientific notation, which keeps exactly `digits` digits
+                let rounded: f64 = format!("{:.*e}", digits - 1, v).parse().unwrap_or(v);
+                write!(self.out, "{}", rounded)
+            }
+        };
+    }
+}
+
+trait EwktWrite {
+    fn ewkt_tag(&self) -> &'static str;
+    fn ewkt_srid(&self) -> Option<i32>;
+    /// Coordinates in parentheses, or `EMPTY`.
+    fn write_ewkt_body(&self, out: &mut EwktWriter);
+    /// Geometry type with dimension suffix, followed by the body.
+    fn write_ewkt_tagged(&self, out: &mut EwktWriter) {
+        out.push_str(self.ewkt_tag());
+        out.push_str(out.dims);
+        out.push(' ');
+        self.write_ewkt_body(out);
+    }
+}
+
+/// EWKT marks 3DM geometries with an `M` suffix, while 3DZ and 4D are recognized by the
+/// number of coordinates.
+fn dims_suffix(point_type: PointType) -> &'static str {
+    if point_type == PointType::PointM {
+        "M"
+    } else {
+        ""
+    }
+}
+
+/// ISO WKT marks all dimensions, e.g. `POINT ZM (1 2 3 4)`.
+fn wkt_dims_suffix(point_type: PointType) -> &'static str {
+    match point_type {
+        PointType::Point => "",
+        PointType::PointZ => " Z",
+        PointType::PointM => " M",
+        PointType::PointZM => " ZM",
+    }
+}
+
+fn to_ewkt<G: EwktWrite>(geom: &G, point_type: PointType, format: FloatFormat) -> String {
+    let mut text = String::new();
+    if let Some(srid) = geom.ewkt_srid() {
+        let _ = write!(text, "SRID={};", srid);
+    }
+    let mut out = EwktWriter {
+        out: &mut text,
+        format,
+        dims: dims_suffix(point_type),
+        result: Ok(()),
+    };
+    geom.write_ewkt_tagged(&mut out);
+    text
+}
+
+fn write_wkt<G: EwktWrite>(
+    geom: &G,
+    point_type: PointType,
+    w: &mut dyn std::fmt::Write,
+) -> std::fmt::Result {
+    let mut out = EwktWriter {
+        out: w,
+        format: FloatFormat::Shortest,
+        dims: wkt_dims_suffix(point_type),
+        result: Ok(()),
+    };
+    geom.write_ewkt_tagged(&mut out);
+    out.result
+}
+
+/// Output in OGC WKT format, as returned by `ST_AsText`.
+///
+/// In contrast to [`to_ewkt`](GeometryT::to_ewkt), the SRID is not written and all
+/// dimensions are marked in the geometry type, e.g. `POINT Z (10 -20 100)`.
+pub trait WktWrite {
+    /// Append the WKT to `w`, e.g. an existing `String` buffer.
+    fn write_wkt<W: std::fmt::Write>(&self, w: &mut W) -> std::fmt::Result;
+
+    /// Format as WKT.
+    fn to_wkt(&self) -> String {
+        let mut text = String::new();
+        // Writing into a String never fails
+        let _ = self.write_wkt(&mut text);
+        text
+    }
+}
+
+fn write_coords<P: postgis::Point>(p: &P, out: &mut EwktWriter) {
+    out.coord(p.x());
+    out.push(' ');
+    out.coord(p.y());
+    if let Some(z) = p.opt_z() {
+        out.push(' ');
+        out.coord(z);
+    }
+    if let Some(m) = p.opt_m() {
+        out.push(' ');
+        out.coord(m);
+    }
+}
+
+/// Write `items` separated by `", "` in parentheses, or `EMPTY` without items.
+fn write_list<T>(
+    items: &[T],
+    out: &mut EwktWriter,
+    mut write_item: impl FnMut(&T, &mut EwktWriter),
+) {
+    if items.is_empty() {
+        out.push_str("EMPTY");
+        return;
+    }
+    out.push('(');
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            out.push_str(", ");
+        }
+        write_item(item, out);
+    }
+    out.push(')');
+}
+
+fn write_points<P: postgis::Point>(points: &[P], out: &mut EwktWriter) {
+    write_list(points, out, write_coords);
+}
+
+fn write_lines<P: PointCoords>(lines: &[LineStringT<P>], out: &mut EwktWriter) {
+    write_list(lines, out, |line, out| write_points(&line.points, out));
+}
+
+/// Points don't share a generic type, so they are formatted through this wrapper.
+struct PointEwkt<'a, P>(&'a P);
+
+impl<P: PointCoords> EwktWrite for PointEwkt<'_, P> {
+    fn ewkt_tag(&self) -> &'static str {
+        "POINT"
+    }
+    fn ewkt_srid(&self) -> Option<i32> {
+        self.0.opt_srid()
+    }
+    fn write_ewkt_body(&self, out: &mut EwktWriter) {
+        if self.0.x().is_nan() && self.0.y().is_nan() {
+            out.push_str("EMPTY");
+        } else {
+            out.push('(');
+            write_coords(self.0, out);
+            out.push(')');
+        }
+    }
+}
+
+macro_rules! impl_ewkt_for_point {
+    ($ptype:ident) => {
+        impl $ptype {
+            /// Format as EWKT with `SRID=...;` prefix if the SRID is set, e.g. `SRID=4326;POINT (10 -20)`.
+            pub fn to_ewkt(&self) -> String {
+                self.to_ewkt_with(FloatFormat::Shortest)
+            }
+
+            /// Format as EWKT with the given formatting of coordinates.
+            pub fn to_ewkt_with(&self, format: FloatFormat) -> String {
+                to_ewkt(&PointEwkt(self), Self::point_type(), format)
+            }
+        }
+
+        impl WktWrite for $ptype {
+            fn write_wkt<W: std::fmt::Write>(&self, w: &mut W) -> std::fmt::Result {
+                write_wkt(&PointEwkt(self), Self::point_type(), w)
+            }
+        }
+    };
+}
+
+impl_ewkt_for_point!(Point);
+impl_ewkt_for_point!(PointZ);
+impl_ewkt_for_point!(PointM);
+impl_ewkt_for_point!(PointZM);
+
+macro_rules! impl_ewkt_for_container {
+    ($geotype:ident, $tag:expr, |$geom:ident, $out:ident| $body:expr) => {
+        impl<P: PointCoords> EwktWrite for $geotype<P> {
+            fn ewkt_tag(&self) -> &'static str {
+                $tag
+            }
+            fn ewkt_srid(&self) -> Option<i32> {
+                self.srid
+            }
+            fn write_ewkt_body(&self, $out: &mut EwktWriter) {
+                let $geom = self;
+                $body
+            }
+        }
+
+        impl<P: PointCoords> $geotype<P> {
+            /// Format as EWKT with `SRID=...;` prefix if the SRID is set.
+            ///
+            /// The output uses the spacing of `ST_AsText`, e.g. `MULTIPOINT ((10 -20), (0 -0.5))`.
+            /// SRIDs of sub-geometries are not written.
+            pub fn to_ewkt(&self) -> String {
+                self.to_ewkt_with(FloatFormat::Shortest)
+            }
+
+            /// Format as EWKT with the given formatting of coordinates.
+            pub fn to_ewkt_with(&self, format: FloatFormat) -> String {
+                to_ewkt(self, P::point_type(), format)
+            }
+        }
+
+        impl<P: PointCoords> WktWrite for $geotype<P> {
+            fn write_wkt<W: std::fmt::Write>(&self, w: &mut W) -> std::fmt::Result {
+                write_wkt(self, P::point_type(), w)
+            }
+        }
+    };
+}
+
+impl_ewkt_for_container!(LineStringT, "LINESTRING", |g, out| write_points(
+    &g.points, out
+));
+impl_ewkt_for_container!(CircularStringT, "CIRCULARSTRING", |g, out| write_points(
+    &g.points, out
+));
+impl_ewkt_for_container!(PolygonT, "POLYGON", |g, out| write_lines(&g.rings, out));
+impl_ewkt_for_container!(MultiPointT, "MULTIPOINT", |g, out| write_list(
+    &g.points,
+    out,
+    |p, out| PointEwkt(p).write_ewkt_body(out)
+));
+impl_ewkt_for_container!(MultiLineStringT, "MULTILINESTRING", |g, out| write_lines(
+    &g.lines, out
+));
+impl_ewkt_for_container!(MultiPolygonT, "MULTIPOLYGON", |g, out| write_list(
+    &g.polygons,
+    out,
+    |poly, out| write_lines(&poly.rings, out)
+));
+impl_ewkt_for_container!(CompoundCurveT, "COMPOUNDCURVE", |g, out| write_list(
+    &g.segments,
+    out,
+    |segment, out| match segment {
+        CurveSegment::LineString(line) => write_points(&line.points, out),
+        CurveSegment::CircularString(curve) => curve.write_ewkt_tagged(out),
+    }
+));
+impl_ewkt_for_container!(CurvePolygonT, "CURVEPOLYGON", |g, out| write_list(
+    &g.rings,
+    out,
+    |ring, out| match ring {
+        Curve::LineString(line) => write_points(&line.points, out),
+        Curve::CircularString(curve) => curve.write_ewkt_tagged(out),
+        Curve::CompoundCurve(curve) => curve.write_ewkt_tagged(out),
+    }
+));
+impl_ewkt_for_container!(GeometryCollectionT, "GEOMETRYCOLLECTION", |g, out| {
+    write_list(&g.geometries, out, |geom, out| geom.write_ewkt_tagged(out))
+});
+
+impl<P: PointCoords> GeometryT<P> {
+    fn with_ewkt<R>(&self, f: impl FnOnce(&dyn EwktWrite) -> R) -> R {
+        match self {
+            GeometryT::Point(g) => f(&PointEwkt(g)),
+            GeometryT::LineString(g) => f(g),
+            GeometryT::Polygon(g) => f(g),
+            GeometryT::MultiPoint(g) => f(g),
+            GeometryT::MultiLineString(g) => f(g),
+            GeometryT::MultiPolygon(g) => f(g),
+            GeometryT::GeometryCollection(g) => f(g),
+            GeometryT::CircularString(g) => f(g),
+            GeometryT::CompoundCurve(g) => f(g),
+            GeometryT::CurvePolygon(g) => f(g),
+        }
+    }
+
+    /// Format as EWKT with `SRID=...;` prefix if the SRID is set, see [`LineStringT::to_ewkt`].
+    pub fn to_ewkt(&self) -> String {
+        self.to_ewkt_with(FloatFormat::Shortest)
+    }
+
+    /// Format as EWKT with the given formatting of coordinates.
+    ///
+    /// [`FloatFormat::Shortest`], used by [`to_ewkt`](Self::to_ewkt), gives the shortest text
+    /// which parses back to the same coordinates. `Fixed` and `Significant` reduce the
+    /// output size or match the output of a given precision, e.g. `ST_AsEWKT(geom, 6)`.
+    pub fn to_ewkt_with(&self, format: FloatFormat) -> String {
+        to_ewkt(self, P::point_type(), format)
+    }
+}
+
+impl<P: PointCoords> EwktWrite for GeometryT<P> {
+    fn ewkt_tag(&self) -> &'static str {
+        self.with_ewkt(|g| g.ewkt_tag())
+    }
+    fn ewkt_srid(&self) -> Option<i32> {
+        self.with_ewkt(|g| g.ewkt_srid())
+    }
+    fn write_ewkt_body(&self, out: &mut EwktWriter) {
+        self.with_ewkt(|g| g.write_ewkt_body(out))
+    }
+}
+
+impl<P: PointCoords> WktWrite for GeometryT<P> {
+    fn write_wkt<W: std::fmt::Write>(&self, w: &mut W) -> std::fmt::Result {
+        write_wkt(self, P::point_type(), w)
+    }
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_to_ewkt() {
+    assert_eq!(Point::new(10.0, -20.0, Some(4326)).to_ewkt(), "SRID=4326;POINT (10 -20)");
+    assert_eq!(Point::new(f64::NAN, f64::NAN, None).to_ewkt(), "POINT EMPTY");
+    assert_eq!(PointM::new(10.0, -20.0, 1.0, None).to_ewkt(), "POINTM (10 -20 1)");
+    assert_eq!(PointZM::new(10.0, -20.0, 100.0, 1.0, None).to_ewkt(), "POINT (10 -20 100 1)");
+
+    let line = LineStringT::<PointZ> {srid: Some(4326), points: vec![PointZ::new(10.0, -20.0, 100.0, None), PointZ::new(0.0, -0.5, 101.0, None)]};
+    assert_eq!(line.to_ewkt(), "SRID=4326;LINESTRING (10 -20 100, 0 -0.5 101)");
+    assert_eq!(LineStringT::<Point>::new().to_ewkt(), "LINESTRING EMPTY");
+
+    let multi = MultiPointT::<PointZ> {srid: Some(4326), points: line.points.clone()};
+    assert_eq!(multi.to_ewkt(), "SRID=4326;MULTIPOINT ((10 -20 100), (0 -0.5 101))");
+    // SELECT ST_AsEWKT(ST_GeomFromEWKT('MULTIPOINT (EMPTY, 1 2)'))
+    let ewkb = hex_to_vec("0104000000020000000101000000000000000000F87F000000000000F87F0101000000000000000000F03F0000000000000040");
+    let multi = MultiPointT::<Point>::read_ewkb(&mut ewkb.as_slice()).unwrap();
+    assert_eq!(multi.to_ewkt(), "MULTIPOINT (EMPTY, (1 2))");
+    assert_eq!(multi.to_wkt(), "MULTIPOINT (EMPTY, (1 2))");
+
+    let poly1 = PolygonT::<Point> {srid: None, rings: vec![line_xy(&[(0., 0.), (2., 0.), (2., 2.), (0., 2.), (0., 0.)])]};
+    let poly2 = PolygonT::<Point> {srid: None, rings: vec![line_xy(&[(10., 10.), (-2., 10.), (-2., -2.), (10., -2.), (10., 10.)])]};
+    let multipoly = MultiPolygonT::<Point> {srid: Some(4326), polygons: vec![poly1.clone(), poly2]};
+    assert_eq!(multipoly.to_ewkt(), "SRID=4326;MULTIPOLYGON (((0 0, 2 0, 2 2, 0 2, 0 0)), ((10 10, -2 10, -2 -2, 10 -2, 10 10)))");
+    assert_eq!(poly1.to_ewkt(), "POLYGON ((0 0, 2 0, 2 2, 0 2, 0 0))");
+
+    let collection = GeometryCollectionT::<Point> {srid: Some(4326), geometries: vec![
+        GeometryT::LineString(line_xy(&[(10., -20.), (0., -0.5)])),
+        GeometryT::MultiPolygon(MultiPolygonT {srid: None, polygons: vec![poly1]}),
+        GeometryT::GeometryCollection(GeometryCollectionT::new()),
+    ]};
+    assert_eq!(GeometryT::GeometryCollection(collection).to_ewkt(),
+               "SRID=4326;GEOMETRYCOLLECTION (LINESTRING (10 -20, 0 -0.5), MULTIPOLYGON (((0 0, 2 0, 2 2, 0 2, 0 0))), GEOMETRYCOLLECTION EMPTY)");
+
+    let curve = CompoundCurveT::<PointM> {srid: None, segments: vec![
+        CurveSegment::CircularString(CircularStringT {srid: None, points: vec![PointM::new(0., 0., 1., None), PointM::new(1., 1., 2., None), PointM::new(2., 0., 3., None)]}),
+        CurveSegment::LineString(LineStringT {srid: None, points: vec![PointM::new(2., 0., 3., None), PointM::new(3., 0., 4., None)]}),
+    ]};
+    assert_eq!(curve.to_ewkt(), "COMPOUNDCURVEM (CIRCULARSTRINGM (0 0 1, 1 1 2, 2 0 3), (2 0 3, 3 0 4))");
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_to_ewkt_with() {
+    let point = PointZ::new(10.0, -0.123456789, 1234.5678, Some(4326));
+    assert_eq!(point.to_ewkt_with(FloatFormat::Shortest), "SRID=4326;POINT (10 -0.123456789 1234.5678)");
+    assert_eq!(point.to_ewkt_with(FloatFormat::Fixed(2)), "SRID=4326;POINT (10.00 -0.12 1234.57)");
+    assert_eq!(point.to_ewkt_with(FloatFormat::Fixed(0)), "SRID=4326;POINT (10 -0 1235)");
+    assert_eq!(point.to_ewkt_with(FloatFormat::Significant(3)), "SRID=4326;POINT (10 -0.123 1230)");
+    assert_eq!(point.to_ewkt_with(FloatFormat::Significant(0)), "SRID=4326;POINT (10 -0.1 1000)");
+
+    let line = LineStringT::<Point> {srid: None, points: vec![Point::new(0.1, 2.0 / 3.0, None)]};
+    assert_eq!(line.to_ewkt(), "LINESTRING (0.1 0.6666666666666666)");
+    assert_eq!(GeometryT::LineString(line).to_ewkt_with(FloatFormat::Fixed(3)), "LINESTRING (0.100 0.667)");
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_write_wkt() {
+    assert_eq!(Point::new(10.0, -20.0, Some(4326)).to_wkt(), "POINT (10 -20)");
+    assert_eq!(PointZ::new(10.0, -20.0, 100.0, None).to_wkt(), "POINT Z (10 -20 100)");
+    assert_eq!(PointM::new(f64::NAN, f64::NAN, f64::NAN, None).to_wkt(), "POINT M EMPTY");
+
+    let curve = CompoundCurveT::<PointZM> {srid: Some(4326), segments: vec![
+        CurveSegment::CircularString(CircularStringT {srid: None, points: vec![PointZM::new(0., 0., 1., 2., None), PointZM::new(1., 1., 1., 2., None), PointZM::new(2., 0., 1., 2., None)]}),
+        CurveSegment::LineString(LineStringT {srid: None, points: vec![PointZM::new(2., 0., 1., 2., None), PointZM::new(3., 0., 1., 2., None)]}),
+    ]};
+    assert_eq!(curve.to_wkt(), "COMPOUNDCURVE ZM (CIRCULARSTRING ZM (0 0 1 2, 1 1 1 2, 2 0 1 2), (2 0 1 2, 3 0 1 2))");
+
+    // Appending into an existing buffer
+    let line = LineStringT::<Point> {srid: Some(4326), points: vec![Point::new(0.5, 1.0, None), Point::new(2.0, 3.0, None)]};
+    let mut buf = String::from("geom=");
+    GeometryT::LineString(line).write_wkt(&mut buf).unwrap();
+    assert_eq!(buf, "geom=LINESTRING (0.5 1, 2 3)");
+
+    // Errors of the writer are returned
+    struct Full(usize);
+    impl std::fmt::Write for Full {
+        fn write_str(&mut self, s: &str) -> std::fmt::Result {
+            self.0 = self.0.checked_sub(s.len()).ok_or(std::fmt::Error)?;
+            Ok(())
+        }
+    }
+    assert!(Point::new(10.0, -20.0, None).write_wkt(&mut Full(100)).is_ok());
+    assert!(Point::new(10.0, -20.0, None).write_wkt(&mut Full(8)).is_err());
+}
@@ -0,0 +1,217 @@
+//! Conversion into `geo_types` geometries.
+//!
+//! Coordinates are 2D, Z and M values and SRIDs are dropped. Nested geometry collections
+//! stay nested, as `geo_types::GeometryCollection` can contain collections. Curves have no
+//! `geo_types` equivalent and fail to convert, linearize them with `ST_CurveToLine` first.
+
+use crate::ewkb::parts::type_name;
+use crate::ewkb::*;
+
+fn coord<P: postgis::Point>(p: &P) -> geo_types::Coord<f64> {
+    geo_types::Coord { x: p.x(), y: p.y() }
+}
+
+fn line_string<P: postgis::Point>(points: &[P]) -> geo_types::LineString<f64> {
+    geo_types::LineString(points.iter().map(coord).collect())
+}
+
+/// The first ring is the exterior, a polygon without rings has an empty exterior.
+fn polygon<P: postgis::Point + EwkbRead>(polygon: &PolygonT<P>) -> geo_types::Polygon<f64> {
+    let mut rings = polygon.rings.iter().map(|r| line_string(&r.points));
+    let exterior = rings
+        .next()
+        .unwrap_or_else(|| geo_types::LineString(Vec::new()));
+    geo_types::Polygon::new(exterior, rings.collect())
+}
+
+fn multi_point<P: postgis::Point>(points: &[P]) -> geo_types::MultiPoint<f64> {
+    geo_types::MultiPoint(points.iter().map(|p| geo_types::Point(coord(p))).collect())
+}
+
+fn multi_line_string<P>(lines: &[LineStringT<P>]) -> geo_types::MultiLineString<f64>
+where
+    P: postgis::Point + EwkbRead,
+{
+    geo_types::MultiLineString(lines.iter().map(|l| line_string(&l.points)).collect())
+}
+
+fn multi_polygon<P>(polygons: &[PolygonT<P>]) -> geo_types::MultiPolygon<f64>
+where
+    P: postgis::Point + EwkbRead,
+{
+    geo_types::MultiPolygon(polygons.iter().map(polygon).collect())
+}
+
+fn geometry_collection<P: PointCoords>(
+    collection: &GeometryCollectionT<P>,
+) -> Result<geo_types::GeometryCollection<f64>, Error> {
+    collection
+        .geometries
+        .iter()
+        .map(GeometryT::to_geo)
+        .collect::<Result<_, _>>()
+        .map(geo_types::GeometryCollection)
+}
+
+macro_rules! impl_from_point {
+    ($ptype:ident) => {
+        impl From<$ptype> for geo_types::Point<f64> {
+            fn from(p: $ptype) -> Self {
+                geo_types::Point(coord(&p))
+            }
+        }
+    };
+}
+
+impl_from_point!(Point);
+impl_from_point!(PointZ);
+impl_from_point!(PointM);
+impl_from_point!(PointZM);
+
+impl<P: postgis::Point + EwkbRead> From<LineStringT<P>> for geo_types::LineString<f64> {
+    fn from(line: LineStringT<P>) -> Self {
+        line_string(&line.points)
+    }
+}
+
+/// The first ring is the exterior, a polygon without rings has an empty exterior.
+impl<P: postgis::Point + EwkbRead> From<PolygonT<P>> for geo_types::Polygon<f64> {
+    fn from(value: PolygonT<P>) -> Self {
+        polygon(&value)
+    }
+}
+
+impl<P: postgis::Point + EwkbRead> From<MultiPointT<P>> for geo_types::MultiPoint<f64> {
+    fn from(multi: MultiPointT<P>) -> Self {
+        multi_point(&multi.points)
+    }
+}
+
+impl<P: postgis::Point + EwkbRead> From<MultiLineStringT<P>> for geo_types::MultiLineString<f64> {
+    fn from(multi: MultiLineStringT<P>) -> Self {
+        multi_line_string(&multi.lines)
+    }
+}
+
+impl<P: postgis::Point + EwkbRead> From<MultiPolygonT<P>> for geo_types::MultiPolygon<f64> {
+    fn from(multi: MultiPolygonT<P>) -> Self {
+        multi_polygon(&multi.polygons)
+    }
+}
+
+/// Fails with `Error::Other` for curves, see [`GeometryT::to_geo`].
+impl<P: PointCoords> TryFrom<GeometryT<P>> for geo_types::Geometry<f64> {
+    type Error = Error;
+
+    fn try_from(geom: GeometryT<P>) -> Result<Self, Error> {
+        geom.to_geo()
+    }
+}
+
+/// Converts each member, nested collections become nested `geo_types` collections.
+/// Fails with `Error::Other` if a member is a curve.
+impl<P: PointCoords> TryFrom<GeometryCollectionT<P>> for geo_types::GeometryCollection<f64> {
+    type Error = Error;
+
+    fn try_from(collection: GeometryCollectionT<P>) -> Result<Self, Error> {
+        geometry_collection(&collection)
+    }
+}
+
+impl<P: PointCoords> GeometryT<P> {
+    /// Convert into the matching `geo_types` geometry, e.g. to use `geo` algorithms.
+    ///
+    /// Z and M values and SRIDs are dropped. Collections become
+    /// `geo_types::Geometry::GeometryCollection`, keeping nested collections. Curves, also
+    /// inside collections, fail with `Error::Other` as `geo_types` has no arcs. Linearize
+    /// them with `ST_CurveToLine` in the database first.
+    pub fn to_geo(&self) -> Result<geo_types::Geometry<f64>, Error> {
+        Ok(match self {
+            GeometryT::Point(p) => geo_types::Geometry::Point(geo_types::Point(coord(p))),
+            GeometryT::LineString(g) => geo_types::Geometry::LineString(line_string(&g.points)),
+            GeometryT::Polygon(g) => geo_types::Geometry::Polygon(polygon(g)),
+            GeometryT::MultiPoint(g) => geo_types::Geometry::MultiPoint(multi_point(&g.points)),
+            GeometryT::MultiLineString(g) => {
+                geo_types::Geometry::MultiLineString(multi_line_string(&g.lines))
+            }
+            GeometryT::MultiPolygon(g) => {
+                geo_types::Geometry::MultiPolygon(multi_polygon(&g.polygons))
+            }
+            GeometryT::GeometryCollection(g) => {
+                geo_types::Geometry::GeometryCollection(geometry_collection(g)?)
+            }
+            GeometryT::CircularString(_)
+            | GeometryT::CompoundCurve(_)
+            | GeometryT::CurvePolygon(_) => {
+                return Err(Error::Other(format!(
+                    "{} can't be converted to geo_types",
+                    type_name(self)
+                )));
+            }
+        })
+    }
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_geo_types_collection() {
+    let p = |x, y| PointZ::new(x, y, 9., Some(4326));
+    let square = LineStringT::<PointZ> {srid: None, points: vec![p(0., 0.), p(1., 0.), p(1., 1.), p(0., 0.)]};
+    let inner = GeometryCollectionT::<PointZ> {srid: None, geometries: vec![GeometryT::Point(p(5., 6.))]};
+    let collection = GeometryCollectionT::<PointZ> {srid: Some(4326), geometries: vec![
+        GeometryT::Point(p(1., 2.)),
+        GeometryT::LineString(square.clone()),
+        GeometryT::Polygon(PolygonT {srid: None, rings: vec![square.clone()]}),
+        GeometryT::MultiLineString(MultiLineStringT {srid: None, lines: vec![square]}),
+        GeometryT::GeometryCollection(inner),
+    ]};
+    let converted = geo_types::GeometryCollection::try_from(collection).unwrap();
+    assert_eq!(converted.0.len(), 5);
+    assert_eq!(converted.0[0], geo_types::Geometry::Point(geo_types::Point::new(1., 2.)));
+    let ring = geo_types::LineString::from(vec![(0., 0.), (1., 0.), (1., 1.), (0., 0.)]);
+    assert_eq!(converted.0[1], geo_types::Geometry::LineString(ring.clone()));
+    assert_eq!(converted.0[2], geo_types::Geometry::Polygon(geo_types::Polygon::new(ring, vec![])));
+    // Nested collections stay nested
+    assert_eq!(converted.0[4], geo_types::Geometry::GeometryCollection(geo_types::GeometryCollection(vec![
+        geo_types::Geometry::Point(geo_types::Point::new(5., 6.))])));
+
+    assert_eq!(geo_types::Polygon::from(PolygonT::<Point>::new()).exterior().0.len(), 0);
+    assert_eq!(geo_types::Point::from(PointZM::new(1., 2., 3., 4., None)), geo_types::Point::new(1., 2.));
+
+    let curved = GeometryCollectionT::<Point> {srid: None, geometries: vec![
+        GeometryT::CircularString(CircularStringT {srid: None, points: vec![]})]};
+    let err = geo_types::GeometryCollection::try_from(curved).unwrap_err();
+    assert_eq!(err.to_string(), r#"Other("CircularString can't be converted to geo_types")"#);
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_to_geo() {
+    let p = |x, y| Point::new(x, y, Some(4326));
+    let line = LineStringT::<Point> {srid: Some(4326), points: vec![p(0., 0.), p(2., 0.), p(2., 2.), p(0., 0.)]};
+    let ring = geo_types::LineString::from(vec![(0., 0.), (2., 0.), (2., 2.), (0., 0.)]);
+    let geom = GeometryT::GeometryCollection(GeometryCollectionT {srid: Some(4326), geometries: vec![
+        GeometryT::Point(p(1., 2.)),
+        GeometryT::MultiPoint(MultiPointT {srid: None, points: vec![p(3., 4.)]}),
+        GeometryT::MultiPolygon(MultiPolygonT {srid: None, polygons: vec![PolygonT {srid: None, rings: vec![line.clone()]}]}),
+        GeometryT::GeometryCollection(GeometryCollectionT::new()),
+    ]});
+    assert_eq!(geom.to_geo().unwrap(), geo_types::Geometry::GeometryCollection(geo_types::GeometryCollection(vec![
+        geo_types::Geometry::Point(geo_types::Point::new(1., 2.)),
+        geo_types::Geometry::MultiPoint(geo_types::MultiPoint(vec![geo_types::Point::new(3., 4.)])),
+        geo_types::Geometry::MultiPolygon(geo_types::MultiPolygon(vec![geo_types::Polygon::new(ring, vec![])])),
+        geo_types::Geometry::GeometryCollection(geo_types::GeometryCollection(vec![])),
+    ])));
+    assert_eq!(geo_types::Geometry::try_from(geom.clone()).unwrap(), geom.to_geo().unwrap());
+
+    // Curves are rejected like with `TryFrom`, also nested in collections
+    let arc = GeometryT::CircularString(CircularStringT::<Point> {srid: None, points: vec![p(0., 0.), p(1., 1.), p(2., 0.)]});
+    assert_eq!(arc.to_geo().unwrap_err().to_string(), r#"Other("CircularString can't be converted to geo_types")"#);
+    let collection = GeometryT::GeometryCollection(GeometryCollectionT {srid: None, geometries: vec![GeometryT::Point(p(1., 2.)), arc]});
+    assert!(collection.to_geo().is_err());
+    assert!(geo_types::Geometry::try_from(collection).is_err());
+
+    // Z and M are dropped
+    let point = GeometryT::Point(PointZM::new(1., 2., 3., 4., None));
+    assert_eq!(point.to_geo().unwrap(), geo_types::Geometry::Point(geo_types::Point::new(1., 2.)));
+}
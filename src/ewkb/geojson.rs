@@ -0,0 +1,163 @@
+//! Conversion from [GeoJSON](https://www.rfc-editor.org/rfc/rfc7946) geometry objects.
+//!
+//! Useful for GeoJSON stored in `json` or `jsonb` columns and read as `serde_json::Value`,
+//! e.g. through `postgres_types::Json<serde_json::Value>`:
+//!
+//! ```rust,ignore
+//! let Json(value): Json<serde_json::Value> = row.get("shape");
+//! let geom = ewkb::Geometry::try_from(value)?;
+//! ```
+
+use crate::ewkb::*;
+use serde_json::Value;
+
+/// SRID of GeoJSON coordinates, which are always WGS 84 longitude/latitude.
+const GEOJSON_SRID: Option<i32> = Some(4326);
+
+fn invalid(msg: impl fmt::Display) -> Error {
+    Error::Read(format!("invalid GeoJSON: {}", msg))
+}
+
+fn member<'a>(obj: &'a Value, key: &str) -> Result<&'a Value, Error> {
+    obj.get(key)
+        .ok_or_else(|| invalid(format_args!("missing member {:?}", key)))
+}
+
+fn array(v: &Value) -> Result<&Vec<Value>, Error> {
+    v.as_array()
+        .ok_or_else(|| invalid(format_args!("expected an array, found {}", v)))
+}
+
+/// Read a position `[x, y]` or `[x, y, z]`, further elements are ignored.
+fn position<P: PointCoords>(v: &Value) -> Result<P, Error> {
+    let coords = array(v)?
+        .iter()
+        .map(|c| {
+            c.as_f64()
+                .ok_or_else(|| invalid(format_args!("expected a number, found {}", c)))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if coords.len() < 2 {
+        return Err(invalid(format_args!(
+            "position with {} coordinates",
+            coords.len()
+        )));
+    }
+    Ok(P::new_from_opt_vals(
+        coords[0],
+        coords[1],
+        coords.get(2).copied(),
+        None,
+        GEOJSON_SRID,
+    ))
+}
+
+fn line<P: PointCoords>(v: &Value) -> Result<LineStringT<P>, Error> {
+    Ok(LineStringT {
+        points: array(v)?.iter().map(position).collect::<Result<_, _>>()?,
+        srid: GEOJSON_SRID,
+    })
+}
+
+fn polygon<P: PointCoords>(v: &Value) -> Result<PolygonT<P>, Error> {
+    Ok(PolygonT {
+        rings: array(v)?.iter().map(line).collect::<Result<_, _>>()?,
+        srid: GEOJSON_SRID,
+    })
+}
+
+fn geometry<P: PointCoords>(v: &Value) -> Result<GeometryT<P>, Error> {
+    let geom_type = member(v, "type")?;
+    if geom_type == "GeometryCollection" {
+        let geometries = array(member(v, "geometries")?)?;
+        return Ok(GeometryT::GeometryCollection(GeometryCollectionT {
+            geometries: geometries.iter().map(geometry).collect::<Result<_, _>>()?,
+            srid: GEOJSON_SRID,
+        }));
+    }
+    let coords = member(v, "coordinates")?;
+    let items = || array(coords).map(|items| items.iter());
+    Ok(match geom_type.as_str() {
+        Some("Point") if array(coords)?.is_empty() => GeometryT::Point(P::new_from_opt_vals(
+            f64::NAN,
+            f64::NAN,
+            None,
+            None,
+            GEOJSON_SRID,
+        )),
+        Some("Point") => GeometryT::Point(position(coords)?),
+        Some("LineString") => GeometryT::LineString(line(coords)?),
+        Some("Polygon") => GeometryT::Polygon(polygon(coords)?),
+        Some("MultiPoint") => GeometryT::MultiPoint(MultiPointT {
+            points: items()?.map(position).collect::<Result<_, _>>()?,
+            srid: GEOJSON_SRID,
+        }),
+        Some("MultiLineString") => GeometryT::MultiLineString(MultiLineStringT {
+            lines: items()?.map(line).collect::<Result<_, _>>()?,
+            srid: GEOJSON_SRID,
+        }),
+        Some("MultiPolygon") => GeometryT::MultiPolygon(MultiPolygonT {
+            polygons: items()?.map(polygon).collect::<Result<_, _>>()?,
+            srid: GEOJSON_SRID,
+        }),
+        _ => {
+            return Err(invalid(format_args!(
+                "unsupported geometry type {}",
+                geom_type
+            )));
+        }
+    })
+}
+
+/// Convert a GeoJSON geometry object, like `ST_GeomFromGeoJSON`.
+///
+/// All parts get SRID 4326, the third coordinate of a position becomes Z. Features and
+/// feature collections are not accepted, convert their `geometry` member instead. Fails
+/// with `Error::Read` if the value isn't a valid GeoJSON geometry.
+impl<P: PointCoords> TryFrom<&Value> for GeometryT<P> {
+    type Error = Error;
+
+    fn try_from(value: &Value) -> Result<Self, Error> {
+        geometry(value)
+    }
+}
+
+/// See the implementation for `&Value`.
+impl<P: PointCoords> TryFrom<Value> for GeometryT<P> {
+    type Error = Error;
+
+    fn try_from(value: Value) -> Result<Self, Error> {
+        geometry(&value)
+    }
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_geojson() {
+    use serde_json::json;
+
+    let geom = Geometry::try_from(json!({"type": "Point", "coordinates": [10.0, -20.0]})).unwrap();
+    assert_eq!(geom.to_ewkt(), "SRID=4326;POINT (10 -20)");
+    let geom = GeometryT::<PointZ>::try_from(&json!({"type": "LineString", "coordinates": [[0, 0, 1], [2, 0, 3]]})).unwrap();
+    assert_eq!(geom.to_ewkt(), "SRID=4326;LINESTRING (0 0 1, 2 0 3)");
+    let GeometryT::LineString(line) = geom else { unreachable!() };
+    assert_eq!(line.points[1], PointZ::new(2., 0., 3., Some(4326)));
+
+    let value = json!({"type": "GeometryCollection", "geometries": [
+        {"type": "Polygon", "coordinates": [[[0, 0], [1, 0], [1, 1], [0, 0]]]},
+        {"type": "MultiPoint", "coordinates": [[1, 2], [3, 4]]},
+        {"type": "MultiLineString", "coordinates": [[[0, 0], [1, 1]]]},
+        {"type": "MultiPolygon", "coordinates": []},
+        {"type": "Point", "coordinates": []},
+    ]});
+    let geom = Geometry::try_from(value).unwrap();
+    assert_eq!(geom.to_ewkt(), "SRID=4326;GEOMETRYCOLLECTION (POLYGON ((0 0, 1 0, 1 1, 0 0)), MULTIPOINT ((1 2), (3 4)), \
+                                MULTILINESTRING ((0 0, 1 1)), MULTIPOLYGON EMPTY, POINT EMPTY)");
+
+    let err = |value: Value| Geometry::try_from(value).unwrap_err().to_string();
+    assert_eq!(err(json!({"type": "Point", "coordinates": [1]})), r#"Read("invalid GeoJSON: position with 1 coordinates")"#);
+    assert_eq!(err(json!({"type": "Point", "coordinates": ["1", 2]})), r#"Read("invalid GeoJSON: expected a number, found \"1\"")"#);
+    assert_eq!(err(json!({"type": "Feature", "geometry": null})), r#"Read("invalid GeoJSON: missing member \"coordinates\"")"#);
+    assert_eq!(err(json!({"type": "Circle", "coordinates": [0, 0]})), r#"Read("invalid GeoJSON: unsupported geometry type \"Circle\"")"#);
+    assert_eq!(err(json!({"coordinates": [0, 0]})), r#"Read("invalid GeoJSON: missing member \"type\"")"#);
+}
@@ -2,11 +2,12 @@ use crate::ewkb::*;
 
 macro_rules! geometry_container_type {
     // geometries containing lines and polygons
-    ($geotypetrait:ident for $geotype:ident contains $itemtype:ident named $itemname:ident) => {
+    ($geotypetrait:ident for $geotype:ident contains $itemtype:ident named $itemname:ident, $itemname_mut:ident) => {
         #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
         #[derive(PartialEq, Clone, Debug)]
         pub struct $geotype<P: postgis::Point + EwkbRead> {
             pub $itemname: Vec<$itemtype<P>>,
+            #[cfg_attr(feature = "serde", serde(default))]
             pub srid: Option<i32>,
         }
 
@@ -29,6 +30,11 @@ macro_rules! geometry_container_type {
                     srid: None,
                 }
             }
+
+            /// Mutable iterator for editing the parts in place.
+            pub fn $itemname_mut(&mut self) -> IterMut<'_, $itemtype<P>> {
+                self.$itemname.iter_mut()
+            }
         }
 
         impl<P> FromIterator<$itemtype<P>> for $geotype<P>
@@ -75,11 +81,14 @@ macro_rules! impl_read_for_geometry_container_type {
                 is_be: bool,
                 type_id: u32,
                 srid: Option<i32>,
+                ctx: &mut ReadContext,
             ) -> Result<Self, Error> {
                 let mut $itemname: Vec<$itemtype<P>> = vec![];
                 let size = read_u32(raw, is_be)? as usize;
-                for _ in 0..size {
-                    $itemname.push($itemtype::read_ewkb_body(raw, is_be, type_id, srid)?);
+                for i in 0..size {
+                    let item = $itemtype::read_ewkb_body(raw, is_be, type_id, srid, ctx)
+                        .map_err(|e| ctx.at(stringify!($itemname), i, e))?;
+                    $itemname.push(item);
                 }
                 Ok($geotype::<P> {
                     $itemname: $itemname,
@@ -101,11 +110,14 @@ macro_rules! impl_read_for_geometry_container_type {
                 is_be: bool,
                 _type_id: u32,
                 srid: Option<i32>,
+                ctx: &mut ReadContext,
             ) -> Result<Self, Error> {
                 let mut $itemname: Vec<$itemtype<P>> = vec![];
                 let size = read_u32(raw, is_be)? as usize;
-                for _ in 0..size {
-                    $itemname.push($itemtype::read_ewkb(raw)?);
+                for i in 0..size {
+                    let item = $itemtype::read_ewkb_with_context(raw, ctx)
+                        .map_err(|e| ctx.at(stringify!($itemname), i, e))?;
+                    $itemname.push(item);
                 }
                 Ok($geotype::<P> {
                     $itemname: $itemname,
@@ -117,7 +129,7 @@ macro_rules! impl_read_for_geometry_container_type {
 }
 
 macro_rules! geometry_container_write {
-    ($geotypetrait:ident and $asewkbtype:ident for $geotype:ident to $ewkbtype:ident with type code $typecode:expr, contains $ewkbitemtype:ident, $itemtype:ident as $itemtypetrait:ident named $itemname:ident, command $writecmd:ident) => {
+    ($geotypetrait:ident and $asewkbtype:ident for $geotype:ident to $ewkbtype:ident with type code $typecode:expr, contains $ewkbitemtype:ident, $itemtype:ident as $itemtypetrait:ident named $itemname:ident, command $writecmd:ident, size $sizecmd:ident) => {
         pub struct $ewkbtype<'a, P, I, T, J>
         where
             P: 'a + postgis::Point,
@@ -183,6 +195,22 @@ macro_rules! geometry_container_write {
                 }
                 Ok(())
             }
+
+            fn ewkb_body_size_hint(&self) -> usize {
+                let items: usize = self
+                    .geom
+                    .$itemname()
+                    .map(|geom| {
+                        let wkb = $ewkbitemtype {
+                            geom,
+                            srid: None,
+                            point_type: self.point_type,
+                        };
+                        wkb.$sizecmd()
+                    })
+                    .sum();
+                4 + items
+            }
         }
 
         impl<'a, P> $asewkbtype<'a> for $geotype<P>
@@ -204,7 +232,7 @@ macro_rules! geometry_container_write {
             }
         }
     };
-    (multipoly $geotypetrait:ident and $asewkbtype:ident for $geotype:ident to $ewkbtype:ident with type code $typecode:expr, contains $ewkbitemtype:ident, $itemtype:ident as $itemtypetrait:ident named $itemname:ident, command $writecmd:ident) => {
+    (multipoly $geotypetrait:ident and $asewkbtype:ident for $geotype:ident to $ewkbtype:ident with type code $typecode:expr, contains $ewkbitemtype:ident, $itemtype:ident as $itemtypetrait:ident named $itemname:ident, command $writecmd:ident, size $sizecmd:ident) => {
         pub struct $ewkbtype<'a, P, I, L, K, T, J>
         where
             P: 'a + postgis::Point,
@@ -288,6 +316,22 @@ macro_rules! geometry_container_write {
                 }
                 Ok(())
             }
+
+            fn ewkb_body_size_hint(&self) -> usize {
+                let items: usize = self
+                    .geom
+                    .$itemname()
+                    .map(|geom| {
+                        let wkb = $ewkbitemtype {
+                            geom,
+                            srid: None,
+                            point_type: self.point_type,
+                        };
+                        wkb.$sizecmd()
+                    })
+                    .sum();
+                4 + items
+            }
         }
 
         impl<'a, P> $asewkbtype<'a> for $geotype<P>
@@ -321,12 +365,12 @@ macro_rules! geometry_container_write {
     };
 }
 
-geometry_container_type!(Polygon for PolygonT contains LineStringT named rings);
+geometry_container_type!(Polygon for PolygonT contains LineStringT named rings, rings_mut);
 impl_read_for_geometry_container_type!(singletype PolygonT contains LineStringT named rings);
 geometry_container_write!(Polygon and AsEwkbPolygon for PolygonT
                           to EwkbPolygon with type code 0x03,
                           contains EwkbLineString,LineStringT as LineString named rings,
-                          command write_ewkb_body);
+                          command write_ewkb_body, size ewkb_body_size_hint);
 
 /// OGC Polygon type
 pub type Polygon = PolygonT<Point>;
@@ -337,12 +381,12 @@ pub type PolygonM = PolygonT<PointM>;
 /// OGC PolygonZM type
 pub type PolygonZM = PolygonT<PointZM>;
 
-geometry_container_type!(MultiLineString for MultiLineStringT contains LineStringT named lines);
+geometry_container_type!(MultiLineString for MultiLineStringT contains LineStringT named lines, lines_mut);
 impl_read_for_geometry_container_type!(multitype MultiLineStringT contains LineStringT named lines);
 geometry_container_write!(MultiLineString and AsEwkbMultiLineString for MultiLineStringT
                           to EwkbMultiLineString with type code 0x05,
                           contains EwkbLineString,LineStringT as LineString named lines,
-                          command write_ewkb);
+                          command write_ewkb, size ewkb_size_hint);
 
 /// OGC MultiLineString type
 pub type MultiLineString = MultiLineStringT<Point>;
@@ -353,12 +397,12 @@ pub type MultiLineStringM = MultiLineStringT<PointM>;
 /// OGC MultiLineStringZM type
 pub type MultiLineStringZM = MultiLineStringT<PointZM>;
 
-geometry_container_type!(MultiPolygon for MultiPolygonT contains PolygonT named polygons);
+geometry_container_type!(MultiPolygon for MultiPolygonT contains PolygonT named polygons, polygons_mut);
 impl_read_for_geometry_container_type!(multitype MultiPolygonT contains PolygonT named polygons);
 geometry_container_write!(multipoly MultiPolygon and AsEwkbMultiPolygon for MultiPolygonT
                           to EwkbMultiPolygon with type code 0x06,
                           contains EwkbPolygon,PolygonT as Polygon named polygons,
-                          command write_ewkb);
+                          command write_ewkb, size ewkb_size_hint);
 
 /// OGC MultiPolygon type
 pub type MultiPolygon = MultiPolygonT<Point>;
@@ -369,6 +413,82 @@ pub type MultiPolygonM = MultiPolygonT<PointM>;
 /// OGC MultiPolygonZM type
 pub type MultiPolygonZM = MultiPolygonT<PointZM>;
 
+impl<P> MultiPolygonT<P>
+where
+    P: postgis::Point + EwkbRead,
+{
+    /// Iterate over all points as `(polygon index, ring index, point)`, exterior ring first.
+    pub fn indexed_points(&self) -> impl Iterator<Item = (usize, usize, &P)> {
+        self.polygons.iter().enumerate().flat_map(|(i, polygon)| {
+            polygon
+                .rings
+                .iter()
+                .enumerate()
+                .flat_map(move |(j, ring)| ring.points.iter().map(move |p| (i, j, p)))
+        })
+    }
+}
+
+/// EWKB writer for a slice of polygons as MultiPolygon, without copying them into a
+/// [`MultiPolygonT`].
+pub struct EwkbMultiPolygonFromSlice<'a, P: postgis::Point + EwkbRead> {
+    pub polygons: &'a [PolygonT<P>],
+    pub srid: Option<i32>,
+    pub point_type: PointType,
+}
+
+impl<'a, P: postgis::Point + EwkbRead> EwkbMultiPolygonFromSlice<'a, P> {
+    /// Writer for `polygons` with the point type of `P`.
+    pub fn new(polygons: &'a [PolygonT<P>], srid: Option<i32>) -> Self {
+        EwkbMultiPolygonFromSlice {
+            polygons,
+            srid,
+            point_type: P::point_type(),
+        }
+    }
+
+    fn polygon_writer(&self, geom: &'a PolygonT<P>) -> impl EwkbWrite + 'a {
+        EwkbPolygon {
+            geom,
+            srid: None,
+            point_type: self.point_type,
+        }
+    }
+}
+
+impl<P: postgis::Point + EwkbRead> fmt::Debug for EwkbMultiPolygonFromSlice<'_, P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "EwkbMultiPolygonFromSlice")
+    }
+}
+
+impl<P: postgis::Point + EwkbRead> EwkbWrite for EwkbMultiPolygonFromSlice<'_, P> {
+    fn opt_srid(&self) -> Option<i32> {
+        self.srid
+    }
+
+    fn type_id(&self) -> u32 {
+        0x06 | Self::wkb_type_id(&self.point_type, self.srid)
+    }
+
+    fn write_ewkb_body<W: Write + ?Sized>(&self, w: &mut W) -> Result<(), Error> {
+        w.write_u32::<LittleEndian>(self.polygons.len() as u32)?;
+        for geom in self.polygons {
+            self.polygon_writer(geom).write_ewkb(w)?;
+        }
+        Ok(())
+    }
+
+    fn ewkb_body_size_hint(&self) -> usize {
+        let items: usize = self
+            .polygons
+            .iter()
+            .map(|geom| self.polygon_writer(geom).ewkb_size_hint())
+            .sum();
+        4 + items
+    }
+}
+
 /// Generic Geometry Data Type
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Debug)]
@@ -380,6 +500,32 @@ pub enum GeometryT<P: postgis::Point + EwkbRead> {
     MultiLineString(MultiLineStringT<P>),
     MultiPolygon(MultiPolygonT<P>),
     GeometryCollection(GeometryCollectionT<P>),
+    CircularString(CircularStringT<P>),
+    CompoundCurve(CompoundCurveT<P>),
+    CurvePolygon(CurvePolygonT<P>),
+}
+
+impl<P> GeometryT<P>
+where
+    P: postgis::Point + EwkbRead,
+{
+    /// Coordinate dimensions of the geometry, for branching on 2D/Z/M/ZM at runtime.
+    pub fn coord_type(&self) -> PointType {
+        P::point_type()
+    }
+
+    /// Read a geometry from EWKB or ISO WKB, detecting the format from the type code.
+    ///
+    /// Type codes with the EWKB flags `0x80000000` (Z), `0x40000000` (M) or `0x20000000`
+    /// (SRID) are read as EWKB, codes from 1000 to 3999 as ISO WKB (1000 = Z, 2000 = M,
+    /// 3000 = ZM) and plain codes 1 to 7 as OGC WKB, which is valid in both formats.
+    /// The detection is done for each nested geometry separately. A type code which has both
+    /// EWKB flags and an ISO offset is not produced by PostGIS or other common writers; its
+    /// dimensions are combined, e.g. `0x80000000 | 2001` is read as a ZM point.
+    /// Dimensions missing in `P` are dropped, missing values in the data are set to 0.
+    pub fn from_wkb(mut raw: &[u8]) -> Result<Self, Error> {
+        Self::read_ewkb(&mut raw)
+    }
 }
 
 impl<'a, P> postgis::Geometry<'a> for GeometryT<P>
@@ -415,6 +561,9 @@ where
             A::MultiLineString(ref geom) => B::MultiLineString(geom),
             A::MultiPolygon(ref geom) => B::MultiPolygon(geom),
             A::GeometryCollection(ref geom) => B::GeometryCollection(geom),
+            A::CircularString(ref geom) => B::Curve(geom),
+            A::CompoundCurve(ref geom) => B::Curve(geom),
+            A::CurvePolygon(ref geom) => B::Curve(geom),
         }
     }
 }
@@ -426,31 +575,39 @@ where
     fn point_type() -> PointType {
         P::point_type()
     }
-    fn read_ewkb<R: Read>(raw: &mut R) -> Result<Self, Error> {
-        let byte_order = raw.read_i8()?;
-        let is_be = byte_order == 0i8;
-
-        let type_id = read_u32(raw, is_be)?;
-        let mut srid: Option<i32> = None;
-        if type_id & 0x20000000 == 0x20000000 {
-            srid = Some(read_i32(raw, is_be)?);
-        }
-
+    fn read_ewkb_body<R: Read>(
+        raw: &mut R,
+        is_be: bool,
+        type_id: u32,
+        srid: Option<i32>,
+        ctx: &mut ReadContext,
+    ) -> Result<Self, Error> {
         let geom = match type_id & 0xff {
-            0x01 => GeometryT::Point(P::read_ewkb_body(raw, is_be, type_id, srid)?),
-            0x02 => {
-                GeometryT::LineString(LineStringT::<P>::read_ewkb_body(raw, is_be, type_id, srid)?)
-            }
-            0x03 => GeometryT::Polygon(PolygonT::read_ewkb_body(raw, is_be, type_id, srid)?),
-            0x04 => GeometryT::MultiPoint(MultiPointT::read_ewkb_body(raw, is_be, type_id, srid)?),
+            0x01 => GeometryT::Point(P::read_ewkb_body(raw, is_be, type_id, srid, ctx)?),
+            0x02 => GeometryT::LineString(LineStringT::<P>::read_ewkb_body(
+                raw, is_be, type_id, srid, ctx,
+            )?),
+            0x03 => GeometryT::Polygon(PolygonT::read_ewkb_body(raw, is_be, type_id, srid, ctx)?),
+            0x04 => GeometryT::MultiPoint(MultiPointT::read_ewkb_body(
+                raw, is_be, type_id, srid, ctx,
+            )?),
             0x05 => GeometryT::MultiLineString(MultiLineStringT::read_ewkb_body(
-                raw, is_be, type_id, srid,
+                raw, is_be, type_id, srid, ctx,
+            )?),
+            0x06 => GeometryT::MultiPolygon(MultiPolygonT::read_ewkb_body(
+                raw, is_be, type_id, srid, ctx,
             )?),
-            0x06 => {
-                GeometryT::MultiPolygon(MultiPolygonT::read_ewkb_body(raw, is_be, type_id, srid)?)
-            }
             0x07 => GeometryT::GeometryCollection(GeometryCollectionT::read_ewkb_body(
-                raw, is_be, type_id, srid,
+                raw, is_be, type_id, srid, ctx,
+            )?),
+            0x08 => GeometryT::CircularString(CircularStringT::read_ewkb_body(
+                raw, is_be, type_id, srid, ctx,
+            )?),
+            0x09 => GeometryT::CompoundCurve(CompoundCurveT::read_ewkb_body(
+                raw, is_be, type_id, srid, ctx,
+            )?),
+            0x0a => GeometryT::CurvePolygon(CurvePolygonT::read_ewkb_body(
+                raw, is_be, type_id, srid, ctx,
             )?),
             _ => {
                 return Err(Error::Read(format!(
@@ -461,14 +618,6 @@ where
         };
         Ok(geom)
     }
-    fn read_ewkb_body<R: Read>(
-        _raw: &mut R,
-        _is_be: bool,
-        _type_id: u32,
-        _srid: Option<i32>,
-    ) -> Result<Self, Error> {
-        panic!("Not used for generic geometry type")
-    }
 }
 
 pub enum EwkbGeometry<'a, P, PI, MP, L, LI, ML, Y, YI, MY, G, GI, GC>
@@ -503,6 +652,8 @@ where
     MultiLineString(EwkbMultiLineString<'a, P, PI, L, LI>),
     MultiPolygon(EwkbMultiPolygon<'a, P, PI, L, LI, Y, YI>),
     GeometryCollection(EwkbGeometryCollection<'a, P, PI, MP, L, LI, ML, Y, YI, MY, G, GI, GC>),
+    CircularString(EwkbCircularString<'a, P, PI>),
+    Curve(EwkbCurve<'a>),
 }
 
 pub trait AsEwkbGeometry<'a> {
@@ -536,6 +687,7 @@ pub trait AsEwkbGeometry<'a> {
         + ExactSizeIterator<Item = &'a Self::GeomType>;
     type GeomCollection: 'a
         + postgis::GeometryCollection<'a, ItemType = Self::GeomType, Iter = Self::GeomIter>;
+    #[allow(clippy::type_complexity)]
     fn as_ewkb(
         &'a self,
     ) -> EwkbGeometry<
@@ -622,6 +774,8 @@ where
             EwkbGeometry::MultiLineString(ref ewkb) => ewkb.opt_srid(),
             EwkbGeometry::MultiPolygon(ref ewkb) => ewkb.opt_srid(),
             EwkbGeometry::GeometryCollection(ref ewkb) => ewkb.opt_srid(),
+            EwkbGeometry::CircularString(ref ewkb) => ewkb.opt_srid(),
+            EwkbGeometry::Curve(ref ewkb) => ewkb.opt_srid(),
         }
     }
 
@@ -634,6 +788,8 @@ where
             EwkbGeometry::MultiLineString(ref ewkb) => ewkb.type_id(),
             EwkbGeometry::MultiPolygon(ref ewkb) => ewkb.type_id(),
             EwkbGeometry::GeometryCollection(ref ewkb) => ewkb.type_id(),
+            EwkbGeometry::CircularString(ref ewkb) => ewkb.type_id(),
+            EwkbGeometry::Curve(ref ewkb) => ewkb.type_id(),
         }
     }
 
@@ -646,6 +802,22 @@ where
             EwkbGeometry::MultiLineString(ref ewkb) => ewkb.write_ewkb_body(w),
             EwkbGeometry::MultiPolygon(ref ewkb) => ewkb.write_ewkb_body(w),
             EwkbGeometry::GeometryCollection(ref ewkb) => ewkb.write_ewkb_body(w),
+            EwkbGeometry::CircularString(ref ewkb) => ewkb.write_ewkb_body(w),
+            EwkbGeometry::Curve(ref ewkb) => ewkb.write_ewkb_body(w),
+        }
+    }
+
+    fn ewkb_body_size_hint(&self) -> usize {
+        match *self {
+            EwkbGeometry::Point(ref ewkb) => ewkb.ewkb_body_size_hint(),
+            EwkbGeometry::LineString(ref ewkb) => ewkb.ewkb_body_size_hint(),
+            EwkbGeometry::Polygon(ref ewkb) => ewkb.ewkb_body_size_hint(),
+            EwkbGeometry::MultiPoint(ref ewkb) => ewkb.ewkb_body_size_hint(),
+            EwkbGeometry::MultiLineString(ref ewkb) => ewkb.ewkb_body_size_hint(),
+            EwkbGeometry::MultiPolygon(ref ewkb) => ewkb.ewkb_body_size_hint(),
+            EwkbGeometry::GeometryCollection(ref ewkb) => ewkb.ewkb_body_size_hint(),
+            EwkbGeometry::CircularString(ref ewkb) => ewkb.ewkb_body_size_hint(),
+            EwkbGeometry::Curve(ref ewkb) => ewkb.ewkb_body_size_hint(),
         }
     }
 }
@@ -693,10 +865,62 @@ where
             GeometryT::GeometryCollection(ref geom) => {
                 EwkbGeometry::GeometryCollection(geom.as_ewkb())
             }
+            GeometryT::CircularString(ref geom) => EwkbGeometry::CircularString(geom.as_ewkb()),
+            GeometryT::CompoundCurve(ref geom) => EwkbGeometry::Curve(geom.as_ewkb()),
+            GeometryT::CurvePolygon(ref geom) => EwkbGeometry::Curve(geom.as_ewkb()),
         }
     }
 }
 
+/// EWKB writer owning its geometry.
+///
+/// Unlike the borrowing `Ewkb*` adapters, it can be stored, returned from functions and
+/// sent to other threads. Writing delegates to [`EwkbGeometry`].
+#[derive(Clone, Debug)]
+pub struct EwkbGeometryOwned<P: postgis::Point + EwkbRead> {
+    pub geom: GeometryT<P>,
+}
+
+impl<P> GeometryT<P>
+where
+    P: postgis::Point + EwkbRead,
+{
+    /// Wrap the geometry into an owned EWKB writer.
+    pub fn into_ewkb(self) -> EwkbGeometryOwned<P> {
+        EwkbGeometryOwned { geom: self }
+    }
+}
+
+impl<P> From<GeometryT<P>> for EwkbGeometryOwned<P>
+where
+    P: postgis::Point + EwkbRead,
+{
+    fn from(geom: GeometryT<P>) -> Self {
+        geom.into_ewkb()
+    }
+}
+
+impl<P> EwkbWrite for EwkbGeometryOwned<P>
+where
+    P: postgis::Point + EwkbRead + for<'a> AsEwkbPoint<'a>,
+{
+    fn opt_srid(&self) -> Option<i32> {
+        self.geom.as_ewkb().opt_srid()
+    }
+
+    fn type_id(&self) -> u32 {
+        self.geom.as_ewkb().type_id()
+    }
+
+    fn write_ewkb_body<W: Write + ?Sized>(&self, w: &mut W) -> Result<(), Error> {
+        self.geom.as_ewkb().write_ewkb_body(w)
+    }
+
+    fn ewkb_body_size_hint(&self) -> usize {
+        self.geom.as_ewkb().ewkb_body_size_hint()
+    }
+}
+
 /// OGC Geometry type
 pub type Geometry = GeometryT<Point>;
 /// OGC GeometryZ type
@@ -706,10 +930,91 @@ pub type GeometryM = GeometryT<PointM>;
 /// OGC GeometryZM type
 pub type GeometryZM = GeometryT<PointZM>;
 
+/// Geometry with the point type chosen at runtime from the EWKB dimension flags.
+///
+/// Useful for reading columns mixing 2D and 3D geometries, e.g. into a cache of
+/// geometries with different dimensions. The geometry traits have associated types
+/// depending on the point type and can't be used as trait objects, so the variants hold
+/// the concrete geometries.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug)]
+pub enum DynGeometry {
+    Xy(Geometry),
+    Xyz(GeometryZ),
+    Xym(GeometryM),
+    Xyzm(GeometryZM),
+}
+
+impl DynGeometry {
+    /// Read a geometry, with the point type given by its dimension flags.
+    pub fn read_ewkb<R: Read>(raw: &mut R) -> Result<Self, Error> {
+        let mut ctx = ReadContext::default();
+        Self::read_ewkb_with_context(raw, &mut ctx).map_err(|e| ctx.annotate(e))
+    }
+
+    fn read_ewkb_with_context<R: Read>(raw: &mut R, ctx: &mut ReadContext) -> Result<Self, Error> {
+        let (is_be, type_id, srid) = read_ewkb_header(raw, ctx)?;
+        let geom = match (has_z(type_id), has_m(type_id)) {
+            (false, false) => {
+                DynGeometry::Xy(GeometryT::read_ewkb_body(raw, is_be, type_id, srid, ctx)?)
+            }
+            (true, false) => {
+                DynGeometry::Xyz(GeometryT::read_ewkb_body(raw, is_be, type_id, srid, ctx)?)
+            }
+            (false, true) => {
+                DynGeometry::Xym(GeometryT::read_ewkb_body(raw, is_be, type_id, srid, ctx)?)
+            }
+            (true, true) => {
+                DynGeometry::Xyzm(GeometryT::read_ewkb_body(raw, is_be, type_id, srid, ctx)?)
+            }
+        };
+        Ok(geom)
+    }
+
+    /// Read a geometry with the widest point type of all its (sub-)geometries.
+    ///
+    /// [`read_ewkb`](Self::read_ewkb) only looks at the flags of the outermost header, so
+    /// a 2D geometry collection containing a 3D line loses the Z values of the line. This
+    /// function reads the headers of all sub-geometries first and then the whole geometry
+    /// with a point type having every dimension found. Missing values of sub-geometries with
+    /// less dimensions are set to 0, like `ST_Force3D` does. The data is parsed twice.
+    pub fn read_ewkb_widest(raw: &[u8]) -> Result<Self, Error> {
+        let mut ctx = ReadContext::default();
+        GeometryT::<Point>::read_ewkb_with_context(&mut &raw[..], &mut ctx)
+            .map_err(|e| ctx.annotate(e))?;
+        let mut raw = raw;
+        let geom = match (ctx.seen_z, ctx.seen_m) {
+            (false, false) => DynGeometry::Xy(GeometryT::read_ewkb(&mut raw)?),
+            (true, false) => DynGeometry::Xyz(GeometryT::read_ewkb(&mut raw)?),
+            (false, true) => DynGeometry::Xym(GeometryT::read_ewkb(&mut raw)?),
+            (true, true) => DynGeometry::Xyzm(GeometryT::read_ewkb(&mut raw)?),
+        };
+        Ok(geom)
+    }
+
+    /// Coordinate dimensions of the geometry.
+    pub fn dimension(&self) -> PointType {
+        match self {
+            DynGeometry::Xy(geom) => geom.coord_type(),
+            DynGeometry::Xyz(geom) => geom.coord_type(),
+            DynGeometry::Xym(geom) => geom.coord_type(),
+            DynGeometry::Xyzm(geom) => geom.coord_type(),
+        }
+    }
+}
+
+/// Collection of geometries with a common point type.
+///
+/// All sub-geometries are read with the point type `P`, regardless of their own dimension
+/// flags: dimensions missing in `P` are dropped and dimensions missing in the data are set
+/// to 0. PostGIS doesn't create collections with mixed dimensions, but other writers may.
+/// Use [`DynGeometry::read_ewkb_widest`] to read such data without losing values, or
+/// [`EwkbRead::read_ewkb_strict`] to reject it.
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Debug)]
 pub struct GeometryCollectionT<P: postgis::Point + EwkbRead> {
     pub geometries: Vec<GeometryT<P>>,
+    #[cfg_attr(feature = "serde", serde(default))]
     pub srid: Option<i32>,
 }
 
@@ -734,6 +1039,33 @@ where
     }
 }
 
+impl<P> FromIterator<GeometryT<P>> for GeometryCollectionT<P>
+where
+    P: postgis::Point + EwkbRead,
+{
+    #[inline]
+    fn from_iter<I: IntoIterator<Item = GeometryT<P>>>(iterable: I) -> GeometryCollectionT<P> {
+        let mut ret = GeometryCollectionT::new();
+        ret.extend(iterable);
+        ret
+    }
+}
+
+impl<P> Extend<GeometryT<P>> for GeometryCollectionT<P>
+where
+    P: postgis::Point + EwkbRead,
+{
+    #[inline]
+    fn extend<I: IntoIterator<Item = GeometryT<P>>>(&mut self, iterable: I) {
+        let iterator = iterable.into_iter();
+        let (lower, _) = iterator.size_hint();
+        self.geometries.reserve(lower);
+        for item in iterator {
+            self.geometries.push(item);
+        }
+    }
+}
+
 impl<'a, P> postgis::GeometryCollection<'a> for GeometryCollectionT<P>
 where
     P: 'a + postgis::Point + EwkbRead,
@@ -757,45 +1089,21 @@ where
         raw: &mut R,
         is_be: bool,
         _type_id: u32,
-        _srid: Option<i32>,
+        srid: Option<i32>,
+        ctx: &mut ReadContext,
     ) -> Result<Self, Error> {
-        let mut ret = GeometryCollectionT::new();
+        let mut ret = GeometryCollectionT {
+            geometries: Vec::new(),
+            srid,
+        };
+        ctx.enter_collection()?;
         let size = read_u32(raw, is_be)? as usize;
-        for _ in 0..size {
-            let is_be = raw.read_i8()? == 0i8;
-
-            let type_id = read_u32(raw, is_be)?;
-            let mut srid: Option<i32> = None;
-            if type_id & 0x20000000 == 0x20000000 {
-                srid = Some(read_i32(raw, is_be)?);
-            }
-            let geom = match type_id & 0xff {
-                0x01 => GeometryT::Point(P::read_ewkb_body(raw, is_be, type_id, srid)?),
-                0x02 => GeometryT::LineString(LineStringT::<P>::read_ewkb_body(
-                    raw, is_be, type_id, srid,
-                )?),
-                0x03 => GeometryT::Polygon(PolygonT::read_ewkb_body(raw, is_be, type_id, srid)?),
-                0x04 => {
-                    GeometryT::MultiPoint(MultiPointT::read_ewkb_body(raw, is_be, type_id, srid)?)
-                }
-                0x05 => GeometryT::MultiLineString(MultiLineStringT::read_ewkb_body(
-                    raw, is_be, type_id, srid,
-                )?),
-                0x06 => GeometryT::MultiPolygon(MultiPolygonT::read_ewkb_body(
-                    raw, is_be, type_id, srid,
-                )?),
-                0x07 => GeometryT::GeometryCollection(GeometryCollectionT::read_ewkb_body(
-                    raw, is_be, type_id, srid,
-                )?),
-                _ => {
-                    return Err(Error::Read(format!(
-                        "Error reading generic geometry type - unsupported type id {}.",
-                        type_id
-                    )))
-                }
-            };
+        for i in 0..size {
+            let geom = GeometryT::read_ewkb_with_context(raw, ctx)
+                .map_err(|e| ctx.at("geometries", i, e))?;
             ret.geometries.push(geom);
         }
+        ctx.leave_collection();
         Ok(ret)
     }
 }
@@ -861,6 +1169,7 @@ pub trait AsEwkbGeometryCollection<'a> {
         + ExactSizeIterator<Item = &'a Self::GeomType>;
     type GeomCollection: 'a
         + postgis::GeometryCollection<'a, ItemType = Self::GeomType, Iter = Self::GeomIter>;
+    #[allow(clippy::type_complexity)]
     fn as_ewkb(
         &'a self,
     ) -> EwkbGeometryCollection<
@@ -1007,10 +1316,76 @@ where
                     };
                     wkb.write_ewkb(w)?;
                 }
+                postgis::GeometryType::Curve(geom) => {
+                    let wkb = EwkbCurve {
+                        geom,
+                        srid: None,
+                        point_type: self.point_type,
+                    };
+                    wkb.write_ewkb(w)?;
+                }
             }
         }
         Ok(())
     }
+
+    fn ewkb_body_size_hint(&self) -> usize {
+        let items: usize = self
+            .geom
+            .geometries()
+            .map(|geom| match geom.as_type() {
+                postgis::GeometryType::Point(geom) => EwkbPoint {
+                    geom,
+                    srid: None,
+                    point_type: self.point_type,
+                }
+                .ewkb_size_hint(),
+                postgis::GeometryType::LineString(geom) => EwkbLineString {
+                    geom,
+                    srid: None,
+                    point_type: self.point_type,
+                }
+                .ewkb_size_hint(),
+                postgis::GeometryType::Polygon(geom) => EwkbPolygon {
+                    geom,
+                    srid: None,
+                    point_type: self.point_type,
+                }
+                .ewkb_size_hint(),
+                postgis::GeometryType::MultiPoint(geom) => EwkbMultiPoint {
+                    geom,
+                    srid: None,
+                    point_type: self.point_type,
+                }
+                .ewkb_size_hint(),
+                postgis::GeometryType::MultiLineString(geom) => EwkbMultiLineString {
+                    geom,
+                    srid: None,
+                    point_type: self.point_type,
+                }
+                .ewkb_size_hint(),
+                postgis::GeometryType::MultiPolygon(geom) => EwkbMultiPolygon {
+                    geom,
+                    srid: None,
+                    point_type: self.point_type,
+                }
+                .ewkb_size_hint(),
+                postgis::GeometryType::GeometryCollection(geom) => EwkbGeometryCollection {
+                    geom,
+                    srid: None,
+                    point_type: self.point_type,
+                }
+                .ewkb_size_hint(),
+                postgis::GeometryType::Curve(geom) => EwkbCurve {
+                    geom,
+                    srid: None,
+                    point_type: self.point_type,
+                }
+                .ewkb_size_hint(),
+            })
+            .sum();
+        4 + items
+    }
 }
 
 impl<'a, P> AsEwkbGeometryCollection<'a> for GeometryCollectionT<P>
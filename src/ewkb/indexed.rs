@@ -0,0 +1,150 @@
+//! Multi polygons with shared vertices.
+
+use crate::ewkb::*;
+use std::collections::HashMap;
+
+/// Topology of a multi polygon referencing a shared vertex list, see
+/// [`MultiPolygonT::read_ewkb_indexed`].
+///
+/// `polygons[i][j]` lists the vertex indices of ring `j` of polygon `i`, including the
+/// closing point.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct IndexedMultiPolygon {
+    pub polygons: Vec<Vec<Vec<usize>>>,
+    pub srid: Option<i32>,
+}
+
+impl IndexedMultiPolygon {
+    /// Rebuild the multi polygon from the vertex list.
+    ///
+    /// # Panics
+    ///
+    /// If an index is out of bounds of `vertices`.
+    pub fn to_multipolygon<P: PointCoords + Clone>(&self, vertices: &[P]) -> MultiPolygonT<P> {
+        let ring = |indices: &Vec<usize>| LineStringT {
+            points: indices.iter().map(|&i| vertices[i].clone()).collect(),
+            srid: self.srid,
+        };
+        MultiPolygonT {
+            polygons: self
+                .polygons
+                .iter()
+                .map(|rings| PolygonT {
+                    rings: rings.iter().map(ring).collect(),
+                    srid: self.srid,
+                })
+                .collect(),
+            srid: self.srid,
+        }
+    }
+}
+
+impl<P: PointCoords> MultiPolygonT<P> {
+    /// Read a multi polygon, merging identical vertices into a shared vertex list.
+    ///
+    /// Vertices are identical if all their coordinates have the same bit pattern, so `0.0`
+    /// and `-0.0` are different vertices. Each vertex is stored at its first occurrence.
+    /// Vertices are merged while reading, so only the distinct vertices are kept in memory.
+    pub fn read_ewkb_indexed<R: Read>(raw: &mut R) -> Result<(Vec<P>, IndexedMultiPolygon), Error> {
+        let mut ctx = ReadContext::default();
+        let mut vertices = Vec::new();
+        let mut lookup: HashMap<[u64; 4], usize> = HashMap::new();
+        read_indexed_body(raw, &mut ctx, &mut vertices, &mut lookup)
+            .map(|indexed| (vertices, indexed))
+            .map_err(|e| ctx.annotate(e))
+    }
+}
+
+fn read_indexed_body<R: Read, P: PointCoords>(
+    raw: &mut R,
+    ctx: &mut ReadContext,
+    vertices: &mut Vec<P>,
+    lookup: &mut HashMap<[u64; 4], usize>,
+) -> Result<IndexedMultiPolygon, Error> {
+    let mut intern = |p: P| {
+        let key = [
+            p.x(),
+            p.y(),
+            p.opt_z().unwrap_or(0.0),
+            p.opt_m().unwrap_or(0.0),
+        ]
+        .map(f64::to_bits);
+        *lookup.entry(key).or_insert_with(|| {
+            vertices.push(p);
+            vertices.len() - 1
+        })
+    };
+    let (is_be, _type_id, srid) = read_ewkb_header(raw, ctx)?;
+    let mut polygons = vec![];
+    for i in 0..read_u32(raw, is_be)? as usize {
+        let polygon =
+            read_indexed_polygon(raw, ctx, &mut intern).map_err(|e| ctx.at("polygons", i, e))?;
+        polygons.push(polygon);
+    }
+    Ok(IndexedMultiPolygon { polygons, srid })
+}
+
+fn read_indexed_polygon<R: Read, P: PointCoords>(
+    raw: &mut R,
+    ctx: &mut ReadContext,
+    intern: &mut impl FnMut(P) -> usize,
+) -> Result<Vec<Vec<usize>>, Error> {
+    let (is_be, type_id, srid) = read_ewkb_header(raw, ctx)?;
+    let mut rings = vec![];
+    for i in 0..read_u32(raw, is_be)? as usize {
+        let ring = read_indexed_ring(raw, is_be, type_id, srid, ctx, intern)
+            .map_err(|e| ctx.at("rings", i, e))?;
+        rings.push(ring);
+    }
+    Ok(rings)
+}
+
+fn read_indexed_ring<R: Read, P: PointCoords>(
+    raw: &mut R,
+    is_be: bool,
+    type_id: u32,
+    srid: Option<i32>,
+    ctx: &mut ReadContext,
+    intern: &mut impl FnMut(P) -> usize,
+) -> Result<Vec<usize>, Error> {
+    let mut ring = vec![];
+    for i in 0..read_u32(raw, is_be)? as usize {
+        let point = P::read_ewkb_body(raw, is_be, type_id, srid, ctx)
+            .map_err(|e| ctx.at("points", i, e))?;
+        ring.push(intern(point));
+    }
+    Ok(ring)
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_read_ewkb_indexed() {
+    // Two squares sharing the edge (1 0)-(1 1)
+    let multi = MultiPolygonT::<Point> {srid: Some(4326), polygons: vec![
+        PolygonT {srid: None, rings: vec![line_xy(&[(0., 0.), (1., 0.), (1., 1.), (0., 1.), (0., 0.)])]},
+        PolygonT {srid: None, rings: vec![line_xy(&[(1., 0.), (2., 0.), (2., 1.), (1., 1.), (1., 0.)])]},
+    ]};
+    let ewkb = multi.as_ewkb().to_ewkb_bytes();
+    let (vertices, indexed) = MultiPolygonT::<Point>::read_ewkb_indexed(&mut ewkb.as_slice()).unwrap();
+    assert_eq!(vertices.len(), 6);
+    assert_eq!(indexed.srid, Some(4326));
+    assert_eq!(indexed.polygons, vec![vec![vec![0, 1, 2, 3, 0]], vec![vec![1, 4, 5, 2, 1]]]);
+    assert_eq!(vertices[4].to_tuple(), (2., 0.));
+
+    let rebuilt = indexed.to_multipolygon(&vertices);
+    assert_eq!(rebuilt.as_ewkb().to_ewkb_bytes(), ewkb);
+
+    // Points differing only in Z are different vertices
+    let p = |z| PointZ::new(0., 0., z, None);
+    let multi = MultiPolygonT::<PointZ> {srid: None, polygons: vec![PolygonT {srid: None, rings: vec![
+        LineStringT {srid: None, points: vec![p(0.), p(1.), p(0.)]}]}]};
+    let ewkb = multi.as_ewkb().to_ewkb_bytes();
+    let (vertices, indexed) = MultiPolygonT::<PointZ>::read_ewkb_indexed(&mut ewkb.as_slice()).unwrap();
+    assert_eq!(vertices.len(), 2);
+    assert_eq!(indexed.polygons[0][0], vec![0, 1, 0]);
+
+    // Errors report the position like `read_ewkb`
+    let truncated = &ewkb[..ewkb.len() - 8];
+    assert_eq!(MultiPolygonT::<PointZ>::read_ewkb_indexed(&mut &truncated[..]).unwrap_err().to_string(),
+        MultiPolygonT::<PointZ>::read_ewkb(&mut &truncated[..]).unwrap_err().to_string());
+}
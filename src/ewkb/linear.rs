@@ -0,0 +1,171 @@
+//! Linear referencing along lines.
+
+use crate::ewkb::measure::segment_param;
+use crate::ewkb::*;
+
+/// Point at `t` between `a` (0) and `b` (1), including Z and M.
+fn lerp<P: PointCoords>(a: &P, b: &P, t: f64, srid: Option<i32>) -> P {
+    let lerp = |u: f64, v: f64| u + t * (v - u);
+    P::new_from_opt_vals(
+        lerp(a.x(), b.x()),
+        lerp(a.y(), b.y()),
+        a.opt_z().zip(b.opt_z()).map(|(u, v)| lerp(u, v)),
+        a.opt_m().zip(b.opt_m()).map(|(u, v)| lerp(u, v)),
+        srid,
+    )
+}
+
+fn segment_length<P: postgis::Point>(a: &P, b: &P) -> f64 {
+    (b.x() - a.x()).hypot(b.y() - a.y())
+}
+
+impl<P: PointCoords> LineStringT<P> {
+    /// Point at `fraction` of the 2D length of the line, like `ST_LineInterpolatePoint`.
+    ///
+    /// A `fraction` of 0 returns the start point and 1 the end point. Z and M values are
+    /// interpolated linearly within the segment. The SRID of the result is the SRID of the
+    /// line. Returns `None` for an empty line or a `fraction` outside of `[0, 1]`.
+    pub fn interpolate(&self, fraction: f64) -> Option<P> {
+        if !(0.0..=1.0).contains(&fraction) {
+            return None;
+        }
+        let last = self.points.last()?;
+        let total: f64 = self
+            .points
+            .windows(2)
+            .map(|s| segment_length(&s[0], &s[1]))
+            .sum();
+        let target = fraction * total;
+        let mut start = 0.0;
+        for seg in self.points.windows(2) {
+            let len = segment_length(&seg[0], &seg[1]);
+            if len > 0.0 && start + len >= target {
+                let t = ((target - start) / len).min(1.0);
+                return Some(lerp(&seg[0], &seg[1], t, self.srid));
+            }
+            start += len;
+        }
+        Some(lerp(last, last, 0.0, self.srid))
+    }
+
+    /// `n` points spaced equally by 2D distance along the line, including both end points,
+    /// e.g. for placing markers along a route.
+    ///
+    /// Points are computed with [`interpolate`](Self::interpolate), so Z and M values are
+    /// interpolated and the points carry the SRID of the line. For `n` = 1 only the start
+    /// point is returned, for `n` = 0 or an empty line no points. Takes O(n · m) time for a
+    /// line with m points.
+    pub fn sample_equidistant(&self, n: usize) -> Vec<P> {
+        match n {
+            0 => Vec::new(),
+            1 => self.interpolate(0.0).into_iter().collect(),
+            _ => (0..n)
+                .filter_map(|i| self.interpolate(i as f64 / (n - 1) as f64))
+                .collect(),
+        }
+    }
+
+    /// Point on the line closest to (x, y) and its 2D distance, like `ST_ClosestPoint`.
+    ///
+    /// Z and M values of the result are interpolated within the nearest segment. If several
+    /// segments are equally close, the first one is used. The SRID of the result is the SRID
+    /// of the line. Returns `None` for an empty line.
+    pub fn closest_point(&self, x: f64, y: f64) -> Option<(P, f64)> {
+        let first = self.points.first()?;
+        let mut best = (lerp(first, first, 0.0, self.srid), f64::INFINITY);
+        for seg in self.points.windows(2) {
+            let (a, b) = (&seg[0], &seg[1]);
+            let t = segment_param(x, y, a.x(), a.y(), b.x(), b.y());
+            let p = lerp(a, b, t, self.srid);
+            let dist = (p.x() - x).hypot(p.y() - y);
+            if dist < best.1 {
+                best = (p, dist);
+            }
+        }
+        if best.1.is_infinite() {
+            best.1 = (first.x() - x).hypot(first.y() - y);
+        }
+        Some(best)
+    }
+
+    /// Maximum 2D distance of a vertex of this line to the nearest point of `other`.
+    ///
+    /// This is the one-sided discrete Hausdorff distance, e.g. for checking that
+    /// [`simplify`](Self::simplify) stayed within its tolerance with
+    /// `line.max_deviation(&line.simplify(tol)) <= tol`. Returns 0 for an empty line and
+    /// infinity if only `other` is empty.
+    pub fn max_deviation(&self, other: &Self) -> f64 {
+        self.points
+            .iter()
+            .map(|p| {
+                other
+                    .closest_point(p.x(), p.y())
+                    .map_or(f64::INFINITY, |(_, d)| d)
+            })
+            .fold(0.0, f64::max)
+    }
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_interpolate() {
+    let p = |x, y, z| PointZ::new(x, y, z, Some(4326));
+    let line = LineStringT::<PointZ> {srid: Some(4326), points: vec![p(0., 0., 10.), p(3., 0., 10.), p(3., 0., 10.), p(3., 1., 20.)]};
+    assert_eq!(line.interpolate(0.0), Some(p(0., 0., 10.)));
+    assert_eq!(line.interpolate(0.5), Some(p(2., 0., 10.)));
+    assert_eq!(line.interpolate(0.875), Some(p(3., 0.5, 15.)));
+    assert_eq!(line.interpolate(1.0), Some(p(3., 1., 20.)));
+    assert_eq!(line.interpolate(1.5), None);
+    assert_eq!(line.interpolate(f64::NAN), None);
+    assert_eq!(LineStringT::<PointZ>::new().interpolate(0.5), None);
+
+    // Zero length
+    let line = LineStringT::<Point> {srid: None, points: vec![Point::new(1., 2., None), Point::new(1., 2., None)]};
+    assert_eq!(line.interpolate(0.3), Some(Point::new(1., 2., None)));
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_sample_equidistant() {
+    let p = |x, y, m| PointM::new(x, y, m, Some(4326));
+    let line = LineStringT::<PointM> {srid: Some(4326), points: vec![p(0., 0., 0.), p(4., 0., 4.), p(4., 2., 8.)]};
+    assert_eq!(line.sample_equidistant(4), vec![p(0., 0., 0.), p(2., 0., 2.), p(4., 0., 4.), p(4., 2., 8.)]);
+    assert_eq!(line.sample_equidistant(2), vec![p(0., 0., 0.), p(4., 2., 8.)]);
+    assert_eq!(line.sample_equidistant(1), vec![p(0., 0., 0.)]);
+    assert!(line.sample_equidistant(0).is_empty());
+    assert!(LineStringT::<PointM>::new().sample_equidistant(5).is_empty());
+
+    let samples = line.sample_equidistant(101);
+    assert_eq!(samples.len(), 101);
+    assert_eq!(samples[100], p(4., 2., 8.));
+    // The distance along the line is x + y
+    assert!(samples.windows(2).all(|s| ((s[1].x + s[1].y) - (s[0].x + s[0].y) - 0.06).abs() < 1e-9));
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_closest_point() {
+    let p = |x, y, m| PointM::new(x, y, m, None);
+    let line = LineStringT::<PointM> {srid: None, points: vec![p(0., 0., 0.), p(4., 0., 4.), p(4., 4., 8.)]};
+    assert_eq!(line.closest_point(1., 2.), Some((p(1., 0., 1.), 2.)));
+    assert_eq!(line.closest_point(7., 6.), Some((p(4., 4., 8.), 13f64.sqrt())));
+    assert_eq!(line.closest_point(5., 1.), Some((p(4., 1., 5.), 1.)));
+    assert_eq!(line.closest_point(-3., -4.), Some((p(0., 0., 0.), 5.)));
+
+    let single = LineStringT::<PointM> {srid: None, points: vec![p(1., 1., 3.)]};
+    assert_eq!(single.closest_point(4., 5.), Some((p(1., 1., 3.), 5.)));
+    assert_eq!(LineStringT::<PointM>::new().closest_point(0., 0.), None);
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_max_deviation() {
+    let original = line_xy(&[(0., 0.), (1., 0.4), (2., -0.3), (3., 0.2), (4., 0.)]);
+    let simplified = original.simplify(0.5);
+    assert_eq!(simplified.points.len(), 2);
+    assert_eq!(original.max_deviation(&simplified), 0.4);
+    assert_eq!(simplified.max_deviation(&original), 0.0);
+
+    assert_eq!(LineStringT::<Point>::new().max_deviation(&original), 0.0);
+    assert_eq!(original.max_deviation(&LineStringT::new()), f64::INFINITY);
+}
@@ -0,0 +1,341 @@
+//! Length and distance measurements.
+
+use crate::ewkb::*;
+
+/// Mean radius of the WGS84 ellipsoid in meters.
+const EARTH_MEAN_RADIUS: f64 = 6_371_008.8;
+
+/// Great-circle distance in meters between two lon/lat positions given in degrees.
+fn haversine_distance(lon1: f64, lat1: f64, lon2: f64, lat2: f64) -> f64 {
+    let (phi1, phi2) = (lat1.to_radians(), lat2.to_radians());
+    let dphi = (lat2 - lat1).to_radians();
+    let dlambda = (lon2 - lon1).to_radians();
+    let a = (dphi / 2.0).sin().powi(2) + phi1.cos() * phi2.cos() * (dlambda / 2.0).sin().powi(2);
+    2.0 * EARTH_MEAN_RADIUS * a.sqrt().asin()
+}
+
+/// Reference ellipsoid for geodesic measurements, like the PostGIS `spheroid` type
+/// `SPHEROID["WGS 84",6378137,298.257223563]`.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Spheroid {
+    /// Semi-major axis in meters.
+    pub a: f64,
+    /// Inverse flattening, 0 for a sphere.
+    pub invf: f64,
+}
+
+impl Spheroid {
+    /// The WGS84 ellipsoid used by `geography` columns.
+    pub const WGS84: Spheroid = Spheroid {
+        a: 6_378_137.0,
+        invf: 298.257_223_563,
+    };
+
+    /// Distance in meters between two lon/lat positions given in degrees, using Vincenty's
+    /// inverse formula.
+    ///
+    /// Returns NaN if the iteration doesn't converge, which happens for nearly antipodal
+    /// positions.
+    fn vincenty_distance(&self, lon1: f64, lat1: f64, lon2: f64, lat2: f64) -> f64 {
+        let f = if self.invf == 0.0 {
+            0.0
+        } else {
+            1.0 / self.invf
+        };
+        let b = self.a * (1.0 - f);
+        // Difference in longitude normalized to -180..180 degrees
+        let l = ((lon2 - lon1 + 180.0).rem_euclid(360.0) - 180.0).to_radians();
+        let u1 = ((1.0 - f) * lat1.to_radians().tan()).atan();
+        let u2 = ((1.0 - f) * lat2.to_radians().tan()).atan();
+        let (sin_u1, cos_u1) = u1.sin_cos();
+        let (sin_u2, cos_u2) = u2.sin_cos();
+
+        let mut lambda = l;
+        let (mut sin_sigma, mut cos_sigma, mut sigma, mut cos2_alpha, mut cos_2sigma_m);
+        let mut iterations = 0;
+        loop {
+            let (sin_lambda, cos_lambda) = lambda.sin_cos();
+            sin_sigma = (cos_u2 * sin_lambda).hypot(cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda);
+            if sin_sigma == 0.0 {
+                // Coincident positions
+                return 0.0;
+            }
+            cos_sigma = sin_u1 * sin_u2 + cos_u1 * cos_u2 * cos_lambda;
+            sigma = sin_sigma.atan2(cos_sigma);
+            let sin_alpha = cos_u1 * cos_u2 * sin_lambda / sin_sigma;
+            cos2_alpha = 1.0 - sin_alpha * sin_alpha;
+            // Zero on the equator
+            cos_2sigma_m = if cos2_alpha == 0.0 {
+                0.0
+            } else {
+                cos_sigma - 2.0 * sin_u1 * sin_u2 / cos2_alpha
+            };
+            let c = f / 16.0 * cos2_alpha * (4.0 + f * (4.0 - 3.0 * cos2_alpha));
+            let prev = lambda;
+            lambda = l
+                + (1.0 - c)
+                    * f
+                    * sin_alpha
+                    * (sigma
+                        + c * sin_sigma
+                            * (cos_2sigma_m
+                                + c * cos_sigma * (-1.0 + 2.0 * cos_2sigma_m * cos_2sigma_m)));
+            iterations += 1;
+            if lambda.abs() > std::f64::consts::PI || iterations >= 200 {
+                return f64::NAN;
+            }
+            if (lambda - prev).abs() < 1e-12 {
+                break;
+            }
+        }
+
+        let u_sq = cos2_alpha * (self.a * self.a - b * b) / (b * b);
+        let big_a =
+            1.0 + u_sq / 16384.0 * (4096.0 + u_sq * (-768.0 + u_sq * (320.0 - 175.0 * u_sq)));
+        let big_b = u_sq / 1024.0 * (256.0 + u_sq * (-128.0 + u_sq * (74.0 - 47.0 * u_sq)));
+        let delta_sigma = big_b
+            * sin_sigma
+            * (cos_2sigma_m
+                + big_b / 4.0
+                    * (cos_sigma * (-1.0 + 2.0 * cos_2sigma_m * cos_2sigma_m)
+                        - big_b / 6.0
+                            * cos_2sigma_m
+                            * (-3.0 + 4.0 * sin_sigma * sin_sigma)
+                            * (-3.0 + 4.0 * cos_2sigma_m * cos_2sigma_m)));
+        b * big_a * (sigma - delta_sigma)
+    }
+}
+
+/// Parameter of the projection of (px, py) onto the segment (ax, ay)-(bx, by), clamped to
+/// `[0, 1]`.
+pub(super) fn segment_param(px: f64, py: f64, ax: f64, ay: f64, bx: f64, by: f64) -> f64 {
+    let (dx, dy) = (bx - ax, by - ay);
+    let len2 = dx * dx + dy * dy;
+    if len2 == 0.0 {
+        0.0
+    } else {
+        (((px - ax) * dx + (py - ay) * dy) / len2).clamp(0.0, 1.0)
+    }
+}
+
+/// Euclidean distance of (px, py) to the segment (ax, ay)-(bx, by).
+pub(super) fn segment_distance(px: f64, py: f64, ax: f64, ay: f64, bx: f64, by: f64) -> f64 {
+    let t = segment_param(px, py, ax, ay, bx, by);
+    (px - (ax + t * (bx - ax))).hypot(py - (ay + t * (by - ay)))
+}
+
+/// Shoelace area of a ring, positive for counter-clockwise orientation.
+///
+/// An unclosed ring is treated as if its last point was connected to the first.
+pub(super) fn ring_signed_area<P: postgis::Point>(points: &[P]) -> f64 {
+    let n = points.len();
+    (0..n)
+        .map(|i| {
+            let (a, b) = (&points[i], &points[(i + 1) % n]);
+            a.x() * b.y() - b.x() * a.y()
+        })
+        .sum::<f64>()
+        / 2.0
+}
+
+/// Winding direction of a ring.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Orientation {
+    CounterClockwise,
+    Clockwise,
+    /// The ring encloses no area, e.g. because it has less than three distinct points.
+    Degenerate,
+}
+
+impl<P> LineStringT<P>
+where
+    P: postgis::Point + EwkbRead,
+{
+    /// Signed shoelace area of the line interpreted as a ring, positive for counter-clockwise orientation.
+    ///
+    /// An unclosed line is treated as if its last point was connected to the first.
+    /// Z and M values are ignored.
+    pub fn signed_area(&self) -> f64 {
+        ring_signed_area(&self.points)
+    }
+
+    /// Length of the line in meters, for lon/lat coordinates as stored in `geography` columns.
+    ///
+    /// The coordinates are interpreted as WGS84 longitude (x) and latitude (y) in degrees.
+    /// Each segment is measured as a great-circle distance (haversine formula) on a sphere
+    /// with the mean WGS84 radius of 6371008.8 m, so the result can deviate from the
+    /// spheroidal `ST_Length(geography)` by up to about 0.5%, see
+    /// [`length_spheroid`](Self::length_spheroid). Z and M values are ignored.
+    pub fn length_geodesic(&self) -> f64 {
+        self.points
+            .windows(2)
+            .map(|seg| haversine_distance(seg[0].x(), seg[0].y(), seg[1].x(), seg[1].y()))
+            .sum()
+    }
+
+    /// Length of the line in meters on a spheroid, like `ST_LengthSpheroid` or
+    /// `ST_Length(geography, true)`.
+    ///
+    /// The coordinates are interpreted as longitude (x) and latitude (y) in degrees. Each
+    /// segment is measured with Vincenty's inverse formula, which agrees with PostGIS to
+    /// well below a millimeter. The formula fails for segments between nearly antipodal
+    /// vertices, the length is NaN then. Z and M values are ignored.
+    pub fn length_spheroid(&self, s: &Spheroid) -> f64 {
+        self.points
+            .windows(2)
+            .map(|seg| s.vincenty_distance(seg[0].x(), seg[0].y(), seg[1].x(), seg[1].y()))
+            .sum()
+    }
+
+    /// Cartesian length of the line in the units of the coordinates, like `ST_Length` for
+    /// `geometry`.
+    ///
+    /// Z and M values are ignored. For lon/lat coordinates the result is in degrees, use
+    /// [`length_geodesic`](Self::length_geodesic) for meters.
+    pub fn length_2d(&self) -> f64 {
+        self.points
+            .windows(2)
+            .map(|seg| (seg[1].x() - seg[0].x()).hypot(seg[1].y() - seg[0].y()))
+            .sum()
+    }
+}
+
+impl<P> MultiLineStringT<P>
+where
+    P: postgis::Point + EwkbRead,
+{
+    /// Cartesian length of all lines, see [`LineStringT::length_2d`].
+    pub fn length_2d(&self) -> f64 {
+        self.lines.iter().map(LineStringT::length_2d).sum()
+    }
+
+    /// Cartesian length of each line, e.g. to drop short components.
+    pub fn component_lengths(&self) -> Vec<f64> {
+        self.lines.iter().map(LineStringT::length_2d).collect()
+    }
+
+    /// Length of all lines in meters, for lon/lat coordinates, see
+    /// [`LineStringT::length_geodesic`].
+    pub fn length_geodesic(&self) -> f64 {
+        self.lines.iter().map(LineStringT::length_geodesic).sum()
+    }
+
+    /// Length of all lines in meters on a spheroid, see [`LineStringT::length_spheroid`].
+    pub fn length_spheroid(&self, s: &Spheroid) -> f64 {
+        self.lines.iter().map(|line| line.length_spheroid(s)).sum()
+    }
+}
+
+impl<P> PolygonT<P>
+where
+    P: postgis::Point + EwkbRead,
+{
+    /// Winding direction of each ring, exterior ring first.
+    ///
+    /// The OGC convention expects a counter-clockwise exterior ring and clockwise holes.
+    pub fn orientation_report(&self) -> Vec<Orientation> {
+        self.rings
+            .iter()
+            .map(|ring| {
+                let area = ring.signed_area();
+                if area > 0.0 {
+                    Orientation::CounterClockwise
+                } else if area < 0.0 {
+                    Orientation::Clockwise
+                } else {
+                    Orientation::Degenerate
+                }
+            })
+            .collect()
+    }
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_length_geodesic() {
+    let p = |x, y| Point::new(x, y, Some(4326));
+    // One degree along the equator
+    let line = LineStringT::<Point> {srid: Some(4326), points: vec![p(0.0, 0.0), p(1.0, 0.0)]};
+    assert!((line.length_geodesic() - 111_195.08).abs() < 0.01);
+
+    // Paris - London - Paris
+    let line = LineStringT::<Point> {srid: Some(4326), points: vec![p(2.3522, 48.8566), p(-0.1276, 51.5072), p(2.3522, 48.8566)]};
+    assert!((line.length_geodesic() - 2.0 * 343_530.34).abs() < 0.01);
+
+    let line = LineStringT::<Point> {srid: Some(4326), points: vec![p(10.0, 10.0)]};
+    assert_eq!(line.length_geodesic(), 0.0);
+    assert_eq!(LineStringT::<Point>::new().length_geodesic(), 0.0);
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_length_spheroid() {
+    let p = |x, y| Point::new(x, y, Some(4326));
+    // One degree along the equator
+    let line = LineStringT::<Point> {srid: Some(4326), points: vec![p(0.0, 0.0), p(1.0, 0.0)]};
+    assert!((line.length_spheroid(&Spheroid::WGS84) - 111_319.491).abs() < 0.001);
+    // One degree along a meridian
+    let line = LineStringT::<Point> {srid: Some(4326), points: vec![p(0.0, 0.0), p(0.0, 1.0)]};
+    assert!((line.length_spheroid(&Spheroid::WGS84) - 110_574.389).abs() < 0.001);
+
+    // Flinders Peak - Buninyong on the GRS80 ellipsoid, the Geoscience Australia example
+    let grs80 = Spheroid {a: 6_378_137.0, invf: 298.257_222_101};
+    let dms = |d: f64, m: f64, s: f64| d.signum() * (d.abs() + m / 60.0 + s / 3600.0);
+    let line = LineStringT::<Point> {srid: None, points: vec![p(dms(144., 25., 29.5244), dms(-37., 57., 3.7203)),
+                                                              p(dms(143., 55., 35.3839), dms(-37., 39., 10.1561))]};
+    assert!((line.length_spheroid(&grs80) - 54_972.271).abs() < 0.001);
+
+    // A sphere matches the haversine formula
+    let sphere = Spheroid {a: EARTH_MEAN_RADIUS, invf: 0.0};
+    let line = LineStringT::<Point> {srid: Some(4326), points: vec![p(2.3522, 48.8566), p(-0.1276, 51.5072), p(2.3522, 48.8566)]};
+    assert!((line.length_spheroid(&sphere) - line.length_geodesic()).abs() < 1e-6);
+
+    // A quarter of the equator, crossing the antimeridian
+    let line = LineStringT::<Point> {srid: Some(4326), points: vec![p(135.0, 0.0), p(-135.0, 0.0)]};
+    assert!((line.length_spheroid(&Spheroid::WGS84) - 10_018_754.171).abs() < 0.001);
+    // Nearly antipodal vertices
+    for end in [p(180.0, 0.0), p(179.9, 0.0), p(179.7, 0.3)] {
+        let line = LineStringT::<Point> {srid: Some(4326), points: vec![p(0.0, 0.0), end]};
+        assert!(line.length_spheroid(&Spheroid::WGS84).is_nan());
+    }
+
+    let line = LineStringT::<Point> {srid: Some(4326), points: vec![p(10.0, 10.0), p(10.0, 10.0)]};
+    assert_eq!(line.length_spheroid(&Spheroid::WGS84), 0.0);
+    assert_eq!(LineStringT::<Point>::new().length_spheroid(&Spheroid::WGS84), 0.0);
+    let multi = MultiLineStringT::<Point> {srid: Some(4326), lines: vec![line, LineStringT {srid: None, points: vec![p(0.0, 0.0), p(1.0, 0.0)]}]};
+    assert!((multi.length_spheroid(&Spheroid::WGS84) - 111_319.491).abs() < 0.001);
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_length_2d() {
+    assert_eq!(line_xy(&[(0., 0.), (3., 4.), (3., 6.)]).length_2d(), 7.0);
+    assert_eq!(line_xy(&[(1., 1.)]).length_2d(), 0.0);
+    // Z is ignored
+    let line_z = LineStringT::<PointZ> {srid: None, points: vec![PointZ::new(0., 0., 0., None), PointZ::new(0., 1., 100., None)]};
+    assert_eq!(line_z.length_2d(), 1.0);
+
+    let multi = MultiLineStringT::<Point> {srid: None, lines: vec![line_xy(&[(0., 0.), (3., 4.)]), line_xy(&[(0., 0.), (0., 0.5)]), LineStringT::new()]};
+    assert_eq!(multi.length_2d(), 5.5);
+    assert_eq!(multi.component_lengths(), vec![5.0, 0.5, 0.0]);
+    assert_eq!(MultiLineStringT::<Point>::new().length_2d(), 0.0);
+
+    let multi = MultiLineStringT::<Point> {srid: Some(4326), lines: vec![line_xy(&[(0., 0.), (1., 0.)]), line_xy(&[(0., 0.), (1., 0.)])]};
+    assert!((multi.length_geodesic() - 2.0 * 111_195.08).abs() < 0.02);
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_orientation() {
+    let exterior = line_xy(&[(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0), (0.0, 0.0)]);
+    let hole = line_xy(&[(1.0, 1.0), (1.0, 2.0), (2.0, 2.0), (2.0, 1.0), (1.0, 1.0)]);
+    assert_eq!(exterior.signed_area(), 16.0);
+    assert_eq!(hole.signed_area(), -1.0);
+    // Unclosed ring
+    assert_eq!(line_xy(&[(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)]).signed_area(), 16.0);
+
+    let flat = line_xy(&[(0.0, 0.0), (1.0, 1.0), (0.0, 0.0)]);
+    let poly = PolygonT::<Point> {srid: None, rings: vec![exterior, hole, flat, LineStringT::new()]};
+    assert_eq!(poly.orientation_report(), vec![Orientation::CounterClockwise, Orientation::Clockwise,
+                                               Orientation::Degenerate, Orientation::Degenerate]);
+}
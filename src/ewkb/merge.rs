@@ -0,0 +1,158 @@
+//! Merging of connected lines and coincident points.
+
+use crate::ewkb::compare::coords_eq;
+use crate::ewkb::*;
+use std::collections::HashMap;
+
+fn coincide<P: postgis::Point>(a: &P, b: &P, epsilon: f64) -> bool {
+    (a.x() - b.x()).hypot(a.y() - b.y()) <= epsilon
+}
+
+/// Append `points` to `chain`, dropping the first point if it duplicates the last of `chain`.
+fn append<P: postgis::Point>(chain: &mut Vec<P>, points: impl Iterator<Item = P>) {
+    let mut points = points.peekable();
+    if let (Some(last), Some(first)) = (chain.last(), points.peek())
+        && coords_eq(last, first)
+    {
+        points.next();
+    }
+    chain.extend(points);
+}
+
+impl<P> MultiLineStringT<P>
+where
+    P: postgis::Point + EwkbRead + Clone,
+{
+    /// Join lines whose end points coincide within `epsilon` into longer lines.
+    ///
+    /// Lines are reversed where needed to connect them, so the direction of merged lines
+    /// is not preserved. Unlike `ST_LineMerge`, lines are also merged through junctions
+    /// where more than two lines meet, the choice of the continuation is unspecified.
+    /// A joining end point is dropped only if it has exactly the same coordinates as the
+    /// point it joins, so every input coordinate appears in the result. Empty lines are
+    /// removed and the order of the output lines is unspecified.
+    pub fn merge_connected(&self, epsilon: f64) -> MultiLineStringT<P> {
+        let mut remaining: Vec<&LineStringT<P>> =
+            self.lines.iter().filter(|l| !l.points.is_empty()).collect();
+        let mut lines = Vec::new();
+        while !remaining.is_empty() {
+            let seed = remaining.swap_remove(0);
+            let mut chain = seed.points.clone();
+            loop {
+                let (first, last) = (&chain[0], &chain[chain.len() - 1]);
+                let Some(i) = remaining.iter().position(|l| {
+                    let (start, end) = (&l.points[0], &l.points[l.points.len() - 1]);
+                    coincide(last, start, epsilon)
+                        || coincide(last, end, epsilon)
+                        || coincide(first, end, epsilon)
+                        || coincide(first, start, epsilon)
+                }) else {
+                    break;
+                };
+                let points = &remaining.swap_remove(i).points;
+                let (start, end) = (&points[0], &points[points.len() - 1]);
+                if coincide(last, start, epsilon) {
+                    append(&mut chain, points.iter().cloned());
+                } else if coincide(last, end, epsilon) {
+                    append(&mut chain, points.iter().rev().cloned());
+                } else {
+                    let mut front = if coincide(first, end, epsilon) {
+                        points.clone()
+                    } else {
+                        points.iter().rev().cloned().collect()
+                    };
+                    append(&mut front, chain.into_iter());
+                    chain = front;
+                }
+            }
+            lines.push(LineStringT {
+                points: chain,
+                srid: seed.srid,
+            });
+        }
+        MultiLineStringT {
+            lines,
+            srid: self.srid,
+        }
+    }
+}
+
+impl<P> MultiPointT<P>
+where
+    P: postgis::Point + EwkbRead,
+{
+    /// Remove points within `epsilon` of an earlier kept point, regardless of their order.
+    ///
+    /// The first point of each cluster is kept. Distances are measured in 2D, Z and M values
+    /// are ignored. An `epsilon` of 0 removes exact duplicates. Points are bucketed into a grid
+    /// with cells of size `epsilon`, so only points in neighbouring cells are compared. Cell
+    /// indices saturate, points beyond the representable grid share the outermost cells.
+    pub fn dedup(&mut self, epsilon: f64) {
+        let size = if epsilon > 0.0 { epsilon } else { 1.0 };
+        let cell = |p: &P| ((p.x() / size).floor() as i64, (p.y() / size).floor() as i64);
+        let mut grid: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+        let mut kept: Vec<P> = Vec::with_capacity(self.points.len());
+        for p in self.points.drain(..) {
+            let (cx, cy) = cell(&p);
+            let duplicate = (cx.saturating_sub(1)..=cx.saturating_add(1))
+                .flat_map(|x| (cy.saturating_sub(1)..=cy.saturating_add(1)).map(move |y| (x, y)))
+                .filter_map(|key| grid.get(&key))
+                .flatten()
+                .any(|&i| coincide(&kept[i], &p, epsilon));
+            if !duplicate {
+                grid.entry((cx, cy)).or_default().push(kept.len());
+                kept.push(p);
+            }
+        }
+        self.points = kept;
+    }
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_merge_connected() {
+    let p = |x, y| Point::new(x, y, None);
+    let line = |points: Vec<Point>| LineStringT::<Point> {srid: None, points};
+    let multi = MultiLineStringT::<Point> {srid: Some(4326), lines: vec![
+        line(vec![p(1., 0.), p(2., 0.)]),
+        line(vec![p(3., 0.), p(2., 0.)]), // reversed
+        line(vec![p(0., 0.), p(1., 0.)]), // connects to the start
+        line(vec![p(10., 10.), p(11., 10.)]),
+        line(vec![]),
+    ]};
+    let merged = multi.merge_connected(0.0);
+    assert_eq!(merged.srid, Some(4326));
+    assert_eq!(merged.lines, vec![
+        line(vec![p(0., 0.), p(1., 0.), p(2., 0.), p(3., 0.)]),
+        line(vec![p(10., 10.), p(11., 10.)]),
+    ]);
+
+    // Nearly coincident end points are joined, both points are kept
+    let multi = MultiLineStringT::<Point> {srid: None, lines: vec![
+        line(vec![p(0., 0.), p(1., 0.)]),
+        line(vec![p(1.001, 0.), p(2., 0.)]),
+    ]};
+    assert_eq!(multi.merge_connected(0.0).lines.len(), 2);
+    assert_eq!(multi.merge_connected(0.01).lines, vec![line(vec![p(0., 0.), p(1., 0.), p(1.001, 0.), p(2., 0.)])]);
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_dedup_points() {
+    let p = |x, y| Point::new(x, y, Some(4326));
+    let mut multi = MultiPointT::<Point> {srid: Some(4326), points: vec![p(0., 0.), p(5., 5.), p(0.05, -0.05), p(5., 5.), p(-0.09, 0.), p(1., 0.)]};
+    multi.dedup(0.1);
+    assert_eq!(multi, MultiPointT::<Point> {srid: Some(4326), points: vec![p(0., 0.), p(5., 5.), p(1., 0.)]});
+
+    let mut multi = MultiPointT::<Point> {srid: None, points: vec![p(0., 0.), p(0., 1e-9), p(0., 0.)]};
+    multi.dedup(0.0);
+    assert_eq!(multi.points, vec![p(0., 0.), p(0., 1e-9)]);
+
+    // Cell indices saturate at the edge of the grid
+    let mut multi = MultiPointT::<Point> {srid: None, points: vec![p(f64::MAX, f64::MAX), p(-f64::MAX, 1.), p(f64::MAX, f64::MAX), p(-f64::MAX, 1.)]};
+    multi.dedup(1.0);
+    assert_eq!(multi.points, vec![p(f64::MAX, f64::MAX), p(-f64::MAX, 1.)]);
+    let mut multi = MultiPointT::<Point> {srid: None, points: vec![p(1., 1.), p(1., 1.), p(2., 1.)]};
+    multi.dedup(1e-300);
+    assert_eq!(multi.points, vec![p(1., 1.), p(2., 1.)]);
+}
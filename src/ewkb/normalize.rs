@@ -0,0 +1,241 @@
+//! Canonical form of geometries like `ST_Normalize`.
+
+use crate::ewkb::compare::{coords_eq, vertex_cmp};
+use crate::ewkb::measure::ring_signed_area;
+use crate::ewkb::*;
+use std::cmp::Ordering;
+
+/// Lexicographic order of point sequences, shorter sequences first if one is a prefix.
+fn points_cmp<'a, P: postgis::Point + 'a>(
+    a: impl IntoIterator<Item = &'a P>,
+    b: impl IntoIterator<Item = &'a P>,
+) -> Ordering {
+    let (mut a, mut b) = (a.into_iter(), b.into_iter());
+    loop {
+        match (a.next(), b.next()) {
+            (Some(p), Some(q)) => match vertex_cmp(p, q) {
+                Ordering::Equal => continue,
+                ord => return ord,
+            },
+            (a, b) => return a.is_some().cmp(&b.is_some()),
+        }
+    }
+}
+
+/// Lexicographic order of sequences by `cmp`, shorter sequences first if one is a prefix.
+fn seq_cmp<T>(a: &[T], b: &[T], cmp: impl Fn(&T, &T) -> Ordering) -> Ordering {
+    a.iter()
+        .zip(b)
+        .map(|(a, b)| cmp(a, b))
+        .find(|ord| ord.is_ne())
+        .unwrap_or_else(|| a.len().cmp(&b.len()))
+}
+
+fn lines_cmp<P: PointCoords>(a: &LineStringT<P>, b: &LineStringT<P>) -> Ordering {
+    points_cmp(&a.points, &b.points)
+}
+
+/// Exterior rings first, then the number of holes and the holes.
+fn polygon_cmp<P: PointCoords>(a: &PolygonT<P>, b: &PolygonT<P>) -> Ordering {
+    match (a.rings.split_first(), b.rings.split_first()) {
+        (Some((a_ext, a_holes)), Some((b_ext, b_holes))) => lines_cmp(a_ext, b_ext)
+            .then(a_holes.len().cmp(&b_holes.len()))
+            .then_with(|| seq_cmp(a_holes, b_holes, lines_cmp)),
+        (a, b) => a.is_some().cmp(&b.is_some()),
+    }
+}
+
+fn segment_points<P: PointCoords>(segments: &[CurveSegment<P>]) -> impl Iterator<Item = &P> {
+    segments.iter().flat_map(|s| match s {
+        CurveSegment::LineString(g) => g.points.iter(),
+        CurveSegment::CircularString(g) => g.points.iter(),
+    })
+}
+
+fn curve_points<P: PointCoords>(curve: &Curve<P>) -> Vec<&P> {
+    match curve {
+        Curve::LineString(g) => g.points.iter().collect(),
+        Curve::CircularString(g) => g.points.iter().collect(),
+        Curve::CompoundCurve(g) => segment_points(&g.segments).collect(),
+    }
+}
+
+fn curve_cmp<P: PointCoords>(a: &Curve<P>, b: &Curve<P>) -> Ordering {
+    points_cmp(curve_points(a), curve_points(b))
+}
+
+/// Position of the geometry type in the sort order of collections, as in GEOS.
+fn sort_index<P: PointCoords>(geom: &GeometryT<P>) -> u8 {
+    match geom {
+        GeometryT::Point(_) => 0,
+        GeometryT::MultiPoint(_) => 1,
+        GeometryT::LineString(_) => 2,
+        GeometryT::MultiLineString(_) => 4,
+        GeometryT::Polygon(_) => 5,
+        GeometryT::MultiPolygon(_) => 6,
+        GeometryT::GeometryCollection(_) => 7,
+        GeometryT::CircularString(_) => 8,
+        GeometryT::CompoundCurve(_) => 9,
+        GeometryT::CurvePolygon(_) => 10,
+    }
+}
+
+fn geometry_cmp<P: PointCoords>(a: &GeometryT<P>, b: &GeometryT<P>) -> Ordering {
+    use GeometryT as G;
+    match (a, b) {
+        (G::Point(a), G::Point(b)) => vertex_cmp(a, b),
+        (G::LineString(a), G::LineString(b)) => lines_cmp(a, b),
+        (G::CircularString(a), G::CircularString(b)) => points_cmp(&a.points, &b.points),
+        (G::Polygon(a), G::Polygon(b)) => polygon_cmp(a, b),
+        (G::MultiPoint(a), G::MultiPoint(b)) => points_cmp(&a.points, &b.points),
+        (G::MultiLineString(a), G::MultiLineString(b)) => seq_cmp(&a.lines, &b.lines, lines_cmp),
+        (G::MultiPolygon(a), G::MultiPolygon(b)) => seq_cmp(&a.polygons, &b.polygons, polygon_cmp),
+        (G::GeometryCollection(a), G::GeometryCollection(b)) => {
+            seq_cmp(&a.geometries, &b.geometries, geometry_cmp)
+        }
+        (G::CompoundCurve(a), G::CompoundCurve(b)) => {
+            points_cmp(segment_points(&a.segments), segment_points(&b.segments))
+        }
+        (G::CurvePolygon(a), G::CurvePolygon(b)) => seq_cmp(&a.rings, &b.rings, curve_cmp),
+        (a, b) => sort_index(a).cmp(&sort_index(b)),
+    }
+}
+
+/// Start the ring at its smallest vertex and orient it. Unclosed rings are left unchanged.
+fn normalize_ring<P: PointCoords>(points: &mut Vec<P>, clockwise: bool) {
+    if points.len() < 4 || !coords_eq(&points[0], &points[points.len() - 1]) {
+        return;
+    }
+    points.pop();
+    let start = (0..points.len())
+        .min_by(|&i, &j| vertex_cmp(&points[i], &points[j]))
+        .unwrap_or(0);
+    points.rotate_left(start);
+    let first = &points[0];
+    let closing = P::new_from_opt_vals(
+        first.x(),
+        first.y(),
+        first.opt_z(),
+        first.opt_m(),
+        first.opt_srid(),
+    );
+    points.push(closing);
+    // Reversing a closed ring keeps the start point
+    let area = ring_signed_area(points);
+    if (clockwise && area > 0.0) || (!clockwise && area < 0.0) {
+        points.reverse();
+    }
+}
+
+/// Reverse the line if it is smaller read from the end.
+fn normalize_line<P: PointCoords>(points: &mut [P]) {
+    let n = points.len();
+    for i in 0..n / 2 {
+        match vertex_cmp(&points[i], &points[n - 1 - i]) {
+            Ordering::Equal => continue,
+            Ordering::Greater => points.reverse(),
+            Ordering::Less => {}
+        }
+        return;
+    }
+}
+
+fn normalize_polygon<P: PointCoords>(polygon: &mut PolygonT<P>) {
+    if let Some((exterior, holes)) = polygon.rings.split_first_mut() {
+        normalize_ring(&mut exterior.points, true);
+        for hole in holes.iter_mut() {
+            normalize_ring(&mut hole.points, false);
+        }
+        holes.sort_by(|a, b| lines_cmp(b, a));
+    }
+}
+
+fn normalize_geometry<P: PointCoords>(geom: &mut GeometryT<P>) {
+    match geom {
+        GeometryT::Point(_) => {}
+        GeometryT::LineString(g) => normalize_line(&mut g.points),
+        GeometryT::CircularString(g) => normalize_line(&mut g.points),
+        GeometryT::Polygon(g) => normalize_polygon(g),
+        GeometryT::MultiPoint(g) => g.points.sort_by(|a, b| vertex_cmp(b, a)),
+        GeometryT::MultiLineString(g) => {
+            g.lines
+                .iter_mut()
+                .for_each(|l| normalize_line(&mut l.points));
+            g.lines.sort_by(|a, b| lines_cmp(b, a));
+        }
+        GeometryT::MultiPolygon(g) => {
+            g.polygons.iter_mut().for_each(normalize_polygon);
+            g.polygons.sort_by(|a, b| polygon_cmp(b, a));
+        }
+        GeometryT::GeometryCollection(g) => {
+            g.geometries.iter_mut().for_each(normalize_geometry);
+            g.geometries.sort_by(|a, b| geometry_cmp(b, a));
+        }
+        GeometryT::CompoundCurve(_) | GeometryT::CurvePolygon(_) => {}
+    }
+}
+
+impl<P: PointCoords> GeometryT<P> {
+    /// Bring the geometry into the canonical form of PostGIS `ST_Normalize`.
+    ///
+    /// Exterior rings are made clockwise and holes counter-clockwise, each ring starting at
+    /// its smallest vertex, ordered by X, Y, Z and M. Lines are reversed if their end is
+    /// smaller than their start. Holes and the parts of multi geometries and collections are
+    /// sorted in descending order, collections by geometry type first. Equal geometries
+    /// written in a different order produce the same EWKB after normalization.
+    ///
+    /// Compound curves and curve polygons are left unchanged, but sorted within collections.
+    pub fn normalize(&mut self) {
+        normalize_geometry(self);
+    }
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_normalize() {
+    let xy = |l: &LineStringT<Point>| l.points.iter().map(|p| (p.x(), p.y())).collect::<Vec<_>>();
+
+    // Counter-clockwise exterior ring starting at (2 2), clockwise hole
+    let mut poly = GeometryT::Polygon(PolygonT {srid: Some(4326), rings: vec![
+        line_xy(&[(2., 2.), (0., 2.), (0., 0.), (2., 0.), (2., 2.)]),
+        line_xy(&[(1., 1.), (1.5, 1.5), (1.5, 1.), (1., 1.)]),
+    ]});
+    poly.normalize();
+    let GeometryT::Polygon(ref p) = poly else { unreachable!() };
+    assert_eq!(xy(&p.rings[0]), vec![(0., 0.), (0., 2.), (2., 2.), (2., 0.), (0., 0.)]);
+    assert_eq!(xy(&p.rings[1]), vec![(1., 1.), (1.5, 1.), (1.5, 1.5), (1., 1.)]);
+    assert_eq!(p.srid, Some(4326));
+
+    let mut l = GeometryT::LineString(line_xy(&[(3., 0.), (1., 1.), (0., 0.)]));
+    l.normalize();
+    assert!(matches!(l, GeometryT::LineString(ref l) if xy(l) == vec![(0., 0.), (1., 1.), (3., 0.)]));
+
+    // Same collection in a different order, with differently oriented parts
+    let a = GeometryCollectionT::<Point> {srid: None, geometries: vec![
+        GeometryT::Point(Point::new(5., 5., None)),
+        GeometryT::LineString(line_xy(&[(0., 0.), (1., 1.)])),
+        poly.clone(),
+        GeometryT::Point(Point::new(1., 1., None)),
+    ]};
+    let b = GeometryCollectionT::<Point> {srid: None, geometries: vec![
+        GeometryT::Polygon(PolygonT {srid: Some(4326), rings: vec![
+            line_xy(&[(0., 2.), (2., 2.), (2., 0.), (0., 0.), (0., 2.)]),
+            line_xy(&[(1.5, 1.5), (1., 1.), (1.5, 1.), (1.5, 1.5)]),
+        ]}),
+        GeometryT::Point(Point::new(1., 1., None)),
+        GeometryT::LineString(line_xy(&[(1., 1.), (0., 0.)])),
+        GeometryT::Point(Point::new(5., 5., None)),
+    ]};
+    let (mut a, mut b) = (GeometryT::GeometryCollection(a), GeometryT::GeometryCollection(b));
+    assert_ne!(a.as_ewkb().to_hex_ewkb(), b.as_ewkb().to_hex_ewkb());
+    a.normalize();
+    b.normalize();
+    assert_eq!(a.as_ewkb().to_hex_ewkb(), b.as_ewkb().to_hex_ewkb());
+    let GeometryT::GeometryCollection(ref c) = a else { unreachable!() };
+    assert!(matches!(c.geometries[..], [GeometryT::Polygon(_), GeometryT::LineString(_), GeometryT::Point(p), GeometryT::Point(q)]
+                     if p.x() == 5. && q.x() == 1.));
+
+    let mut multi = GeometryT::MultiPoint(MultiPointT::<Point> {srid: None, points: vec![Point::new(0., 1., None), Point::new(1., 0., None), Point::new(0., 0., None)]});
+    multi.normalize();
+    assert!(matches!(multi, GeometryT::MultiPoint(ref m) if m.points.iter().map(|p| (p.x(), p.y())).eq([(1., 0.), (0., 1.), (0., 0.)])));
+}
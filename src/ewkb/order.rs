@@ -0,0 +1,134 @@
+//! Space-filling curve keys and geohashes for spatially local ordering.
+
+use crate::ewkb::*;
+
+/// Map `v` from `[min, max]` to an integer in `[0, 2^bits)`, clamping values outside the range.
+fn quantize(v: f64, min: f64, max: f64, bits: u32) -> u64 {
+    let cells = (1u64 << bits) as f64;
+    let t = if max > min { (v - min) / (max - min) } else { 0.0 };
+    // NaN is mapped to 0 by the saturating cast
+    ((t * cells) as u64).min((1u64 << bits) - 1)
+}
+
+/// Spread the lower 32 bits of `v` to the even bit positions.
+fn spread_bits(v: u64) -> u64 {
+    let mut v = v & 0xffff_ffff;
+    v = (v | (v << 16)) & 0x0000_ffff_0000_ffff;
+    v = (v | (v << 8)) & 0x00ff_00ff_00ff_00ff;
+    v = (v | (v << 4)) & 0x0f0f_0f0f_0f0f_0f0f;
+    v = (v | (v << 2)) & 0x3333_3333_3333_3333;
+    v = (v | (v << 1)) & 0x5555_5555_5555_5555;
+    v
+}
+
+fn morton_key(x: f64, y: f64, bbox: (f64, f64, f64, f64), bits: u32) -> u64 {
+    assert!(
+        (1..=32).contains(&bits),
+        "morton_key bits must be between 1 and 32"
+    );
+    let (xmin, ymin, xmax, ymax) = bbox;
+    spread_bits(quantize(x, xmin, xmax, bits)) | (spread_bits(quantize(y, ymin, ymax, bits)) << 1)
+}
+
+const GEOHASH_ALPHABET: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+/// Interleave longitude and latitude bisections, 5 bits per character, longitude first.
+fn geohash(lon: f64, lat: f64, precision: usize) -> Option<String> {
+    if !(-180.0..=180.0).contains(&lon) || !(-90.0..=90.0).contains(&lat) {
+        return None;
+    }
+    let (mut lon_range, mut lat_range) = ((-180.0, 180.0), (-90.0, 90.0));
+    let mut hash = String::with_capacity(precision);
+    let mut even = true;
+    for _ in 0..precision {
+        let mut idx = 0;
+        for _ in 0..5 {
+            let (v, range) = if even {
+                (lon, &mut lon_range)
+            } else {
+                (lat, &mut lat_range)
+            };
+            let mid = (range.0 + range.1) / 2.0;
+            idx <<= 1;
+            if v >= mid {
+                idx |= 1;
+                range.0 = mid;
+            } else {
+                range.1 = mid;
+            }
+            even = !even;
+        }
+        hash.push(GEOHASH_ALPHABET[idx] as char);
+    }
+    Some(hash)
+}
+
+macro_rules! impl_morton_key {
+    ($ptype:ident) => {
+        impl $ptype {
+            /// Z-order (Morton) key of the x/y coordinates, for sorting points by spatial locality.
+            ///
+            /// `bbox` is `(xmin, ymin, xmax, ymax)`. Each coordinate is quantized to `bits`
+            /// bits (1 to 32) within the box, coordinates outside are clamped to its edges.
+            /// Z and M values are ignored.
+            ///
+            /// # Panics
+            ///
+            /// If `bits` is not between 1 and 32.
+            pub fn morton_key(&self, bbox: (f64, f64, f64, f64), bits: u32) -> u64 {
+                morton_key(postgis::Point::x(self), postgis::Point::y(self), bbox, bits)
+            }
+
+            /// Geohash of the x/y coordinates as longitude/latitude, like `ST_GeoHash`.
+            ///
+            /// `precision` is the number of characters. Returns `None` if the longitude is
+            /// outside of [-180, 180] or the latitude outside of [-90, 90], which includes NaN.
+            /// The SRID is not checked.
+            pub fn geohash(&self, precision: usize) -> Option<String> {
+                geohash(postgis::Point::x(self), postgis::Point::y(self), precision)
+            }
+        }
+    };
+}
+
+impl_morton_key!(Point);
+impl_morton_key!(PointZ);
+impl_morton_key!(PointM);
+impl_morton_key!(PointZM);
+
+#[test]
+#[rustfmt::skip]
+fn test_morton_key() {
+    let bbox = (0.0, 0.0, 4.0, 4.0);
+    let key = |x, y| Point::new(x, y, None).morton_key(bbox, 2);
+    assert_eq!(key(0.5, 0.5), 0b0000);
+    assert_eq!(key(1.5, 0.5), 0b0001);
+    assert_eq!(key(0.5, 1.5), 0b0010);
+    assert_eq!(key(1.5, 1.5), 0b0011);
+    assert_eq!(key(2.5, 0.5), 0b0100);
+    assert_eq!(key(3.5, 3.5), 0b1111);
+    // Clamped to the bounding box
+    assert_eq!(key(-10.0, 100.0), key(0.0, 4.0));
+
+    let mut points = vec![Point::new(3.5, 3.5, None), Point::new(0.5, 1.5, None), Point::new(1.5, 0.5, None)];
+    points.sort_by_key(|p| p.morton_key(bbox, 2));
+    assert_eq!(points, vec![Point::new(1.5, 0.5, None), Point::new(0.5, 1.5, None), Point::new(3.5, 3.5, None)]);
+
+    let full = (-180.0, -90.0, 180.0, 90.0);
+    assert_eq!(PointZ::new(180.0, 90.0, 1.0, None).morton_key(full, 32), u64::MAX);
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_geohash() {
+    let p = |x, y| Point::new(x, y, Some(4326));
+    assert_eq!(p(-5.6, 42.6).geohash(5).as_deref(), Some("ezs42"));
+    assert_eq!(p(10.40744, 57.64911).geohash(11).as_deref(), Some("u4pruydqqvj"));
+    assert_eq!(PointZ::new(10.40744, 57.64911, 100.0, None).geohash(3).as_deref(), Some("u4p"));
+    assert_eq!(p(0.0, 0.0).geohash(0).as_deref(), Some(""));
+    assert_eq!(p(180.0, 90.0).geohash(4).as_deref(), Some("zzzz"));
+    assert_eq!(p(-180.0, -90.0).geohash(4).as_deref(), Some("0000"));
+    assert_eq!(p(180.1, 0.0).geohash(5), None);
+    assert_eq!(p(0.0, -90.5).geohash(5), None);
+    assert_eq!(p(f64::NAN, 0.0).geohash(5), None);
+}
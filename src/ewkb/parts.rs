@@ -0,0 +1,355 @@
+//! Splitting multi geometries into their parts and collecting collections into multi geometries.
+
+use crate::ewkb::*;
+
+// The SRID is propagated like `read_ewkb` does for a standalone geometry: into the points
+// and rings of single geometries, but not into the members of nested multi geometries.
+// Segments and rings of curves get the SRID as well.
+
+fn stamp_line<P: PointCoords>(line: &mut LineStringT<P>, srid: Option<i32>) {
+    line.srid = srid;
+    line.points.iter_mut().for_each(|p| p.set_srid(srid));
+}
+
+fn stamp_polygon<P: PointCoords>(polygon: &mut PolygonT<P>, srid: Option<i32>) {
+    polygon.srid = srid;
+    polygon.rings.iter_mut().for_each(|ring| stamp_line(ring, srid));
+}
+
+fn stamp_circular_string<P: PointCoords>(curve: &mut CircularStringT<P>, srid: Option<i32>) {
+    curve.srid = srid;
+    curve.points.iter_mut().for_each(|p| p.set_srid(srid));
+}
+
+fn stamp_compound_curve<P: PointCoords>(curve: &mut CompoundCurveT<P>, srid: Option<i32>) {
+    curve.srid = srid;
+    for segment in &mut curve.segments {
+        match segment {
+            CurveSegment::LineString(line) => stamp_line(line, srid),
+            CurveSegment::CircularString(curve) => stamp_circular_string(curve, srid),
+        }
+    }
+}
+
+pub(super) fn stamp_geometry<P: PointCoords>(geom: &mut GeometryT<P>, srid: Option<i32>) {
+    match geom {
+        GeometryT::Point(p) => p.set_srid(srid),
+        GeometryT::LineString(line) => stamp_line(line, srid),
+        GeometryT::Polygon(polygon) => stamp_polygon(polygon, srid),
+        GeometryT::MultiPoint(multi) => multi.srid = srid,
+        GeometryT::MultiLineString(multi) => multi.srid = srid,
+        GeometryT::MultiPolygon(multi) => multi.srid = srid,
+        GeometryT::GeometryCollection(collection) => collection.srid = srid,
+        GeometryT::CircularString(curve) => stamp_circular_string(curve, srid),
+        GeometryT::CompoundCurve(curve) => stamp_compound_curve(curve, srid),
+        GeometryT::CurvePolygon(polygon) => {
+            polygon.srid = srid;
+            for ring in &mut polygon.rings {
+                match ring {
+                    Curve::LineString(line) => stamp_line(line, srid),
+                    Curve::CircularString(curve) => stamp_circular_string(curve, srid),
+                    Curve::CompoundCurve(curve) => stamp_compound_curve(curve, srid),
+                }
+            }
+        }
+    }
+}
+
+impl<P: PointCoords> MultiPointT<P> {
+    /// Move the points out of the multi point, each carrying the SRID of the multi point.
+    ///
+    /// This is the client-side equivalent of `ST_Dump`.
+    pub fn into_parts(self) -> Vec<P> {
+        let srid = self.srid;
+        self.points
+            .into_iter()
+            .map(|mut p| {
+                p.set_srid(srid);
+                p
+            })
+            .collect()
+    }
+}
+
+impl<P: PointCoords> MultiLineStringT<P> {
+    /// Move the lines out of the multi line, each carrying the SRID of the multi line.
+    ///
+    /// This is the client-side equivalent of `ST_Dump`.
+    pub fn into_parts(self) -> Vec<LineStringT<P>> {
+        let srid = self.srid;
+        self.lines
+            .into_iter()
+            .map(|mut line| {
+                stamp_line(&mut line, srid);
+                line
+            })
+            .collect()
+    }
+}
+
+impl<P: PointCoords> MultiPolygonT<P> {
+    /// Move the polygons out of the multi polygon, each carrying the SRID of the multi polygon.
+    ///
+    /// This is the client-side equivalent of `ST_Dump`.
+    pub fn into_parts(self) -> Vec<PolygonT<P>> {
+        let srid = self.srid;
+        self.polygons
+            .into_iter()
+            .map(|mut polygon| {
+                stamp_polygon(&mut polygon, srid);
+                polygon
+            })
+            .collect()
+    }
+}
+
+impl<P: PointCoords> GeometryCollectionT<P> {
+    /// Move the geometries out of the collection, each carrying the SRID of the collection.
+    ///
+    /// Nested collections and multi geometries are returned as they are, not flattened.
+    pub fn into_parts(self) -> Vec<GeometryT<P>> {
+        let srid = self.srid;
+        self.geometries
+            .into_iter()
+            .map(|mut geom| {
+                stamp_geometry(&mut geom, srid);
+                geom
+            })
+            .collect()
+    }
+}
+
+fn push_points<P: PointCoords>(out: &mut Vec<P>, points: Vec<P>, srid: Option<i32>) {
+    out.extend(points.into_iter().map(|mut p| {
+        p.set_srid(srid.or(p.opt_srid()));
+        p
+    }));
+}
+
+fn collect_points<P: PointCoords>(geom: GeometryT<P>, srid: Option<i32>, out: &mut Vec<P>) {
+    let segments = |out: &mut Vec<P>, curve: CompoundCurveT<P>, srid: Option<i32>| {
+        let srid = srid.or(curve.srid);
+        for segment in curve.segments {
+            match segment {
+                CurveSegment::LineString(g) => push_points(out, g.points, srid),
+                CurveSegment::CircularString(g) => push_points(out, g.points, srid),
+            }
+        }
+    };
+    match geom {
+        GeometryT::Point(p) => push_points(out, vec![p], srid),
+        GeometryT::LineString(g) => push_points(out, g.points, srid.or(g.srid)),
+        GeometryT::CircularString(g) => push_points(out, g.points, srid.or(g.srid)),
+        GeometryT::CompoundCurve(g) => segments(out, g, srid),
+        GeometryT::Polygon(g) => {
+            let srid = srid.or(g.srid);
+            for ring in g.rings {
+                push_points(out, ring.points, srid);
+            }
+        }
+        GeometryT::CurvePolygon(g) => {
+            let srid = srid.or(g.srid);
+            for ring in g.rings {
+                match ring {
+                    Curve::LineString(g) => push_points(out, g.points, srid),
+                    Curve::CircularString(g) => push_points(out, g.points, srid),
+                    Curve::CompoundCurve(g) => segments(out, g, srid),
+                }
+            }
+        }
+        GeometryT::MultiPoint(g) => push_points(out, g.points, srid.or(g.srid)),
+        GeometryT::MultiLineString(g) => {
+            let srid = srid.or(g.srid);
+            for line in g.lines {
+                push_points(out, line.points, srid);
+            }
+        }
+        GeometryT::MultiPolygon(g) => {
+            let srid = srid.or(g.srid);
+            for ring in g.polygons.into_iter().flat_map(|p| p.rings) {
+                push_points(out, ring.points, srid);
+            }
+        }
+        GeometryT::GeometryCollection(g) => {
+            let srid = srid.or(g.srid);
+            for geom in g.geometries {
+                collect_points(geom, srid, out);
+            }
+        }
+    }
+}
+
+impl<P: PointCoords> GeometryT<P> {
+    /// Move all points out of the geometry, recursing into collections.
+    ///
+    /// Each point carries the SRID of the outermost geometry that has one, or keeps its own.
+    /// Closing points of rings and shared points of curve segments are returned as often as
+    /// they occur.
+    pub fn into_points(self) -> Vec<P> {
+        let mut points = Vec::new();
+        collect_points(self, None, &mut points);
+        points
+    }
+}
+
+/// Name of the geometry type, as used in EWKB read errors.
+pub(crate) fn type_name<P: PointCoords>(geom: &GeometryT<P>) -> &'static str {
+    geometry_type_name(match geom {
+        GeometryT::Point(_) => 0x01,
+        GeometryT::LineString(_) => 0x02,
+        GeometryT::Polygon(_) => 0x03,
+        GeometryT::MultiPoint(_) => 0x04,
+        GeometryT::MultiLineString(_) => 0x05,
+        GeometryT::MultiPolygon(_) => 0x06,
+        GeometryT::GeometryCollection(_) => 0x07,
+        GeometryT::CircularString(_) => 0x08,
+        GeometryT::CompoundCurve(_) => 0x09,
+        GeometryT::CurvePolygon(_) => 0x0a,
+    })
+}
+
+macro_rules! impl_try_into_multi {
+    ($fname:ident, $multitype:ident, $variant:ident, $itemname:ident) => {
+        /// Convert a collection whose members are all of the same type into the multi geometry.
+        ///
+        /// The collection's SRID is kept, the members are moved as they are. Fails with
+        /// `Error::Other` naming the first member of a different type.
+        pub fn $fname(self) -> Result<$multitype<P>, Error> {
+            let mut multi = $multitype::new();
+            multi.srid = self.srid;
+            multi.$itemname.reserve(self.geometries.len());
+            for (i, geom) in self.geometries.into_iter().enumerate() {
+                match geom {
+                    GeometryT::$variant(item) => multi.$itemname.push(item),
+                    other => {
+                        return Err(Error::Other(format!(
+                            "geometry {} of the collection is a {}, expected {}",
+                            i,
+                            type_name(&other),
+                            stringify!($variant)
+                        )));
+                    }
+                }
+            }
+            Ok(multi)
+        }
+    };
+}
+
+impl<P: PointCoords> GeometryCollectionT<P> {
+    impl_try_into_multi!(try_into_multipoint, MultiPointT, Point, points);
+    impl_try_into_multi!(try_into_multilinestring, MultiLineStringT, LineString, lines);
+    impl_try_into_multi!(try_into_multipolygon, MultiPolygonT, Polygon, polygons);
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_try_into_multi() {
+    let p = |x, y| Point::new(x, y, None);
+    let ring = LineStringT::<Point> {srid: None, points: vec![p(0., 0.), p(1., 0.), p(1., 1.), p(0., 0.)]};
+    let poly = PolygonT::<Point> {srid: None, rings: vec![ring.clone()]};
+    let collection = GeometryCollectionT::<Point> {srid: Some(4326), geometries: vec![
+        GeometryT::Polygon(poly.clone()), GeometryT::Polygon(poly.clone())]};
+    let multi = collection.clone().try_into_multipolygon().unwrap();
+    assert_eq!(multi.srid, Some(4326));
+    assert_eq!(multi.polygons, vec![poly.clone(), poly.clone()]);
+    let err = collection.try_into_multilinestring().unwrap_err();
+    assert_eq!(err.to_string(), r#"Other("geometry 0 of the collection is a Polygon, expected LineString")"#);
+
+    let collection = GeometryCollectionT::<Point> {srid: None, geometries: vec![
+        GeometryT::Point(p(1., 2.)), GeometryT::MultiPoint(MultiPointT::new())]};
+    let err = collection.try_into_multipoint().unwrap_err();
+    assert_eq!(err.to_string(), r#"Other("geometry 1 of the collection is a MultiPoint, expected Point")"#);
+
+    let empty = GeometryCollectionT::<Point>::new().try_into_multilinestring().unwrap();
+    assert!(empty.lines.is_empty());
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_into_parts() {
+    let p = |x, y| Point::new(x, y, None);
+    let multi = MultiPointT::<Point> {srid: Some(4326), points: vec![p(1., 2.), p(3., 4.)]};
+    assert_eq!(multi.into_parts(), vec![Point::new(1., 2., Some(4326)), Point::new(3., 4., Some(4326))]);
+
+    let line = LineStringT::<Point> {srid: None, points: vec![p(0., 0.), p(1., 1.)]};
+    let multi = MultiLineStringT::<Point> {srid: Some(3857), lines: vec![line.clone(), line.clone()]};
+    let parts = multi.into_parts();
+    assert_eq!(parts.len(), 2);
+    assert_eq!(parts[1].srid, Some(3857));
+    assert_eq!(parts[1].points[1], Point::new(1., 1., Some(3857)));
+
+    let ring = LineStringT::<Point> {srid: None, points: vec![p(0., 0.), p(1., 0.), p(1., 1.), p(0., 0.)]};
+    let poly = PolygonT::<Point> {srid: None, rings: vec![ring.clone()]};
+    let multi = MultiPolygonT::<Point> {srid: Some(4326), polygons: vec![poly.clone()]};
+    let parts = multi.into_parts();
+    assert_eq!(parts[0].srid, Some(4326));
+    assert_eq!(parts[0].rings[0].srid, Some(4326));
+    assert!(parts[0].rings[0].points.iter().all(|p| p.srid == Some(4326)));
+    // Parts equal the geometries read from their own EWKB
+    let ewkb = parts[0].as_ewkb().to_ewkb_bytes();
+    assert_eq!(PolygonT::<Point>::read_ewkb(&mut ewkb.as_slice()).unwrap(), parts[0]);
+
+    let inner = MultiPointT::<Point> {srid: None, points: vec![p(5., 5.)]};
+    let collection = GeometryCollectionT::<Point> {srid: Some(4326), geometries: vec![
+        GeometryT::Point(p(1., 1.)), GeometryT::Polygon(poly), GeometryT::MultiPoint(inner)]};
+    let parts = collection.into_parts();
+    assert_eq!(parts.len(), 3);
+    match &parts[0] { GeometryT::Point(p) => assert_eq!(p.srid, Some(4326)), _ => panic!() }
+    match &parts[1] { GeometryT::Polygon(poly) => assert_eq!(poly.rings[0].points[0].srid, Some(4326)), _ => panic!() }
+    match &parts[2] { GeometryT::MultiPoint(multi) => {
+        assert_eq!(multi.srid, Some(4326));
+        assert_eq!(multi.points[0].srid, None);
+    }, _ => panic!() }
+
+    // Segments and rings of curves get the SRID as well
+    let arc = CircularStringT::<Point> {srid: None, points: vec![p(0., 0.), p(1., 1.), p(2., 0.)]};
+    let compound = CompoundCurveT::<Point> {srid: None, segments: vec![
+        CurveSegment::CircularString(arc.clone()),
+        CurveSegment::LineString(LineStringT {srid: None, points: vec![p(2., 0.), p(0., 0.)]}),
+    ]};
+    let curve_polygon = CurvePolygonT::<Point> {srid: None, rings: vec![Curve::CompoundCurve(compound.clone()), Curve::CircularString(arc)]};
+    let collection = GeometryCollectionT::<Point> {srid: Some(4326), geometries: vec![
+        GeometryT::CompoundCurve(compound), GeometryT::CurvePolygon(curve_polygon)]};
+    let parts = collection.into_parts();
+    match &parts[0] { GeometryT::CompoundCurve(curve) => match &curve.segments[1] {
+        CurveSegment::LineString(line) => assert_eq!(line.srid, Some(4326)),
+        _ => panic!(),
+    }, _ => panic!() }
+    match &parts[1] { GeometryT::CurvePolygon(polygon) => match &polygon.rings[1] {
+        Curve::CircularString(arc) => assert_eq!(arc.srid, Some(4326)),
+        _ => panic!(),
+    }, _ => panic!() }
+    for part in parts {
+        assert!(part.into_points().iter().all(|p| p.srid == Some(4326)));
+    }
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_into_points() {
+    let p = |x, y| Point::new(x, y, None);
+    let ring = LineStringT::<Point> {srid: None, points: vec![p(0., 0.), p(1., 0.), p(1., 1.), p(0., 0.)]};
+    let collection = GeometryT::GeometryCollection(GeometryCollectionT::<Point> {srid: Some(4326), geometries: vec![
+        GeometryT::Point(p(5., 5.)),
+        GeometryT::MultiPolygon(MultiPolygonT {srid: None, polygons: vec![PolygonT {srid: None, rings: vec![ring.clone()]}]}),
+        GeometryT::GeometryCollection(GeometryCollectionT {srid: None, geometries: vec![GeometryT::LineString(ring)]}),
+    ]});
+    let points = collection.into_points();
+    assert_eq!(points.len(), 9);
+    assert_eq!(points[0], Point::new(5., 5., Some(4326)));
+    assert_eq!(points[4], Point::new(0., 0., Some(4326)));
+    assert!(points.iter().all(|p| p.srid == Some(4326)));
+
+    // Points without an enclosing SRID keep their own
+    let multi = GeometryT::MultiPoint(MultiPointT::<PointZ> {srid: None, points: vec![PointZ::new(1., 2., 3., Some(3857)), PointZ::new(4., 5., 6., None)]});
+    assert_eq!(multi.into_points(), vec![PointZ::new(1., 2., 3., Some(3857)), PointZ::new(4., 5., 6., None)]);
+
+    let curve = CompoundCurveT::<Point> {srid: Some(4326), segments: vec![
+        CurveSegment::CircularString(CircularStringT {srid: None, points: vec![p(0., 0.), p(1., 1.), p(2., 0.)]}),
+        CurveSegment::LineString(LineStringT {srid: None, points: vec![p(2., 0.), p(3., 0.)]}),
+    ]};
+    let points = GeometryT::CurvePolygon(CurvePolygonT {srid: None, rings: vec![Curve::CompoundCurve(curve)]}).into_points();
+    assert_eq!(points.iter().map(|p| (p.x(), p.y(), p.srid)).collect::<Vec<_>>(),
+               vec![(0., 0., Some(4326)), (1., 1., Some(4326)), (2., 0., Some(4326)), (2., 0., Some(4326)), (3., 0., Some(4326))]);
+}
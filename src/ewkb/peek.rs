@@ -0,0 +1,205 @@
+//! Header inspection without decoding coordinates, and checks against column declarations.
+
+use crate::ewkb::*;
+
+/// Read the SRID of an EWKB geometry from its header.
+///
+/// Only the byte order, type id and SRID are read, nothing is allocated. Returns `None`
+/// if the SRID flag isn't set, e.g. for ISO WKB.
+pub fn peek_srid(mut raw: &[u8]) -> Result<Option<i32>, Error> {
+    let (_, _, srid) = read_ewkb_header(&mut raw, &mut ReadContext::default())?;
+    Ok(srid)
+}
+
+/// Geometry type of an EWKB header, see [`peek_geom_type`].
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum GeometryKind {
+    Point,
+    LineString,
+    Polygon,
+    MultiPoint,
+    MultiLineString,
+    MultiPolygon,
+    GeometryCollection,
+    CircularString,
+    CompoundCurve,
+    CurvePolygon,
+}
+
+/// Geometry type and dimensions of an EWKB geometry.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct GeomKind {
+    pub kind: GeometryKind,
+    pub point_type: PointType,
+}
+
+/// Read the geometry type and dimensions of an EWKB or ISO WKB geometry from its header.
+///
+/// Like [`peek_srid`], coordinates are not decoded. The type of sub-geometries isn't checked,
+/// e.g. a geometry collection is reported without looking at its contents. Type ids not
+/// supported by [`GeometryT`] are rejected with an error.
+pub fn peek_geom_type(mut raw: &[u8]) -> Result<GeomKind, Error> {
+    let (_, type_id, _) = read_ewkb_header(&mut raw, &mut ReadContext::default())?;
+    let kind = match type_id & 0xff {
+        0x01 => GeometryKind::Point,
+        0x02 => GeometryKind::LineString,
+        0x03 => GeometryKind::Polygon,
+        0x04 => GeometryKind::MultiPoint,
+        0x05 => GeometryKind::MultiLineString,
+        0x06 => GeometryKind::MultiPolygon,
+        0x07 => GeometryKind::GeometryCollection,
+        0x08 => GeometryKind::CircularString,
+        0x09 => GeometryKind::CompoundCurve,
+        0x0a => GeometryKind::CurvePolygon,
+        _ => {
+            return Err(Error::Read(format!(
+                "unsupported geometry type id {}",
+                type_id & 0xff
+            )));
+        }
+    };
+    Ok(GeomKind {
+        kind,
+        point_type: PointType::from_type_id(type_id),
+    })
+}
+
+/// Reader checking geometries against a column declaration, as listed in `geometry_columns`.
+///
+/// `geom_type` corresponds to the columns `type` and `coord_dimension`, e.g. `POLYGON` with
+/// 2 dimensions is `GeomKind { kind: GeometryKind::Polygon, point_type: PointType::Point }`.
+/// An `srid` of `None` accepts any SRID, like an SRID of 0 in `geometry_columns`.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct TypedGeometryReader {
+    pub geom_type: GeomKind,
+    pub srid: Option<i32>,
+}
+
+impl TypedGeometryReader {
+    /// Read a geometry, failing with `Error::Read` if it doesn't match the declaration.
+    ///
+    /// Type, dimensions and SRID are checked from the header before decoding. The
+    /// dimensions of sub-geometries are checked like in [`EwkbRead::read_ewkb_strict`].
+    /// `P` has to match the declared dimensions, otherwise `Error::Other` is returned.
+    pub fn read<P: PointCoords>(&self, raw: &[u8]) -> Result<GeometryT<P>, Error> {
+        if P::point_type() != self.geom_type.point_type {
+            return Err(Error::Other(format!(
+                "reader for {:?} coordinates used with {:?}",
+                self.geom_type.point_type,
+                P::point_type()
+            )));
+        }
+        let found = peek_geom_type(raw)?;
+        if found != self.geom_type {
+            return Err(Error::Read(format!(
+                "expected {:?} with {:?} coordinates, found {:?} with {:?} coordinates",
+                self.geom_type.kind, self.geom_type.point_type, found.kind, found.point_type
+            )));
+        }
+        if let Some(srid) = self.srid {
+            let found = peek_srid(raw)?;
+            if found != Some(srid) {
+                return Err(Error::Read(format!(
+                    "expected SRID {}, found {:?}",
+                    srid, found
+                )));
+            }
+        }
+        GeometryT::read_ewkb_strict(&mut &raw[..])
+    }
+}
+
+/// EWKB bytes of a geometry, passed through without decoding.
+///
+/// With the `postgres` feature, values of `geometry` and `geography` columns are read and
+/// written verbatim, e.g. to copy geometries between tables without parsing them. The
+/// SRID of `geography` values isn't normalized, unlike when reading through `Geography`.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct RawGeometry(pub Vec<u8>);
+
+impl RawGeometry {
+    /// Geometry type and dimensions from the header, see [`peek_geom_type`].
+    pub fn geom_type(&self) -> Result<GeomKind, Error> {
+        peek_geom_type(&self.0)
+    }
+
+    /// SRID from the header, see [`peek_srid`].
+    pub fn srid(&self) -> Result<Option<i32>, Error> {
+        peek_srid(&self.0)
+    }
+
+    /// Decode the geometry.
+    pub fn decode<P: PointCoords>(&self) -> Result<GeometryT<P>, Error> {
+        GeometryT::read_ewkb(&mut self.0.as_slice())
+    }
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_peek_srid() {
+    // SELECT 'SRID=4326;POINT (10 -20)'::geometry
+    let ewkb = hex_to_vec("0101000020E6100000000000000000244000000000000034C0");
+    assert_eq!(peek_srid(&ewkb).unwrap(), Some(4326));
+    // Coordinates are not required
+    assert_eq!(peek_srid(&ewkb[..9]).unwrap(), Some(4326));
+    // Big endian
+    let ewkb = hex_to_vec("0020000001000010E64024000000000000C034000000000000");
+    assert_eq!(peek_srid(&ewkb).unwrap(), Some(4326));
+    // SELECT 'LINESTRING (10 -20, 0 -0.5)'::geometry
+    let ewkb = hex_to_vec("010200000002000000000000000000244000000000000034C00000000000000000000000000000E0BF");
+    assert_eq!(peek_srid(&ewkb).unwrap(), None);
+    assert!(peek_srid(&ewkb[..3]).is_err());
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_peek_geom_type() {
+    let kind = |kind, point_type| GeomKind {kind, point_type};
+    // SELECT 'SRID=4326;MULTIPOINT ((10 -20 100), (0 -0.5 101))'::geometry
+    let ewkb = hex_to_vec("01040000A0E6100000020000000101000080000000000000244000000000000034C0000000000000594001010000800000000000000000000000000000E0BF0000000000405940");
+    assert_eq!(peek_geom_type(&ewkb).unwrap(), kind(GeometryKind::MultiPoint, PointType::PointZ));
+    // ISO WKB: SELECT ST_AsBinary('LINESTRING M (10 -20 1, 0 -0.5 2)'::geometry)
+    let wkb = hex_to_vec("01D207000002000000000000000000244000000000000034C0000000000000F03F0000000000000000000000000000E0BF0000000000000040");
+    assert_eq!(peek_geom_type(&wkb).unwrap(), kind(GeometryKind::LineString, PointType::PointM));
+    // SELECT 'CURVEPOLYGON EMPTY'::geometry
+    let ewkb = hex_to_vec("010A00000000000000");
+    assert_eq!(peek_geom_type(&ewkb).unwrap(), kind(GeometryKind::CurvePolygon, PointType::Point));
+    // Triangle
+    let ewkb = hex_to_vec("011100000000000000");
+    assert_eq!(peek_geom_type(&ewkb).unwrap_err().to_string(), r#"Read("unsupported geometry type id 17")"#);
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_typed_geometry_reader() {
+    let reader = TypedGeometryReader {
+        geom_type: GeomKind {kind: GeometryKind::LineString, point_type: PointType::Point},
+        srid: Some(4326),
+    };
+    // SELECT 'SRID=4326;LINESTRING (10 -20, 0 -0.5)'::geometry
+    let ewkb = hex_to_vec("0102000020E610000002000000000000000000244000000000000034C00000000000000000000000000000E0BF");
+    assert!(matches!(reader.read::<Point>(&ewkb), Ok(GeometryT::LineString(line)) if line.points.len() == 2));
+    assert_eq!(reader.read::<PointZ>(&ewkb).unwrap_err().to_string(), r#"Other("reader for Point coordinates used with PointZ")"#);
+
+    // SELECT 'LINESTRING (10 -20, 0 -0.5)'::geometry
+    let ewkb = hex_to_vec("010200000002000000000000000000244000000000000034C00000000000000000000000000000E0BF");
+    assert_eq!(reader.read::<Point>(&ewkb).unwrap_err().to_string(), r#"Read("expected SRID 4326, found None")"#);
+    assert!(TypedGeometryReader {srid: None, ..reader}.read::<Point>(&ewkb).is_ok());
+
+    // SELECT 'SRID=4326;POINT (10 -20)'::geometry
+    let ewkb = hex_to_vec("0101000020E6100000000000000000244000000000000034C0");
+    assert_eq!(reader.read::<Point>(&ewkb).unwrap_err().to_string(),
+               r#"Read("expected LineString with Point coordinates, found Point with Point coordinates")"#);
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_raw_geometry() {
+    // SELECT 'SRID=4326;LINESTRING (10 -20, 0 -0.5)'::geometry
+    let raw = RawGeometry(hex_to_vec("0102000020E610000002000000000000000000244000000000000034C00000000000000000000000000000E0BF"));
+    assert_eq!(raw.geom_type().unwrap(), GeomKind {kind: GeometryKind::LineString, point_type: PointType::Point});
+    assert_eq!(raw.srid().unwrap(), Some(4326));
+    let geom = raw.decode::<Point>().unwrap();
+    assert_eq!(geom.as_ewkb().to_ewkb_bytes(), raw.0);
+    assert!(RawGeometry::default().geom_type().is_err());
+}
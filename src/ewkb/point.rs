@@ -1,10 +1,11 @@
 use crate::{error::Error, types as postgis};
 use byteorder::{LittleEndian, WriteBytesExt};
+#[cfg(feature = "geo-types")]
 use geo_types::geometry::Point as _Point;
 use std::fmt;
 use std::io::prelude::*;
 
-use super::{has_m, has_z, read_f64, EwkbRead, EwkbWrite};
+use super::{has_m, has_z, read_f64, EwkbRead, EwkbWrite, ReadContext};
 
 pub struct EwkbPoint<'a> {
     pub geom: &'a dyn postgis::Point,
@@ -31,14 +32,37 @@ impl EwkbWrite for EwkbPoint<'_> {
         self.srid
     }
     fn write_ewkb_body<W: Write + ?Sized>(&self, w: &mut W) -> Result<(), Error> {
-        w.write_f64::<LittleEndian>(self.geom.x())?;
-        w.write_f64::<LittleEndian>(self.geom.y())?;
-        self.geom.opt_z().map(|z| w.write_f64::<LittleEndian>(z));
-        self.geom.opt_m().map(|m| w.write_f64::<LittleEndian>(m));
-        Ok(())
+        write_point_coords(self.geom, w)
+    }
+    fn ewkb_body_size_hint(&self) -> usize {
+        point_coords_size(self.geom)
     }
 }
 
+/// Write the coordinates of a point without header, as contained in lines and rings.
+pub(crate) fn write_point_coords<P, W>(p: &P, w: &mut W) -> Result<(), Error>
+where
+    P: postgis::Point + ?Sized,
+    W: Write + ?Sized,
+{
+    w.write_f64::<LittleEndian>(p.x())?;
+    w.write_f64::<LittleEndian>(p.y())?;
+    if let Some(z) = p.opt_z() {
+        w.write_f64::<LittleEndian>(z)?;
+    }
+    if let Some(m) = p.opt_m() {
+        w.write_f64::<LittleEndian>(m)?;
+    }
+    Ok(())
+}
+
+/// Number of bytes written by [`write_point_coords`].
+pub(crate) fn point_coords_size<P: postgis::Point + ?Sized>(p: &P) -> usize {
+    let z_size = if p.opt_z().is_some() { 8 } else { 0 };
+    let m_size = if p.opt_m().is_some() { 8 } else { 0 };
+    16 + z_size + m_size
+}
+
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(PartialEq, Clone, Copy, Debug)]
 pub enum PointType {
@@ -48,11 +72,57 @@ pub enum PointType {
     PointZM,
 }
 
+impl PointType {
+    /// Point type from the Z and M flags of an EWKB type id, e.g. `0xC0000003` is `PointZM`.
+    ///
+    /// The geometry type and the SRID flag are ignored.
+    pub const fn from_type_id(type_id: u32) -> PointType {
+        match (type_id & 0x80000000 != 0, type_id & 0x40000000 != 0) {
+            (false, false) => PointType::Point,
+            (true, false) => PointType::PointZ,
+            (false, true) => PointType::PointM,
+            (true, true) => PointType::PointZM,
+        }
+    }
+    /// Whether points of this type have a Z coordinate.
+    pub const fn has_z(&self) -> bool {
+        matches!(self, PointType::PointZ | PointType::PointZM)
+    }
+    /// Whether points of this type have an M value.
+    pub const fn has_m(&self) -> bool {
+        matches!(self, PointType::PointM | PointType::PointZM)
+    }
+    /// Number of ordinates per point, between 2 and 4.
+    pub const fn num_coords(&self) -> usize {
+        2 + self.has_z() as usize + self.has_m() as usize
+    }
+}
+
+/// 2D point.
+///
+/// With the `geo-types` feature, the coordinates are stored as `geo_types::Point` in the
+/// field `point`, otherwise in the fields `x` and `y`. Both have the same serde representation.
+#[cfg(feature = "geo-types")]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(PartialEq, Clone, Copy, Debug, Default)]
 pub struct Point {
     #[cfg_attr(feature = "serde", serde(flatten))]
     pub point: _Point,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub srid: Option<i32>,
+}
+
+/// 2D point.
+///
+/// With the `geo-types` feature, the coordinates are stored as `geo_types::Point` in the
+/// field `point`, otherwise in the fields `x` and `y`. Both have the same serde representation.
+#[cfg(not(feature = "geo-types"))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Clone, Copy, Debug, Default)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+    #[cfg_attr(feature = "serde", serde(default))]
     pub srid: Option<i32>,
 }
 
@@ -62,15 +132,36 @@ pub struct PointZ {
     pub x: f64,
     pub y: f64,
     pub z: f64,
+    #[cfg_attr(feature = "serde", serde(default))]
     pub srid: Option<i32>,
 }
 
+/// XYZ coordinates with a guaranteed C layout of three consecutive `f64`, for FFI.
+///
+/// `PointZ` can't be passed to C directly because of its `srid` field, so slices of
+/// points have to be copied into `CoordZ` values, see [`LineStringT::to_coords`](super::LineStringT::to_coords).
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Clone, Copy, Debug, Default)]
+#[repr(C)]
+pub struct CoordZ {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl From<CoordZ> for PointZ {
+    fn from(c: CoordZ) -> Self {
+        Self::new(c.x, c.y, c.z, None)
+    }
+}
+
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(PartialEq, Clone, Copy, Debug, Default)]
 pub struct PointM {
     pub x: f64,
     pub y: f64,
     pub m: f64,
+    #[cfg_attr(feature = "serde", serde(default))]
     pub srid: Option<i32>,
 }
 
@@ -81,16 +172,42 @@ pub struct PointZM {
     pub y: f64,
     pub z: f64,
     pub m: f64,
+    #[cfg_attr(feature = "serde", serde(default))]
     pub srid: Option<i32>,
 }
 
+/// Parse exactly `N` numbers separated by commas and/or whitespace.
+fn parse_coords<const N: usize>(s: &str) -> Result<[f64; N], Error> {
+    let values = s
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|v| !v.is_empty())
+        .map(|v| {
+            v.parse::<f64>()
+                .map_err(|_| Error::Other(format!("invalid coordinate {:?} in {:?}", v, s)))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    values.try_into().map_err(|values: Vec<f64>| {
+        Error::Other(format!(
+            "expected {} coordinates, found {} in {:?}",
+            N,
+            values.len(),
+            s
+        ))
+    })
+}
+
 impl Point {
+    #[cfg(feature = "geo-types")]
     pub fn new(x: f64, y: f64, srid: Option<i32>) -> Self {
         Self {
             point: _Point::new(x, y),
             srid,
         }
     }
+    #[cfg(not(feature = "geo-types"))]
+    pub fn new(x: f64, y: f64, srid: Option<i32>) -> Self {
+        Self { x, y, srid }
+    }
     pub fn new_from_opt_vals(
         x: f64,
         y: f64,
@@ -100,14 +217,39 @@ impl Point {
     ) -> Self {
         Self::new(x, y, srid)
     }
+    /// Parse a point without SRID from 2 numbers separated by commas or whitespace, e.g. `"10,-20"`.
+    pub fn from_coord_str(s: &str) -> Result<Self, Error> {
+        let [x, y] = parse_coords(s)?;
+        Ok(Self::new(x, y, None))
+    }
+    /// Create a point without SRID from `(x, y)`.
+    pub fn from_tuple((x, y): (f64, f64)) -> Self {
+        Self::new(x, y, None)
+    }
+    /// Coordinates as `(x, y)`, without SRID.
+    pub fn to_tuple(&self) -> (f64, f64) {
+        (self.x(), self.y())
+    }
 
+    #[cfg(feature = "geo-types")]
     pub fn x(&self) -> f64 {
         self.point.x()
     }
 
+    #[cfg(feature = "geo-types")]
     pub fn y(&self) -> f64 {
         self.point.y()
     }
+
+    #[cfg(not(feature = "geo-types"))]
+    pub fn x(&self) -> f64 {
+        self.x
+    }
+
+    #[cfg(not(feature = "geo-types"))]
+    pub fn y(&self) -> f64 {
+        self.y
+    }
 }
 
 impl From<(f64, f64)> for Point {
@@ -116,15 +258,27 @@ impl From<(f64, f64)> for Point {
     }
 }
 
+impl From<Point> for (f64, f64) {
+    fn from(p: Point) -> Self {
+        p.to_tuple()
+    }
+}
+
 impl postgis::Point for Point {
     fn x(&self) -> f64 {
-        self.point.x()
+        Point::x(self)
     }
     fn y(&self) -> f64 {
-        self.point.y()
+        Point::y(self)
     }
 }
 
+/// Value of a dimension missing in the source: NaN for empty points, whose X and Y are NaN,
+/// otherwise 0.
+fn missing_coord(x: f64, y: f64) -> f64 {
+    if x.is_nan() && y.is_nan() { f64::NAN } else { 0.0 }
+}
+
 impl PointZ {
     pub fn new(x: f64, y: f64, z: f64, srid: Option<i32>) -> Self {
         Self { x, y, z, srid }
@@ -136,7 +290,32 @@ impl PointZ {
         _m: Option<f64>,
         srid: Option<i32>,
     ) -> Self {
-        Self::new(x, y, z.unwrap_or(0.0), srid)
+        Self::new(x, y, z.unwrap_or_else(|| missing_coord(x, y)), srid)
+    }
+    /// Parse a point without SRID from 3 numbers separated by commas or whitespace, e.g. `"10 -20 100"`.
+    pub fn from_coord_str(s: &str) -> Result<Self, Error> {
+        let [x, y, z] = parse_coords(s)?;
+        Ok(Self::new(x, y, z, None))
+    }
+    /// Create a point without SRID from `(x, y, z)`.
+    pub fn from_tuple((x, y, z): (f64, f64, f64)) -> Self {
+        Self::new(x, y, z, None)
+    }
+    /// Coordinates as `(x, y, z)`, without SRID.
+    pub fn to_tuple(&self) -> (f64, f64, f64) {
+        (self.x, self.y, self.z)
+    }
+    /// Coordinates without SRID, with a C compatible layout.
+    pub fn coord(&self) -> CoordZ {
+        CoordZ {
+            x: self.x,
+            y: self.y,
+            z: self.z,
+        }
+    }
+    /// Add the measure `m`, keeping coordinates and SRID.
+    pub fn to_zm(&self, m: f64) -> PointZM {
+        PointZM::new(self.x, self.y, self.z, m, self.srid)
     }
 }
 
@@ -146,6 +325,12 @@ impl From<(f64, f64, f64)> for PointZ {
     }
 }
 
+impl From<PointZ> for (f64, f64, f64) {
+    fn from(p: PointZ) -> Self {
+        p.to_tuple()
+    }
+}
+
 impl postgis::Point for PointZ {
     fn x(&self) -> f64 {
         self.x
@@ -169,7 +354,24 @@ impl PointM {
         m: Option<f64>,
         srid: Option<i32>,
     ) -> Self {
-        Self::new(x, y, m.unwrap_or(0.0), srid)
+        Self::new(x, y, m.unwrap_or_else(|| missing_coord(x, y)), srid)
+    }
+    /// Parse a point without SRID from x, y and m separated by commas or whitespace.
+    pub fn from_coord_str(s: &str) -> Result<Self, Error> {
+        let [x, y, m] = parse_coords(s)?;
+        Ok(Self::new(x, y, m, None))
+    }
+    /// Create a point without SRID from `(x, y, m)`.
+    pub fn from_tuple((x, y, m): (f64, f64, f64)) -> Self {
+        Self::new(x, y, m, None)
+    }
+    /// Coordinates as `(x, y, m)`, without SRID.
+    pub fn to_tuple(&self) -> (f64, f64, f64) {
+        (self.x, self.y, self.m)
+    }
+    /// Add the Z coordinate `z`, keeping coordinates, measure and SRID.
+    pub fn to_zm(&self, z: f64) -> PointZM {
+        PointZM::new(self.x, self.y, z, self.m, self.srid)
     }
 }
 
@@ -179,6 +381,12 @@ impl From<(f64, f64, f64)> for PointM {
     }
 }
 
+impl From<PointM> for (f64, f64, f64) {
+    fn from(p: PointM) -> Self {
+        p.to_tuple()
+    }
+}
+
 impl postgis::Point for PointM {
     fn x(&self) -> f64 {
         self.x
@@ -202,7 +410,29 @@ impl PointZM {
         m: Option<f64>,
         srid: Option<i32>,
     ) -> Self {
-        Self::new(x, y, z.unwrap_or(0.0), m.unwrap_or(0.0), srid)
+        let missing = missing_coord(x, y);
+        Self::new(x, y, z.unwrap_or(missing), m.unwrap_or(missing), srid)
+    }
+    /// Parse a point without SRID from x, y, z and m separated by commas or whitespace.
+    pub fn from_coord_str(s: &str) -> Result<Self, Error> {
+        let [x, y, z, m] = parse_coords(s)?;
+        Ok(Self::new(x, y, z, m, None))
+    }
+    /// Create a point without SRID from `(x, y, z, m)`.
+    pub fn from_tuple((x, y, z, m): (f64, f64, f64, f64)) -> Self {
+        Self::new(x, y, z, m, None)
+    }
+    /// Coordinates as `(x, y, z, m)`, without SRID.
+    pub fn to_tuple(&self) -> (f64, f64, f64, f64) {
+        (self.x, self.y, self.z, self.m)
+    }
+    /// Drop the Z coordinate, keeping the measure and SRID.
+    pub fn to_m(&self) -> PointM {
+        PointM::new(self.x, self.y, self.m, self.srid)
+    }
+    /// Drop the measure, keeping the Z coordinate and SRID.
+    pub fn to_z(&self) -> PointZ {
+        PointZ::new(self.x, self.y, self.z, self.srid)
     }
 }
 
@@ -212,6 +442,12 @@ impl From<(f64, f64, f64, f64)> for PointZM {
     }
 }
 
+impl From<PointZM> for (f64, f64, f64, f64) {
+    fn from(p: PointZM) -> Self {
+        p.to_tuple()
+    }
+}
+
 impl postgis::Point for PointZM {
     fn x(&self) -> f64 {
         self.x
@@ -227,6 +463,36 @@ impl postgis::Point for PointZM {
     }
 }
 
+/// Construction and SRID access shared by all point types, for generic geometry operations.
+pub trait PointCoords: postgis::Point + EwkbRead {
+    /// Create a point, ignoring the dimensions not supported by the point type.
+    ///
+    /// Dimensions of the point type missing in the arguments are set to 0, or to NaN if
+    /// X and Y are NaN, so that empty points stay empty.
+    fn new_from_opt_vals(
+        x: f64,
+        y: f64,
+        z: Option<f64>,
+        m: Option<f64>,
+        srid: Option<i32>,
+    ) -> Self;
+    fn opt_srid(&self) -> Option<i32>;
+    fn set_srid(&mut self, srid: Option<i32>);
+}
+
+/// Reject NaN and infinite coordinates, except for empty points outside of lines and rings.
+fn check_finite(type_id: u32, coords: [Option<f64>; 4]) -> Result<(), Error> {
+    let mut coords = coords.into_iter().flatten();
+    let is_point = type_id & 0xff == 0x01;
+    if is_point && coords.clone().all(f64::is_nan) {
+        return Ok(());
+    }
+    match coords.find(|v| !v.is_finite()) {
+        Some(v) => Err(Error::Read(format!("non-finite coordinate {}", v))),
+        None => Ok(()),
+    }
+}
+
 macro_rules! impl_point_read_traits {
     ($ptype:ident) => {
         impl EwkbRead for $ptype {
@@ -238,6 +504,7 @@ macro_rules! impl_point_read_traits {
                 is_be: bool,
                 type_id: u32,
                 srid: Option<i32>,
+                ctx: &mut ReadContext,
             ) -> Result<Self, Error> {
                 let x = read_f64(raw, is_be)?;
                 let y = read_f64(raw, is_be)?;
@@ -251,10 +518,31 @@ macro_rules! impl_point_read_traits {
                 } else {
                     None
                 };
+                if ctx.reject_nonfinite {
+                    check_finite(type_id, [Some(x), Some(y), z, m])?;
+                }
                 Ok(Self::new_from_opt_vals(x, y, z, m, srid))
             }
         }
 
+        impl PointCoords for $ptype {
+            fn new_from_opt_vals(
+                x: f64,
+                y: f64,
+                z: Option<f64>,
+                m: Option<f64>,
+                srid: Option<i32>,
+            ) -> Self {
+                $ptype::new_from_opt_vals(x, y, z, m, srid)
+            }
+            fn opt_srid(&self) -> Option<i32> {
+                self.srid
+            }
+            fn set_srid(&mut self, srid: Option<i32>) {
+                self.srid = srid;
+            }
+        }
+
         impl<'a> AsEwkbPoint<'a> for $ptype {
             fn as_ewkb(&'a self) -> EwkbPoint<'a> {
                 EwkbPoint {
@@ -0,0 +1,283 @@
+//! Point in polygon and line intersection tests.
+
+use crate::ewkb::validate::{on_segment, orientation, segments_intersect};
+use crate::ewkb::*;
+
+/// Number of times `ring` winds around the position (x, y).
+///
+/// Counter-clockwise windings count positive, clockwise windings negative, so a simple
+/// counter-clockwise ring yields 1 for points inside and 0 for points outside. A clockwise
+/// ring yields -1 inside. Self-intersecting rings can give other values, which lets callers
+/// choose between the even-odd (`wn % 2 != 0`) and the nonzero (`wn != 0`) fill rule.
+/// The result for points exactly on the ring is unspecified. An unclosed ring is treated as
+/// if its last point was connected to the first. Z and M values are ignored.
+pub fn winding_number<P>(ring: &LineStringT<P>, x: f64, y: f64) -> i32
+where
+    P: postgis::Point + EwkbRead,
+{
+    let points = &ring.points;
+    let n = points.len();
+    let mut wn = 0;
+    for i in 0..n {
+        let (a, b) = (&points[i], &points[(i + 1) % n]);
+        // > 0 if (x, y) is left of the edge a-b
+        let side = (b.x() - a.x()) * (y - a.y()) - (x - a.x()) * (b.y() - a.y());
+        if a.y() <= y {
+            if b.y() > y && side > 0.0 {
+                wn += 1;
+            }
+        } else if b.y() <= y && side < 0.0 {
+            wn -= 1;
+        }
+    }
+    wn
+}
+
+impl<P> PolygonT<P>
+where
+    P: postgis::Point + EwkbRead,
+{
+    /// Whether the position (x, y) lies inside the exterior ring and outside all holes.
+    ///
+    /// Rings are filled with the nonzero rule of [`winding_number`], independent of their
+    /// orientation. The result for points on a ring is unspecified.
+    pub fn contains_point(&self, x: f64, y: f64) -> bool {
+        match self.rings.split_first() {
+            Some((exterior, holes)) => {
+                winding_number(exterior, x, y) != 0
+                    && holes.iter().all(|hole| winding_number(hole, x, y) == 0)
+            }
+            None => false,
+        }
+    }
+
+    /// Whether `other` lies inside this polygon, an approximation of `ST_Contains` for
+    /// polygons which are either disjoint or nested.
+    ///
+    /// True if all vertices of the exterior ring of `other` are inside this polygon, no ring
+    /// of this polygon touches or crosses that exterior ring, and no hole of this polygon lies
+    /// inside it. Holes of `other` are ignored. Unlike `ST_Contains`, polygons sharing
+    /// boundary points, e.g. a polygon and itself, are not contained. An empty polygon neither
+    /// contains nor is contained. Compares all pairs of segments in 2D, which takes O(n * m)
+    /// time.
+    pub fn contains_polygon(&self, other: &Self) -> bool {
+        let Some(inner) = other.rings.first().filter(|ring| !ring.points.is_empty()) else {
+            return false;
+        };
+        inner
+            .points
+            .iter()
+            .all(|p| self.contains_point(p.x(), p.y()))
+            && !self.rings.iter().any(|ring| ring.intersects(inner))
+            && !self.rings.iter().skip(1).any(|hole| {
+                hole.points
+                    .first()
+                    .is_some_and(|p| winding_number(inner, p.x(), p.y()) != 0)
+            })
+    }
+}
+
+/// Append the common points of the closed segments p1-p2 and q1-q2 to `out`.
+///
+/// For collinear overlapping segments these are the endpoints of the overlap.
+fn segment_intersections(
+    (p1, p2): ((f64, f64), (f64, f64)),
+    (q1, q2): ((f64, f64), (f64, f64)),
+    out: &mut Vec<(f64, f64)>,
+) {
+    let (r, s) = ((p2.0 - p1.0, p2.1 - p1.1), (q2.0 - q1.0, q2.1 - q1.1));
+    let cross = |a: (f64, f64), b: (f64, f64)| a.0 * b.1 - a.1 * b.0;
+    let denom = cross(r, s);
+    if denom != 0.0 {
+        let qp = (q1.0 - p1.0, q1.1 - p1.1);
+        let (t, u) = (cross(qp, s) / denom, cross(qp, r) / denom);
+        if (0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u) {
+            out.push((p1.0 + t * r.0, p1.1 + t * r.1));
+        }
+    } else if orientation(p1, p2, q1) == 0.0
+        && orientation(p1, p2, q2) == 0.0
+        && orientation(q1, q2, p1) == 0.0
+        && orientation(q1, q2, p2) == 0.0
+    {
+        out.extend([q1, q2].into_iter().filter(|&c| on_segment(p1, p2, c)));
+        out.extend([p1, p2].into_iter().filter(|&c| on_segment(q1, q2, c)));
+    }
+}
+
+fn segments<P: postgis::Point>(
+    points: &[P],
+) -> impl Iterator<Item = ((f64, f64), (f64, f64))> + '_ {
+    points
+        .windows(2)
+        .map(|s| ((s[0].x(), s[0].y()), (s[1].x(), s[1].y())))
+}
+
+impl<P> LineStringT<P>
+where
+    P: postgis::Point + EwkbRead,
+{
+    /// Whether a segment of this line touches or crosses a segment of `other`.
+    ///
+    /// Compares all pairs of segments in 2D, which takes O(n * m) time. A line with a single
+    /// point has no segments and intersects nothing.
+    pub fn intersects(&self, other: &Self) -> bool {
+        segments(&self.points).any(|(p1, p2)| {
+            segments(&other.points).any(|(q1, q2)| segments_intersect(p1, p2, q1, q2))
+        })
+    }
+
+    /// Whether the line doesn't touch or cross itself, like `ST_IsSimple` for linestrings.
+    ///
+    /// Adjacent segments may only share their common point, a line doubling back on itself
+    /// is not simple. The first and last point of a closed line may coincide. Consecutive
+    /// duplicate points are ignored and Z and M values as well. Compares all pairs of
+    /// segments, which takes O(n²) time.
+    pub fn is_simple(&self) -> bool {
+        let mut coords: Vec<(f64, f64)> = Vec::with_capacity(self.points.len());
+        for p in &self.points {
+            if coords.last() != Some(&(p.x(), p.y())) {
+                coords.push((p.x(), p.y()));
+            }
+        }
+        let n = coords.len().saturating_sub(1);
+        let closed = n >= 2 && coords[0] == coords[n];
+        // Segments a-b and b-c overlapping beyond b
+        let overlaps =
+            |a, b, c| orientation(a, b, c) == 0.0 && (on_segment(a, b, c) || on_segment(b, c, a));
+        for i in 0..n {
+            for j in i + 1..n {
+                let (p1, p2, q1, q2) = (coords[i], coords[i + 1], coords[j], coords[j + 1]);
+                let simple = if j == i + 1 {
+                    !overlaps(p1, p2, q2)
+                } else if closed && i == 0 && j == n - 1 {
+                    !overlaps(q1, q2, p2)
+                } else {
+                    !segments_intersect(p1, p2, q1, q2)
+                };
+                if !simple {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Points where this line touches or crosses `other`, in the order of this line.
+    ///
+    /// Collinear overlapping segments contribute the endpoints of the overlap. Duplicates,
+    /// e.g. from a crossing at a vertex shared by two segments, are removed. The points are
+    /// 2D and carry the SRID of this line.
+    pub fn intersection_points(&self, other: &Self) -> Vec<Point> {
+        let mut coords = Vec::new();
+        for p in segments(&self.points) {
+            let start = coords.len();
+            for q in segments(&other.points) {
+                segment_intersections(p, q, &mut coords);
+            }
+            // Order the points of this segment along the segment
+            let dist = |c: &(f64, f64)| (c.0 - p.0.0).hypot(c.1 - p.0.1);
+            coords[start..].sort_by(|a, b| dist(a).total_cmp(&dist(b)));
+        }
+        let mut points: Vec<Point> = Vec::with_capacity(coords.len());
+        for (x, y) in coords {
+            if !points.iter().any(|p| p.x() == x && p.y() == y) {
+                points.push(Point::new(x, y, self.srid));
+            }
+        }
+        points
+    }
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_winding_number() {
+    let exterior = line_xy(&[(0., 0.), (4., 0.), (4., 4.), (0., 4.), (0., 0.)]);
+    assert_eq!(winding_number(&exterior, 1., 1.), 1);
+    assert_eq!(winding_number(&exterior, 5., 1.), 0);
+    let hole = line_xy(&[(1., 1.), (1., 2.), (2., 2.), (2., 1.), (1., 1.)]);
+    assert_eq!(winding_number(&hole, 1.5, 1.5), -1);
+    // Ring winding twice around its center
+    let double = line_xy(&[(0., 0.), (2., 0.), (2., 2.), (0., 2.), (0., 0.), (2., 0.), (2., 2.), (0., 2.), (0., 0.)]);
+    assert_eq!(winding_number(&double, 1., 1.), 2);
+    assert_eq!(winding_number(&LineStringT::<Point>::new(), 0., 0.), 0);
+
+    let poly = PolygonT::<Point> {srid: None, rings: vec![exterior, hole]};
+    assert!(poly.contains_point(3., 3.));
+    assert!(!poly.contains_point(1.5, 1.5));
+    assert!(!poly.contains_point(-1., 3.));
+    assert!(!PolygonT::<Point>::new().contains_point(0., 0.));
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_line_intersection() {
+    let p = |x, y| Point::new(x, y, None);
+    let zigzag = line_xy(&[(0., 0.), (2., 2.), (4., 0.), (6., 2.)]);
+    let horizontal = line_xy(&[(-1., 1.), (7., 1.)]);
+    assert!(zigzag.intersects(&horizontal));
+    assert!(horizontal.intersects(&zigzag));
+    assert_eq!(zigzag.intersection_points(&horizontal), vec![p(1., 1.), p(3., 1.), p(5., 1.)]);
+    assert_eq!(horizontal.intersection_points(&zigzag), vec![p(1., 1.), p(3., 1.), p(5., 1.)]);
+
+    // Touching in a vertex
+    let touching = line_xy(&[(2., 2.), (2., 5.)]);
+    assert!(zigzag.intersects(&touching));
+    assert_eq!(zigzag.intersection_points(&touching), vec![p(2., 2.)]);
+
+    // Collinear overlap
+    let overlap = line_xy(&[(3., 1.), (5., -1.)]);
+    assert_eq!(zigzag.intersection_points(&overlap), vec![p(3., 1.), p(4., 0.)]);
+
+    let apart = line_xy(&[(0., 3.), (6., 3.)]);
+    assert!(!zigzag.intersects(&apart));
+    assert!(zigzag.intersection_points(&apart).is_empty());
+    assert!(!zigzag.intersects(&line_xy(&[(1., 1.)])));
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_is_simple() {
+    assert!(line_xy(&[(0., 0.), (1., 0.), (1., 0.), (2., 1.)]).is_simple());
+    assert!(line_xy(&[(0., 0.), (1., 0.), (1., 1.), (0., 0.)]).is_simple());
+    assert!(line_xy(&[(0., 0.)]).is_simple());
+    assert!(LineStringT::<Point>::new().is_simple());
+    // Crossing
+    assert!(!line_xy(&[(0., 0.), (2., 2.), (2., 0.), (0., 2.)]).is_simple());
+    // Touching an earlier vertex
+    assert!(!line_xy(&[(0., 0.), (2., 0.), (2., 2.), (1., 0.)]).is_simple());
+    // Doubling back
+    assert!(!line_xy(&[(0., 0.), (2., 0.), (1., 0.)]).is_simple());
+    assert!(!line_xy(&[(0., 0.), (1., 0.), (0., 0.)]).is_simple());
+    // Closed line with overlapping first and last segment
+    assert!(!line_xy(&[(0., 0.), (2., 0.), (2., 2.), (1., 0.), (0., 0.)]).is_simple());
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_contains_polygon() {
+    let square = |x0: f64, y0: f64, x1: f64, y1: f64| line_xy(&[(x0, y0), (x1, y0), (x1, y1), (x0, y1), (x0, y0)]);
+    let poly = |rings: Vec<LineStringT<Point>>| PolygonT::<Point> {srid: None, rings};
+
+    let outer = poly(vec![square(0., 0., 10., 10.)]);
+    let inner = poly(vec![square(2., 2., 4., 4.)]);
+    assert!(outer.contains_polygon(&inner));
+    assert!(!inner.contains_polygon(&outer));
+    // Disjoint
+    assert!(!outer.contains_polygon(&poly(vec![square(20., 0., 30., 10.)])));
+    // Overlapping: two vertices inside, but the boundaries cross
+    assert!(!outer.contains_polygon(&poly(vec![square(5., 5., 15., 15.)])));
+    // Shared boundary
+    assert!(!outer.contains_polygon(&poly(vec![square(0., 0., 5., 5.)])));
+    assert!(!outer.contains_polygon(&outer));
+
+    // Inside a hole, and around a hole
+    let holed = poly(vec![square(0., 0., 10., 10.), square(3., 3., 7., 7.)]);
+    assert!(!holed.contains_polygon(&poly(vec![square(4., 4., 6., 6.)])));
+    assert!(!holed.contains_polygon(&poly(vec![square(2., 2., 8., 8.)])));
+    assert!(holed.contains_polygon(&poly(vec![square(1., 1., 2., 2.)])));
+    // Holes of the contained polygon don't matter
+    assert!(outer.contains_polygon(&poly(vec![square(1., 1., 9., 9.), square(2., 2., 3., 3.)])));
+
+    assert!(!outer.contains_polygon(&PolygonT::new()));
+    assert!(!PolygonT::<Point>::new().contains_polygon(&inner));
+}
@@ -0,0 +1,209 @@
+//! Client-side geometry simplification.
+
+use crate::ewkb::measure::{ring_signed_area, segment_distance};
+use crate::ewkb::*;
+
+/// Mask of the points kept by the Ramer-Douglas-Peucker algorithm.
+fn rdp_keep<P: postgis::Point>(points: &[P], tolerance: f64) -> Vec<bool> {
+    let mut keep = vec![false; points.len()];
+    if points.len() < 3 {
+        keep.iter_mut().for_each(|k| *k = true);
+        return keep;
+    }
+    let last = points.len() - 1;
+    keep[0] = true;
+    keep[last] = true;
+    let mut stack = vec![(0, last)];
+    while let Some((first, last)) = stack.pop() {
+        let (a, b) = (&points[first], &points[last]);
+        let mut max_dist = 0.0;
+        let mut index = first;
+        for (i, p) in points.iter().enumerate().take(last).skip(first + 1) {
+            let dist = segment_distance(p.x(), p.y(), a.x(), a.y(), b.x(), b.y());
+            if dist > max_dist {
+                max_dist = dist;
+                index = i;
+            }
+        }
+        if max_dist > tolerance {
+            keep[index] = true;
+            stack.push((first, index));
+            stack.push((index, last));
+        }
+    }
+    keep
+}
+
+fn rdp<P: postgis::Point + Clone>(points: &[P], tolerance: f64) -> Vec<P> {
+    points
+        .iter()
+        .zip(rdp_keep(points, tolerance))
+        .filter(|(_, keep)| *keep)
+        .map(|(p, _)| p.clone())
+        .collect()
+}
+
+/// A large triangle spanned by vertices of `points`, as a closed ring with the orientation
+/// of `points`.
+///
+/// Uses the first vertex, the vertex farthest from it and the vertex farthest from the
+/// segment between those two.
+fn fallback_triangle<P: postgis::Point + Clone>(points: &[P]) -> Vec<P> {
+    let a = &points[0];
+    let dist_a = |p: &P| (p.x() - a.x()).hypot(p.y() - a.y());
+    let b = points
+        .iter()
+        .max_by(|p, q| dist_a(p).total_cmp(&dist_a(q)))
+        .unwrap();
+    let dist_ab = |p: &P| segment_distance(p.x(), p.y(), a.x(), a.y(), b.x(), b.y());
+    let c = points
+        .iter()
+        .max_by(|p, q| dist_ab(p).total_cmp(&dist_ab(q)))
+        .unwrap();
+    let mut triangle = vec![a.clone(), b.clone(), c.clone(), a.clone()];
+    if ring_signed_area(&triangle).signum() != ring_signed_area(points).signum() {
+        triangle.reverse();
+    }
+    triangle
+}
+
+impl<P> LineStringT<P>
+where
+    P: postgis::Point + EwkbRead + Clone,
+{
+    /// Simplify the line with the Ramer-Douglas-Peucker algorithm.
+    ///
+    /// Vertices closer than `tolerance` to the simplified line are removed, the end points are
+    /// always kept. This is the client-side equivalent of `ST_Simplify`.
+    pub fn simplify(&self, tolerance: f64) -> LineStringT<P> {
+        LineStringT {
+            points: rdp(&self.points, tolerance),
+            srid: self.srid,
+        }
+    }
+
+    /// Keep only the points for which `f` returns `true`, like [`Vec::retain`].
+    pub fn retain_points<F: FnMut(&P) -> bool>(&mut self, f: F) {
+        self.points.retain(f);
+    }
+}
+
+impl<P> PolygonT<P>
+where
+    P: postgis::Point + EwkbRead + Clone,
+{
+    /// Simplify all rings, keeping each ring a valid closed ring with at least 4 points.
+    ///
+    /// Rings are simplified like [`LineStringT::simplify`]. If a simplified ring collapses to
+    /// fewer than 4 points or to zero area, it is replaced by a large triangle spanned by
+    /// its original vertices. Rings which are already degenerate are left unchanged.
+    /// This is the client-side analog of `ST_SimplifyPreserveTopology` for single polygons,
+    /// but does not prevent rings from crossing each other.
+    pub fn simplify_safe(&self, tolerance: f64) -> PolygonT<P> {
+        let rings = self
+            .rings
+            .iter()
+            .map(|ring| {
+                if ring.points.len() < 4 || ring_signed_area(&ring.points) == 0.0 {
+                    return ring.clone();
+                }
+                let mut points = rdp(&ring.points, tolerance);
+                if points.len() < 4 || ring_signed_area(&points) == 0.0 {
+                    points = fallback_triangle(&ring.points);
+                }
+                LineStringT {
+                    points,
+                    srid: ring.srid,
+                }
+            })
+            .collect();
+        PolygonT {
+            rings,
+            srid: self.srid,
+        }
+    }
+
+    /// Keep only the points of each ring for which `f` returns `true`.
+    ///
+    /// A closed ring which lost its closing point is closed again by repeating its new
+    /// first point. If fewer than 4 points would remain, the ring is left unchanged.
+    pub fn retain_points<F: FnMut(&P) -> bool>(&mut self, mut f: F) {
+        for ring in &mut self.rings {
+            let mut retained = LineStringT {
+                points: ring.points.iter().filter(|p| f(p)).cloned().collect(),
+                srid: ring.srid,
+            };
+            if ring.is_ring() && !retained.points.is_empty() && !retained.is_ring() {
+                retained.points.push(retained.points[0].clone());
+            }
+            if retained.points.len() >= 4 {
+                *ring = retained;
+            }
+        }
+    }
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_simplify_line() {
+    let p = |x, y| Point::new(x, y, None);
+    let line = LineStringT::<Point> {srid: Some(4326), points: vec![p(0., 0.), p(1., 0.1), p(2., -0.1), p(3., 5.), p(4., 6.), p(5., 7.), p(6., 8.1), p(7., 9.)]};
+    let simple = line.simplify(0.5);
+    assert_eq!(simple, LineStringT::<Point> {srid: Some(4326), points: vec![p(0., 0.), p(2., -0.1), p(3., 5.), p(7., 9.)]});
+    // Only the collinear vertex (4 6) is removed without tolerance
+    assert_eq!(line.simplify(0.0).points.len(), 7);
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_simplify_safe() {
+    let p = |x, y| Point::new(x, y, None);
+    // Square with a small bump on the lower edge
+    let ring = LineStringT::<Point> {srid: None, points: vec![p(0., 0.), p(5., 0.1), p(10., 0.), p(10., 10.), p(0., 10.), p(0., 0.)]};
+    let poly = PolygonT::<Point> {srid: Some(4326), rings: vec![ring]};
+    let simple = poly.simplify_safe(1.0);
+    assert_eq!(simple.srid, Some(4326));
+    assert_eq!(simple.rings[0].points, vec![p(0., 0.), p(10., 0.), p(10., 10.), p(0., 10.), p(0., 0.)]);
+
+    // A huge tolerance collapses the ring, which falls back to a triangle
+    let simple = poly.simplify_safe(100.0);
+    let ring = &simple.rings[0].points;
+    assert_eq!(ring.len(), 4);
+    assert_eq!(ring.first(), ring.last());
+    assert!(ring_signed_area(ring).abs() > 0.0);
+
+    // The fallback triangle keeps the orientation of the ring. Its third vertex is the tip
+    // of the spike, on the other side of the diagonal than most of the hole.
+    let exterior = line_xy(&[(-100., -100.), (100., -100.), (100., 100.), (-100., 100.), (-100., -100.)]);
+    let hole = line_xy(&[(0., 0.), (14., 0.), (15., 8.), (16., 0.), (30., 0.), (30., -10.), (0., -10.), (0., 0.)]);
+    assert!(ring_signed_area(&hole.points) < 0.0);
+    let simple = PolygonT::<Point> {srid: None, rings: vec![exterior, hole]}.simplify_safe(500.0);
+    assert_eq!(simple.rings[1].points, vec![p(0., 0.), p(15., 8.), p(30., -10.), p(0., 0.)]);
+    assert!(ring_signed_area(&simple.rings[0].points) > 0.0);
+    assert!(ring_signed_area(&simple.rings[1].points) < 0.0);
+
+    // Degenerate rings are kept as they are
+    let ring = LineStringT::<Point> {srid: None, points: vec![p(0., 0.), p(1., 1.), p(0., 0.)]};
+    let poly = PolygonT::<Point> {srid: None, rings: vec![ring.clone()]};
+    assert_eq!(poly.simplify_safe(1.0).rings[0], ring);
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_retain_points() {
+    let p = |x, y| Point::new(x, y, None);
+    let mut line = LineStringT::<Point> {srid: Some(4326), points: vec![p(0., 0.), p(f64::NAN, 1.), p(2., 2.)]};
+    line.retain_points(|p| p.x().is_finite());
+    assert_eq!(line, LineStringT::<Point> {srid: Some(4326), points: vec![p(0., 0.), p(2., 2.)]});
+
+    // Dropping the start and closing point re-closes the ring
+    let ring = LineStringT::<Point> {srid: None, points: vec![p(0., 0.), p(5., 0.), p(5., 5.), p(3., 6.), p(0., 5.), p(0., 0.)]};
+    let mut poly = PolygonT::<Point> {srid: None, rings: vec![ring.clone()]};
+    poly.retain_points(|p| p.x() != 0. || p.y() != 0.);
+    assert_eq!(poly.rings[0].points, vec![p(5., 0.), p(5., 5.), p(3., 6.), p(0., 5.), p(5., 0.)]);
+
+    // Rings never drop below 4 points
+    let mut poly = PolygonT::<Point> {srid: None, rings: vec![ring.clone()]};
+    poly.retain_points(|p| p.x() == 0.);
+    assert_eq!(poly.rings[0], ring);
+}
@@ -0,0 +1,120 @@
+//! Checked spatial reference identifiers.
+
+use crate::ewkb::*;
+
+/// Spatial reference identifier accepted by PostGIS.
+///
+/// The geometry types keep their `srid: Option<i32>` fields, `Srid` can be used with their
+/// `with_srid` constructors to check the SRID once when it is created instead of when the
+/// geometry is written. `Srid::UNKNOWN` (0) is the PostGIS sentinel for an unknown SRID.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "i32", into = "i32"))]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy, Debug)]
+pub struct Srid(i32);
+
+impl Srid {
+    /// The PostGIS "unknown" SRID.
+    pub const UNKNOWN: Srid = Srid(0);
+    /// WGS 84 longitude/latitude.
+    pub const WGS84: Srid = Srid(4326);
+
+    /// Returns `None` for values outside 0 to 999999, which PostGIS rejects.
+    pub const fn new(srid: i32) -> Option<Srid> {
+        if srid >= 0 && srid <= SRID_MAXIMUM {
+            Some(Srid(srid))
+        } else {
+            None
+        }
+    }
+
+    pub const fn get(self) -> i32 {
+        self.0
+    }
+
+    pub const fn is_unknown(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl From<Srid> for i32 {
+    fn from(srid: Srid) -> i32 {
+        srid.0
+    }
+}
+
+impl TryFrom<i32> for Srid {
+    type Error = Error;
+
+    fn try_from(srid: i32) -> Result<Self, Self::Error> {
+        Srid::new(srid).ok_or_else(|| Error::Other(format!("invalid SRID {srid}")))
+    }
+}
+
+impl fmt::Display for Srid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+macro_rules! impl_with_srid {
+    (point $($ptype:ident),*) => {
+        $(
+            impl $ptype {
+                /// Replace the SRID, see [`Srid`].
+                pub fn with_srid(mut self, srid: impl Into<Option<Srid>>) -> Self {
+                    self.srid = srid.into().map(i32::from);
+                    self
+                }
+            }
+        )*
+    };
+    ($($geotype:ident),*) => {
+        $(
+            impl<P: postgis::Point + EwkbRead> $geotype<P> {
+                /// Replace the SRID of the geometry, see [`Srid`].
+                ///
+                /// The SRIDs of the contained points and parts are left unchanged.
+                pub fn with_srid(mut self, srid: impl Into<Option<Srid>>) -> Self {
+                    self.srid = srid.into().map(i32::from);
+                    self
+                }
+            }
+        )*
+    };
+}
+
+impl_with_srid!(point Point, PointZ, PointM, PointZM);
+impl_with_srid!(
+    LineStringT,
+    CircularStringT,
+    MultiPointT,
+    PolygonT,
+    MultiLineStringT,
+    MultiPolygonT,
+    CompoundCurveT,
+    CurvePolygonT,
+    GeometryCollectionT
+);
+
+#[test]
+#[rustfmt::skip]
+fn test_srid() {
+    assert_eq!(Srid::new(4326), Some(Srid::WGS84));
+    assert_eq!(Srid::new(0), Some(Srid::UNKNOWN));
+    assert!(Srid::new(0).unwrap().is_unknown());
+    assert_eq!(Srid::new(-1), None);
+    assert_eq!(Srid::new(999_999).map(Srid::get), Some(999_999));
+    assert_eq!(Srid::new(1_000_000), None);
+    assert_eq!(Srid::try_from(-1).unwrap_err().to_string(), r#"Other("invalid SRID -1")"#);
+    assert_eq!(i32::from(Srid::WGS84), 4326);
+    assert_eq!(Srid::WGS84.to_string(), "4326");
+
+    let p = Point::new(1.0, 2.0, None).with_srid(Srid::WGS84);
+    assert_eq!(p.srid, Some(4326));
+    assert_eq!(p.with_srid(None).srid, None);
+
+    let line = LineStringT::<Point>::new().with_srid(Srid::new(3857));
+    assert_eq!(line.srid, Some(3857));
+    let poly = PolygonT::<PointZ>::new().with_srid(Srid::UNKNOWN);
+    assert_eq!(poly.srid, Some(0));
+}
@@ -0,0 +1,203 @@
+//! Complexity statistics of geometries.
+
+use crate::ewkb::*;
+
+/// Size of a geometry, see [`GeometryT::stats`].
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub struct GeomStats {
+    /// Number of points, including the closing points of rings. Empty points aren't counted.
+    pub points: usize,
+    /// Number of polygon rings, exterior and interior.
+    pub rings: usize,
+    /// Number of single geometries, i.e. points, lines, polygons and curves.
+    pub parts: usize,
+    /// Number of geometry levels: 1 for single geometries, 2 for multi geometries and
+    /// collections of single geometries, plus one for each nested collection.
+    pub depth: usize,
+}
+
+impl GeomStats {
+    fn single(points: usize, rings: usize) -> Self {
+        GeomStats {
+            points,
+            rings,
+            parts: 1,
+            depth: 1,
+        }
+    }
+
+    /// Sum of the parts, one level deeper.
+    fn multi(parts: impl Iterator<Item = GeomStats>) -> Self {
+        let mut stats = parts.fold(GeomStats::default(), |acc, s| GeomStats {
+            points: acc.points + s.points,
+            rings: acc.rings + s.rings,
+            parts: acc.parts + s.parts,
+            depth: acc.depth.max(s.depth),
+        });
+        stats.depth = stats.depth.max(1) + 1;
+        stats
+    }
+}
+
+/// 0 for an empty point like `POINT EMPTY`, which has NaN coordinates, 1 otherwise.
+fn point_count<P: postgis::Point>(point: &P) -> usize {
+    if point.x().is_nan() && point.y().is_nan() {
+        0
+    } else {
+        1
+    }
+}
+
+fn compound_curve_points<P: postgis::Point + EwkbRead>(curve: &CompoundCurveT<P>) -> usize {
+    curve
+        .segments
+        .iter()
+        .map(|s| match s {
+            CurveSegment::LineString(g) => g.points.len(),
+            CurveSegment::CircularString(g) => g.points.len(),
+        })
+        .sum()
+}
+
+fn polygon_points<P: postgis::Point + EwkbRead>(polygon: &PolygonT<P>) -> usize {
+    polygon.rings.iter().map(|r| r.points.len()).sum()
+}
+
+impl<P> MultiPolygonT<P>
+where
+    P: postgis::Point + EwkbRead,
+{
+    /// Number of rings of all polygons.
+    pub fn total_rings(&self) -> usize {
+        self.polygons.iter().map(|p| p.rings.len()).sum()
+    }
+
+    /// Number of points of all rings.
+    pub fn total_points(&self) -> usize {
+        self.polygons.iter().map(polygon_points).sum()
+    }
+
+    /// Number of points of the largest ring, 0 if there are no rings.
+    pub fn max_ring_points(&self) -> usize {
+        self.polygons
+            .iter()
+            .flat_map(|p| p.rings.iter())
+            .map(|r| r.points.len())
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+impl<P> GeometryT<P>
+where
+    P: postgis::Point + EwkbRead,
+{
+    /// Count points, rings, parts and nesting depth, e.g. to reject overly complex geometries.
+    pub fn stats(&self) -> GeomStats {
+        match self {
+            GeometryT::Point(p) => GeomStats::single(point_count(p), 0),
+            GeometryT::LineString(g) => GeomStats::single(g.points.len(), 0),
+            GeometryT::CircularString(g) => GeomStats::single(g.points.len(), 0),
+            GeometryT::CompoundCurve(g) => GeomStats::single(compound_curve_points(g), 0),
+            GeometryT::Polygon(g) => GeomStats::single(polygon_points(g), g.rings.len()),
+            GeometryT::CurvePolygon(g) => {
+                let points = g
+                    .rings
+                    .iter()
+                    .map(|r| match r {
+                        Curve::LineString(g) => g.points.len(),
+                        Curve::CircularString(g) => g.points.len(),
+                        Curve::CompoundCurve(g) => compound_curve_points(g),
+                    })
+                    .sum();
+                GeomStats::single(points, g.rings.len())
+            }
+            GeometryT::MultiPoint(g) => GeomStats::multi(
+                g.points
+                    .iter()
+                    .map(|p| GeomStats::single(point_count(p), 0)),
+            ),
+            GeometryT::MultiLineString(g) => {
+                GeomStats::multi(g.lines.iter().map(|l| GeomStats::single(l.points.len(), 0)))
+            }
+            GeometryT::MultiPolygon(g) => GeomStats::multi(
+                g.polygons
+                    .iter()
+                    .map(|p| GeomStats::single(polygon_points(p), p.rings.len())),
+            ),
+            GeometryT::GeometryCollection(g) => {
+                GeomStats::multi(g.geometries.iter().map(GeometryT::stats))
+            }
+        }
+    }
+
+    /// Number of points like `ST_NPoints`, including the closing points of rings and the
+    /// shared end points of compound curve segments.
+    pub fn npoints(&self) -> usize {
+        self.stats().points
+    }
+
+    /// Number of polygon rings like `ST_NRings`, exterior and interior, 0 for geometries
+    /// without polygons. Rings of curve polygons are included.
+    pub fn nrings(&self) -> usize {
+        self.stats().rings
+    }
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_stats() {
+    let ring = |n: usize| LineStringT::<Point> {srid: None, points: (0..n).map(|i| Point::new(i as f64, 0.0, None)).collect()};
+    let multi = MultiPolygonT::<Point> {srid: None, polygons: vec![
+        PolygonT {srid: None, rings: vec![ring(5), ring(4)]},
+        PolygonT {srid: None, rings: vec![ring(7)]},
+    ]};
+    assert_eq!(multi.total_rings(), 3);
+    assert_eq!(multi.total_points(), 16);
+    assert_eq!(multi.max_ring_points(), 7);
+    assert_eq!(MultiPolygonT::<Point>::new().max_ring_points(), 0);
+
+    let geom = GeometryT::MultiPolygon(multi.clone());
+    assert_eq!(geom.stats(), GeomStats {points: 16, rings: 3, parts: 2, depth: 2});
+    assert_eq!(GeometryT::<Point>::Point(Point::new(0.0, 0.0, None)).stats(), GeomStats {points: 1, rings: 0, parts: 1, depth: 1});
+    assert_eq!(GeometryT::MultiPolygon(MultiPolygonT::<Point>::new()).stats(), GeomStats {points: 0, rings: 0, parts: 0, depth: 2});
+
+    let inner = GeometryCollectionT {srid: None, geometries: vec![geom, GeometryT::LineString(ring(3))]};
+    let outer = GeometryT::GeometryCollection(GeometryCollectionT {srid: None, geometries: vec![
+        GeometryT::GeometryCollection(inner), GeometryT::Point(Point::new(0.0, 0.0, None)),
+    ]});
+    assert_eq!(outer.stats(), GeomStats {points: 20, rings: 3, parts: 4, depth: 4});
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_npoints_nrings() {
+    // SELECT ST_NPoints(g), ST_NRings(g) FROM (SELECT 'GEOMETRYCOLLECTION (POINT (0 0), LINESTRING (0 0, 1 1),
+    //   POLYGON ((0 0, 4 0, 4 4, 0 0), (1 1, 2 1, 2 2, 1 1)))'::geometry AS g) t  -- 11, 2
+    let p = |x, y| Point::new(x, y, None);
+    let polygon = PolygonT {srid: None, rings: vec![line_xy(&[(0., 0.), (4., 0.), (4., 4.), (0., 0.)]), line_xy(&[(1., 1.), (2., 1.), (2., 2.), (1., 1.)])]};
+    let collection = GeometryT::GeometryCollection(GeometryCollectionT {srid: None, geometries: vec![
+        GeometryT::Point(p(0., 0.)),
+        GeometryT::LineString(line_xy(&[(0., 0.), (1., 1.)])),
+        GeometryT::Polygon(polygon),
+    ]});
+    assert_eq!((collection.npoints(), collection.nrings()), (11, 2));
+    let line = GeometryT::LineString(line_xy(&[(0., 0.), (1., 1.)]));
+    assert_eq!((line.npoints(), line.nrings()), (2, 0));
+
+    // SELECT ST_NPoints('COMPOUNDCURVE (CIRCULARSTRING (0 0, 1 1, 2 0), (2 0, 3 0))'::geometry)  -- 5
+    let curve = GeometryT::CompoundCurve(CompoundCurveT {srid: None, segments: vec![
+        CurveSegment::CircularString(CircularStringT {srid: None, points: vec![p(0., 0.), p(1., 1.), p(2., 0.)]}),
+        CurveSegment::LineString(line_xy(&[(2., 0.), (3., 0.)])),
+    ]});
+    assert_eq!((curve.npoints(), curve.nrings()), (5, 0));
+    assert_eq!(GeometryT::GeometryCollection(GeometryCollectionT::<Point>::new()).npoints(), 0);
+
+    // SELECT ST_NPoints('POINT EMPTY'::geometry), ST_NPoints('MULTIPOINT (EMPTY, 1 2)'::geometry)  -- 0, 1
+    let empty = Point::new(f64::NAN, f64::NAN, None);
+    assert_eq!(GeometryT::Point(empty).npoints(), 0);
+    assert_eq!(GeometryT::MultiPoint(MultiPointT {srid: None, points: vec![empty, p(1., 2.)]}).npoints(), 1);
+    assert_eq!(GeometryT::Point(PointZ::new(f64::NAN, f64::NAN, f64::NAN, None)).npoints(), 0);
+    let collection = GeometryT::GeometryCollection(GeometryCollectionT {srid: None, geometries: vec![GeometryT::Point(empty), GeometryT::Point(p(0., 0.))]});
+    assert_eq!(collection.npoints(), 1);
+}
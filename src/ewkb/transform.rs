@@ -0,0 +1,345 @@
+//! Pluggable coordinate transformations.
+
+use crate::ewkb::parts::stamp_geometry;
+use crate::ewkb::*;
+
+/// Transformation of 2D coordinates, e.g. between two coordinate reference systems.
+///
+/// The crate doesn't ship any projections. Implement this trait with a projection library
+/// like the `proj` crate to reproject geometries with [`GeometryT::reproject`].
+/// Closures `Fn(f64, f64) -> (f64, f64)` implement it as well.
+pub trait CoordTransform {
+    fn transform(&self, x: f64, y: f64) -> (f64, f64);
+}
+
+impl<F: Fn(f64, f64) -> (f64, f64)> CoordTransform for F {
+    fn transform(&self, x: f64, y: f64) -> (f64, f64) {
+        self(x, y)
+    }
+}
+
+/// Traversal of all points and SRIDs of a geometry.
+trait PointVisitor {
+    fn point<P: PointCoords>(&self, p: &mut P);
+
+    fn srid(&self, _srid: &mut Option<i32>) {}
+
+    fn points<P: PointCoords>(&self, points: &mut [P], srid: &mut Option<i32>) {
+        points.iter_mut().for_each(|p| self.point(p));
+        self.srid(srid);
+    }
+
+    fn lines<P: PointCoords>(&self, lines: &mut [LineStringT<P>]) {
+        for line in lines {
+            self.points(&mut line.points, &mut line.srid);
+        }
+    }
+
+    fn polygon<P: PointCoords>(&self, polygon: &mut PolygonT<P>) {
+        self.lines(&mut polygon.rings);
+        self.srid(&mut polygon.srid);
+    }
+
+    fn segment<P: PointCoords>(&self, segment: &mut CurveSegment<P>) {
+        match segment {
+            CurveSegment::LineString(g) => self.points(&mut g.points, &mut g.srid),
+            CurveSegment::CircularString(g) => self.points(&mut g.points, &mut g.srid),
+        }
+    }
+
+    fn compound_curve<P: PointCoords>(&self, curve: &mut CompoundCurveT<P>) {
+        curve.segments.iter_mut().for_each(|s| self.segment(s));
+        self.srid(&mut curve.srid);
+    }
+
+    fn geometry<P: PointCoords>(&self, geom: &mut GeometryT<P>) {
+        match geom {
+            GeometryT::Point(p) => self.point(p),
+            GeometryT::LineString(g) => self.points(&mut g.points, &mut g.srid),
+            GeometryT::Polygon(g) => self.polygon(g),
+            GeometryT::MultiPoint(g) => self.points(&mut g.points, &mut g.srid),
+            GeometryT::MultiLineString(g) => {
+                self.lines(&mut g.lines);
+                self.srid(&mut g.srid);
+            }
+            GeometryT::MultiPolygon(g) => {
+                g.polygons.iter_mut().for_each(|p| self.polygon(p));
+                self.srid(&mut g.srid);
+            }
+            GeometryT::GeometryCollection(g) => {
+                g.geometries.iter_mut().for_each(|g| self.geometry(g));
+                self.srid(&mut g.srid);
+            }
+            GeometryT::CircularString(g) => self.points(&mut g.points, &mut g.srid),
+            GeometryT::CompoundCurve(g) => self.compound_curve(g),
+            GeometryT::CurvePolygon(g) => {
+                for ring in &mut g.rings {
+                    match ring {
+                        Curve::LineString(g) => self.points(&mut g.points, &mut g.srid),
+                        Curve::CircularString(g) => self.points(&mut g.points, &mut g.srid),
+                        Curve::CompoundCurve(g) => self.compound_curve(g),
+                    }
+                }
+                self.srid(&mut g.srid);
+            }
+        }
+    }
+}
+
+/// Applies the transformation and, if `srid` is given, replaces every SRID which is set.
+struct Reprojection<'a, T> {
+    transform: Option<&'a T>,
+    srid: Option<i32>,
+}
+
+impl<T: CoordTransform> PointVisitor for Reprojection<'_, T> {
+    fn point<P: PointCoords>(&self, p: &mut P) {
+        let (x, y) = match self.transform {
+            Some(t) => t.transform(p.x(), p.y()),
+            None => (p.x(), p.y()),
+        };
+        let srid = p.opt_srid().map(|srid| self.srid.unwrap_or(srid));
+        *p = P::new_from_opt_vals(x, y, p.opt_z(), p.opt_m(), srid);
+    }
+
+    fn srid(&self, srid: &mut Option<i32>) {
+        if srid.is_some() && self.srid.is_some() {
+            *srid = self.srid;
+        }
+    }
+}
+
+/// Rounds X/Y, Z and M to their own number of decimals.
+struct Rounding {
+    xy: f64,
+    z: f64,
+    m: f64,
+}
+
+impl Rounding {
+    fn new(xy: u32, z: u32, m: u32) -> Self {
+        let scale = |decimals: u32| 10f64.powi(decimals as i32);
+        Rounding {
+            xy: scale(xy),
+            z: scale(z),
+            m: scale(m),
+        }
+    }
+}
+
+impl PointVisitor for Rounding {
+    fn point<P: PointCoords>(&self, p: &mut P) {
+        let round = |v: f64, scale: f64| (v * scale).round() / scale;
+        *p = P::new_from_opt_vals(
+            round(p.x(), self.xy),
+            round(p.y(), self.xy),
+            p.opt_z().map(|z| round(z, self.z)),
+            p.opt_m().map(|m| round(m, self.m)),
+            p.opt_srid(),
+        );
+    }
+}
+
+impl<P: PointCoords + Clone> GeometryT<P> {
+    /// Apply `t` to every coordinate and set the SRID of the result to `to_srid`.
+    ///
+    /// The coordinates are assumed to be in the reference system `from_srid`, the SRID stored
+    /// in the geometry isn't checked. If `from_srid` equals `to_srid`, the coordinates are
+    /// copied without calling `t`. Z and M values are kept. SRIDs of sub-geometries and points
+    /// are replaced by `to_srid` if they are set; a missing SRID of the geometry is set like
+    /// [`GeometryCollectionT::into_parts`] does for its parts.
+    pub fn reproject<T: CoordTransform>(&self, from_srid: i32, to_srid: i32, t: &T) -> Self {
+        let reprojection = Reprojection {
+            transform: (from_srid != to_srid).then_some(t),
+            srid: Some(to_srid),
+        };
+        let mut geom = self.clone();
+        reprojection.geometry(&mut geom);
+        stamp_geometry(&mut geom, Some(to_srid));
+        geom
+    }
+
+    /// Move every coordinate by `dx` and `dy`, like `ST_Translate`.
+    ///
+    /// Z and M values and the SRIDs are kept.
+    pub fn translate(&self, dx: f64, dy: f64) -> Self {
+        self.map_xy(|x, y| (x + dx, y + dy))
+    }
+
+    /// Scale X by `fx` and Y by `fy` relative to `origin`, like `ST_Scale` with a reference
+    /// point. The origin defaults to (0, 0).
+    ///
+    /// Z and M values and the SRIDs are kept.
+    pub fn scale(&self, fx: f64, fy: f64, origin: Option<(f64, f64)>) -> Self {
+        let (ox, oy) = origin.unwrap_or((0.0, 0.0));
+        self.map_xy(|x, y| ((x - ox) * fx + ox, (y - oy) * fy + oy))
+    }
+
+    fn map_xy(&self, f: impl Fn(f64, f64) -> (f64, f64)) -> Self {
+        let mapping = Reprojection {
+            transform: Some(&f),
+            srid: None,
+        };
+        let mut geom = self.clone();
+        mapping.geometry(&mut geom);
+        geom
+    }
+}
+
+impl<P: PointCoords> GeometryT<P> {
+    /// Round X and Y of every coordinate to the nearest node of the grid with the given origin
+    /// and cell sizes, like `ST_SnapToGrid`.
+    ///
+    /// A size of 0 leaves the axis unchanged. Z and M values are never snapped. Unlike
+    /// `ST_SnapToGrid`, points which become identical are kept; remove them with
+    /// [`MultiPointT::dedup`] or [`LineStringT::retain_points`] if required.
+    pub fn snap_to_grid(&mut self, origin_x: f64, origin_y: f64, size_x: f64, size_y: f64) {
+        let snap = |v: f64, origin: f64, size: f64| {
+            if size == 0.0 {
+                v
+            } else {
+                ((v - origin) / size).round() * size + origin
+            }
+        };
+        let snap_xy = |x, y| (snap(x, origin_x, size_x), snap(y, origin_y, size_y));
+        let snapping = Reprojection {
+            transform: Some(&snap_xy),
+            srid: None,
+        };
+        snapping.geometry(self);
+    }
+}
+
+macro_rules! impl_round_coords_detailed {
+    ($geotype:ident, |$visitor:ident, $geom:ident| $body:expr) => {
+        impl<P: PointCoords> $geotype<P> {
+            /// Round X and Y to `xy` decimals, Z to `z` decimals and M to `m` decimals.
+            ///
+            /// Dimensions missing in the point type are ignored, e.g. `m` has no effect on
+            /// `PointZ` coordinates. Rounding is done in binary floating point, so a rounded
+            /// value like 0.1 is the nearest `f64`, which is printed with the expected digits.
+            pub fn round_coords_detailed(&mut self, xy: u32, z: u32, m: u32) {
+                let $visitor = Rounding::new(xy, z, m);
+                let $geom = self;
+                $body
+            }
+        }
+    };
+}
+
+impl_round_coords_detailed!(LineStringT, |r, g| r.points(&mut g.points, &mut g.srid));
+impl_round_coords_detailed!(CircularStringT, |r, g| r.points(&mut g.points, &mut g.srid));
+impl_round_coords_detailed!(MultiPointT, |r, g| r.points(&mut g.points, &mut g.srid));
+impl_round_coords_detailed!(PolygonT, |r, g| r.polygon(g));
+impl_round_coords_detailed!(MultiLineStringT, |r, g| r.lines(&mut g.lines));
+impl_round_coords_detailed!(MultiPolygonT, |r, g| g
+    .polygons
+    .iter_mut()
+    .for_each(|p| r.polygon(p)));
+impl_round_coords_detailed!(CompoundCurveT, |r, g| r.compound_curve(g));
+impl_round_coords_detailed!(GeometryT, |r, g| r.geometry(g));
+
+#[test]
+#[rustfmt::skip]
+fn test_reproject() {
+    // Spherical web mercator
+    let mercator = |lon: f64, lat: f64| {
+        let r = 6_378_137.0;
+        (r * lon.to_radians(), r * (std::f64::consts::FRAC_PI_4 + lat.to_radians() / 2.0).tan().ln())
+    };
+    let line = LineStringT::<PointZ> {srid: None, points: vec![PointZ::new(0., 0., 5., None), PointZ::new(180., 0., 6., None)]};
+    let geom = GeometryT::LineString(line).reproject(4326, 3857, &mercator);
+    match &geom {
+        GeometryT::LineString(line) => {
+            assert_eq!(line.srid, Some(3857));
+            assert_eq!(line.points[0].srid, Some(3857));
+            assert!(line.points[0].y.abs() < 1e-6);
+            assert_eq!(line.points[0].z, 5.);
+            assert!((line.points[1].x - 20_037_508.34).abs() < 0.01);
+            assert_eq!(line.points[1].z, 6.);
+        }
+        _ => panic!("wrong geometry type"),
+    }
+
+    // Identical SRIDs don't call the transformation
+    let p = |x, y| Point::new(x, y, Some(4326));
+    let multi = MultiPointT::<Point> {srid: Some(4326), points: vec![p(1., 2.)]};
+    let geom = GeometryT::MultiPoint(multi.clone()).reproject(4326, 4326, &|_, _| -> (f64, f64) { unreachable!() });
+    match geom {
+        GeometryT::MultiPoint(g) => assert_eq!(g, multi),
+        _ => panic!("wrong geometry type"),
+    }
+
+    let collection = GeometryCollectionT::<Point> {srid: Some(4326), geometries: vec![GeometryT::Point(Point::new(1., 2., None))]};
+    let geom = GeometryT::GeometryCollection(collection).reproject(4326, 2056, &|x, y| (x + 1., y * 2.));
+    match geom {
+        GeometryT::GeometryCollection(g) => {
+            assert_eq!(g.srid, Some(2056));
+            assert!(matches!(g.geometries[0], GeometryT::Point(p) if p == Point::new(2., 4., None)));
+        }
+        _ => panic!("wrong geometry type"),
+    }
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_translate_scale() {
+    let p = |x, y| PointZ::new(x, y, 5., Some(4326));
+    let line = GeometryT::LineString(LineStringT::<PointZ> {srid: Some(4326), points: vec![p(0., 0.), p(2., 1.)]});
+    match line.translate(1., -1.) {
+        GeometryT::LineString(l) => {
+            assert_eq!(l.points, vec![p(1., -1.), p(3., 0.)]);
+            assert_eq!(l.srid, Some(4326));
+        }
+        _ => panic!("wrong geometry type"),
+    }
+    match line.scale(2., 3., None) {
+        GeometryT::LineString(l) => assert_eq!(l.points, vec![p(0., 0.), p(4., 3.)]),
+        _ => panic!("wrong geometry type"),
+    }
+    // Scaling about (1 1) keeps it in place
+    match line.scale(2., 2., Some((1., 1.))) {
+        GeometryT::LineString(l) => assert_eq!(l.points, vec![p(-1., -1.), p(3., 1.)]),
+        _ => panic!("wrong geometry type"),
+    }
+
+    let collection = GeometryT::GeometryCollection(GeometryCollectionT::<Point> {srid: None, geometries: vec![GeometryT::Point(Point::new(1., 2., None))]});
+    let moved = collection.translate(10., 20.).scale(0.5, 0.5, None);
+    assert_eq!(moved.to_ewkt(), "GEOMETRYCOLLECTION (POINT (5.5 11))");
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_snap_to_grid() {
+    let p = |x, y| PointZ::new(x, y, 1.26, Some(4326));
+    let line = LineStringT::<PointZ> {srid: Some(4326), points: vec![p(0.26, -0.74), p(1.24, 3.1), p(1.26, 3.1)]};
+    let mut geom = GeometryT::GeometryCollection(GeometryCollectionT {srid: Some(4326), geometries: vec![GeometryT::LineString(line)]});
+    geom.snap_to_grid(0., 0.25, 0.5, 0.);
+    match geom {
+        GeometryT::GeometryCollection(g) => match &g.geometries[0] {
+            GeometryT::LineString(line) => assert_eq!(line.points, vec![p(0.5, -0.74), p(1., 3.1), p(1.5, 3.1)]),
+            _ => panic!("wrong geometry type"),
+        },
+        _ => panic!("wrong geometry type"),
+    }
+
+    let mut geom = GeometryT::Point(Point::new(-1.2, 7.9, None));
+    geom.snap_to_grid(0.5, 0.5, 1., 1.);
+    assert!(matches!(geom, GeometryT::Point(p) if p == Point::new(-1.5, 7.5, None)));
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_round_coords_detailed() {
+    let p = |x, y, z, m| PointZM::new(x, y, z, m, Some(4326));
+    let mut line = LineStringT::<PointZM> {srid: Some(4326), points: vec![p(7.1234567, 46.9876543, 512.345, 10.12345)]};
+    line.round_coords_detailed(6, 2, 3);
+    assert_eq!(line.points, vec![p(7.123457, 46.987654, 512.35, 10.123)]);
+
+    let mut geom = GeometryT::MultiPoint(MultiPointT::<PointM> {srid: None, points: vec![PointM::new(1.25, -1.75, 0.4444, None)]});
+    geom.round_coords_detailed(0, 5, 1);
+    match geom {
+        GeometryT::MultiPoint(g) => assert_eq!(g.points, vec![PointM::new(1., -2., 0.4, None)]),
+        _ => panic!("wrong geometry type"),
+    }
+}
@@ -0,0 +1,111 @@
+//! Ear-clipping triangulation of polygons.
+
+use crate::ewkb::validate::orientation;
+use crate::ewkb::*;
+
+type Coord = (f64, f64);
+
+/// Whether `p` lies inside or on the boundary of the counter-clockwise triangle a-b-c.
+fn in_triangle(a: Coord, b: Coord, c: Coord, p: Coord) -> bool {
+    orientation(a, b, p) >= 0.0 && orientation(b, c, p) >= 0.0 && orientation(c, a, p) >= 0.0
+}
+
+/// Triangulate a simple ring, given without closing point.
+fn ear_clipping(mut ring: Vec<Coord>) -> Vec<[Coord; 3]> {
+    let area: f64 = (0..ring.len())
+        .map(|i| {
+            let (a, b) = (ring[i], ring[(i + 1) % ring.len()]);
+            a.0 * b.1 - b.0 * a.1
+        })
+        .sum();
+    if area < 0.0 {
+        ring.reverse();
+    }
+    let mut triangles = Vec::with_capacity(ring.len().saturating_sub(2));
+    let mut i = 0;
+    // Number of vertices checked since the last removal
+    let mut checked = 0;
+    while ring.len() >= 3 && checked < ring.len() {
+        let n = ring.len();
+        let (a, b, c) = (ring[(i + n - 1) % n], ring[i % n], ring[(i + 1) % n]);
+        let turn = orientation(a, b, c);
+        let is_ear = turn > 0.0
+            && !ring
+                .iter()
+                .filter(|&&p| p != a && p != b && p != c)
+                .any(|&p| in_triangle(a, b, c, p));
+        if turn == 0.0 || is_ear {
+            // Collinear vertices are dropped without a triangle
+            if is_ear {
+                triangles.push([a, b, c]);
+            }
+            ring.remove(i % n);
+            i %= n - 1;
+            checked = 0;
+        } else {
+            i = (i + 1) % n;
+            checked += 1;
+        }
+    }
+    triangles
+}
+
+impl<P> PolygonT<P>
+where
+    P: postgis::Point + EwkbRead,
+{
+    /// Split the exterior ring into triangles by ear clipping, e.g. for rendering.
+    ///
+    /// Holes are not supported and ignored. The ring may be clockwise or counter-clockwise,
+    /// the triangles are always counter-clockwise. Duplicate points and collinear vertices
+    /// don't produce degenerate triangles. For a self-intersecting ring, the triangulation
+    /// stops when no more ears are found and the triangles found so far are returned.
+    /// The points are 2D and carry the SRID of the polygon. The naive algorithm takes
+    /// O(n³) time in the worst case.
+    pub fn triangulate(&self) -> Vec<[Point; 3]> {
+        let Some(exterior) = self.rings.first() else {
+            return Vec::new();
+        };
+        let mut ring: Vec<Coord> = Vec::with_capacity(exterior.points.len());
+        for p in &exterior.points {
+            if ring.last() != Some(&(p.x(), p.y())) {
+                ring.push((p.x(), p.y()));
+            }
+        }
+        if ring.len() > 1 && ring.first() == ring.last() {
+            ring.pop();
+        }
+        let point = |(x, y): Coord| Point::new(x, y, self.srid);
+        ear_clipping(ring)
+            .into_iter()
+            .map(|[a, b, c]| [point(a), point(b), point(c)])
+            .collect()
+    }
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_triangulate() {
+    let polygon = |coords: &[(f64, f64)]| PolygonT::<Point> {srid: Some(4326), rings: vec![line_xy(coords)]};
+    let area = |t: &[Point; 3]| orientation((t[0].x(), t[0].y()), (t[1].x(), t[1].y()), (t[2].x(), t[2].y())) / 2.0;
+
+    // Clockwise square with a duplicate and a collinear point
+    let square = polygon(&[(0., 0.), (0., 2.), (0., 2.), (2., 2.), (2., 1.), (2., 0.), (0., 0.)]);
+    let triangles = square.triangulate();
+    assert!(triangles.len() <= 3);
+    assert!(triangles.iter().all(|t| area(t) > 0.0));
+    assert_eq!(triangles.iter().map(area).sum::<f64>(), 4.0);
+    assert_eq!(triangles[0][0].srid, Some(4326));
+
+    // Concave "U" shape
+    let u = polygon(&[(0., 0.), (3., 0.), (3., 3.), (2., 3.), (2., 1.), (1., 1.), (1., 3.), (0., 3.), (0., 0.)]);
+    let triangles = u.triangulate();
+    assert_eq!(triangles.len(), 6);
+    assert!(triangles.iter().all(|t| area(t) > 0.0));
+    assert_eq!(triangles.iter().map(area).sum::<f64>(), 7.0);
+    // No triangle covers the notch
+    assert!(!triangles.iter().any(|t| in_triangle((t[0].x(), t[0].y()), (t[1].x(), t[1].y()), (t[2].x(), t[2].y()), (1.5, 2.))));
+
+    assert!(polygon(&[(0., 0.), (1., 1.), (2., 2.), (0., 0.)]).triangulate().is_empty());
+    assert!(PolygonT::<Point>::new().triangulate().is_empty());
+}
@@ -0,0 +1,255 @@
+//! Client-side validity checks for polygons.
+
+use crate::ewkb::compare::coords_eq;
+use crate::ewkb::measure::ring_signed_area;
+use crate::ewkb::*;
+
+/// Reason why a polygon is invalid, see [`PolygonT::validate`].
+///
+/// `polygon` is the index of the polygon within a multipolygon (always 0 for a single
+/// polygon) and `ring` the index of the ring, 0 being the exterior ring.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ValidationError {
+    /// The first and last point of the ring differ.
+    UnclosedRing { polygon: usize, ring: usize },
+    /// The ring has less than 4 points.
+    TooFewPoints { polygon: usize, ring: usize },
+    /// Two non-adjacent segments of the ring touch or cross.
+    SelfIntersection { polygon: usize, ring: usize },
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ValidationError::UnclosedRing { polygon, ring } => {
+                write!(f, "ring {ring} of polygon {polygon} is not closed")
+            }
+            ValidationError::TooFewPoints { polygon, ring } => {
+                write!(f, "ring {ring} of polygon {polygon} has too few points")
+            }
+            ValidationError::SelfIntersection { polygon, ring } => {
+                write!(f, "ring {ring} of polygon {polygon} self-intersects")
+            }
+        }
+    }
+}
+
+/// Cross product (b - a) x (c - a), positive if c is left of a-b and zero if collinear.
+pub(super) fn orientation(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> f64 {
+    (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)
+}
+
+/// Whether c, known to be collinear with a-b, lies within the bounding box of a-b.
+pub(super) fn on_segment(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> bool {
+    c.0 >= a.0.min(b.0) && c.0 <= a.0.max(b.0) && c.1 >= a.1.min(b.1) && c.1 <= a.1.max(b.1)
+}
+
+/// Whether the closed segments p1-p2 and q1-q2 have a point in common.
+pub(super) fn segments_intersect(
+    p1: (f64, f64),
+    p2: (f64, f64),
+    q1: (f64, f64),
+    q2: (f64, f64),
+) -> bool {
+    let (o1, o2) = (orientation(p1, p2, q1), orientation(p1, p2, q2));
+    let (o3, o4) = (orientation(q1, q2, p1), orientation(q1, q2, p2));
+    let opposite = |u: f64, v: f64| (u < 0.0 && v > 0.0) || (u > 0.0 && v < 0.0);
+    if opposite(o1, o2) && opposite(o3, o4) {
+        return true;
+    }
+    (o1 == 0.0 && on_segment(p1, p2, q1))
+        || (o2 == 0.0 && on_segment(p1, p2, q2))
+        || (o3 == 0.0 && on_segment(q1, q2, p1))
+        || (o4 == 0.0 && on_segment(q1, q2, p2))
+}
+
+/// O(n^2) test of a closed ring for touching or crossing non-adjacent segments.
+///
+/// Consecutive duplicate points are ignored.
+fn ring_self_intersects<P: postgis::Point>(points: &[P]) -> bool {
+    let mut coords: Vec<(f64, f64)> = Vec::with_capacity(points.len());
+    for p in points {
+        if coords.last() != Some(&(p.x(), p.y())) {
+            coords.push((p.x(), p.y()));
+        }
+    }
+    let n = coords.len().saturating_sub(1);
+    for i in 0..n {
+        for j in i + 2..n {
+            // The first and last segment share the closing point
+            if i == 0 && j == n - 1 {
+                continue;
+            }
+            if segments_intersect(coords[i], coords[i + 1], coords[j], coords[j + 1]) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+fn validate_rings<P>(rings: &[LineStringT<P>], polygon: usize, errors: &mut Vec<ValidationError>)
+where
+    P: postgis::Point + EwkbRead,
+{
+    for (ring, line) in rings.iter().enumerate() {
+        if line.points.len() < 4 {
+            errors.push(ValidationError::TooFewPoints { polygon, ring });
+        }
+        if !line.is_ring() {
+            errors.push(ValidationError::UnclosedRing { polygon, ring });
+        } else if line.points.len() >= 4 && ring_self_intersects(&line.points) {
+            errors.push(ValidationError::SelfIntersection { polygon, ring });
+        }
+    }
+}
+
+impl<P> PolygonT<P>
+where
+    P: postgis::Point + EwkbRead,
+{
+    /// Check the rings for common mistakes before sending the polygon to the database.
+    ///
+    /// Reports unclosed rings, rings with less than 4 points and rings whose segments touch or
+    /// cross each other. This is a client-side approximation of `ST_IsValid`, it doesn't check
+    /// the relation between different rings, e.g. holes outside of the exterior ring.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+        validate_rings(&self.rings, 0, &mut errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl<P> MultiPolygonT<P>
+where
+    P: postgis::Point + EwkbRead,
+{
+    /// Check all polygons like [`PolygonT::validate`].
+    ///
+    /// Overlapping polygons are not detected.
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+        for (polygon, poly) in self.polygons.iter().enumerate() {
+            validate_rings(&poly.rings, polygon, &mut errors);
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Remove consecutive duplicates, close the ring and orient it, `None` if less than 4 points remain.
+fn repair_ring<P: PointCoords + Clone>(
+    ring: &LineStringT<P>,
+    counter_clockwise: bool,
+) -> Option<LineStringT<P>> {
+    let mut points: Vec<P> = Vec::with_capacity(ring.points.len() + 1);
+    for p in &ring.points {
+        if !points.last().is_some_and(|last| coords_eq(last, p)) {
+            points.push(p.clone());
+        }
+    }
+    if points.len() > 1 && !coords_eq(&points[0], &points[points.len() - 1]) {
+        points.push(points[0].clone());
+    }
+    if points.len() < 4 {
+        return None;
+    }
+    let area = ring_signed_area(&points);
+    if (counter_clockwise && area < 0.0) || (!counter_clockwise && area > 0.0) {
+        points.reverse();
+    }
+    Some(LineStringT {
+        points,
+        srid: ring.srid,
+    })
+}
+
+impl<P> PolygonT<P>
+where
+    P: PointCoords + Clone,
+{
+    /// Best-effort repair of the common mistakes reported by [`validate`](Self::validate).
+    ///
+    /// Consecutive duplicate points are removed, open rings are closed and rings with less
+    /// than 4 points afterwards are dropped. Rings are oriented by the OGC convention,
+    /// the exterior counter-clockwise and holes clockwise. Self-intersections are not
+    /// resolved, use `ST_MakeValid` for these. If the exterior ring is dropped, the result
+    /// is an empty polygon.
+    pub fn make_valid_lite(&self) -> PolygonT<P> {
+        let mut rings = Vec::with_capacity(self.rings.len());
+        let mut repaired = self
+            .rings
+            .iter()
+            .enumerate()
+            .map(|(i, ring)| repair_ring(ring, i == 0));
+        if let Some(Some(exterior)) = repaired.next() {
+            rings.push(exterior);
+            rings.extend(repaired.flatten());
+        }
+        PolygonT {
+            rings,
+            srid: self.srid,
+        }
+    }
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_validate() {
+    let square = line_xy(&[(0., 0.), (4., 0.), (4., 4.), (4., 4.), (0., 4.), (0., 0.)]);
+    let poly = PolygonT::<Point> {srid: None, rings: vec![square.clone()]};
+    assert_eq!(poly.validate(), Ok(()));
+
+    // Bow tie
+    let bowtie = line_xy(&[(0., 0.), (4., 4.), (4., 0.), (0., 4.), (0., 0.)]);
+    let unclosed = line_xy(&[(1., 1.), (2., 1.), (2., 2.), (1., 2.)]);
+    let short = line_xy(&[(1., 1.), (2., 1.), (1., 1.)]);
+    let poly = PolygonT::<Point> {srid: None, rings: vec![bowtie, unclosed, short]};
+    assert_eq!(poly.validate(), Err(vec![ValidationError::SelfIntersection {polygon: 0, ring: 0},
+                                         ValidationError::UnclosedRing {polygon: 0, ring: 1},
+                                         ValidationError::TooFewPoints {polygon: 0, ring: 2}]));
+
+    // Ring touching itself in a vertex
+    let touching = line_xy(&[(0., 0.), (4., 0.), (2., 2.), (4., 4.), (0., 4.), (2., 2.), (0., 0.)]);
+    let multi = MultiPolygonT::<Point> {srid: None, polygons: vec![poly.clone(), PolygonT {srid: None, rings: vec![square, touching]}]};
+    let errors = multi.validate().unwrap_err();
+    assert_eq!(errors.len(), 4);
+    assert_eq!(errors[3], ValidationError::SelfIntersection {polygon: 1, ring: 1});
+    assert_eq!(errors[3].to_string(), "ring 1 of polygon 1 self-intersects");
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_make_valid_lite() {
+    let xy = |l: &LineStringT<Point>| l.points.iter().map(|p| (p.x(), p.y())).collect::<Vec<_>>();
+
+    // Open clockwise exterior with a duplicate, open counter-clockwise hole and a short ring
+    let poly = PolygonT::<Point> {srid: Some(4326), rings: vec![
+        line_xy(&[(0., 0.), (0., 4.), (0., 4.), (4., 4.), (4., 0.)]),
+        line_xy(&[(1., 1.), (2., 1.), (2., 2.), (1., 2.), (1., 1.), (1., 1.)]),
+        line_xy(&[(3., 3.), (3.5, 3.), (3., 3.)]),
+    ]};
+    assert!(poly.validate().is_err());
+    let fixed = poly.make_valid_lite();
+    assert_eq!(fixed.validate(), Ok(()));
+    assert_eq!(fixed.srid, Some(4326));
+    assert_eq!(fixed.rings.len(), 2);
+    assert_eq!(xy(&fixed.rings[0]), vec![(0., 0.), (4., 0.), (4., 4.), (0., 4.), (0., 0.)]);
+    assert_eq!(xy(&fixed.rings[1]), vec![(1., 1.), (1., 2.), (2., 2.), (2., 1.), (1., 1.)]);
+    assert_eq!(fixed.orientation_report(), vec![Orientation::CounterClockwise, Orientation::Clockwise]);
+
+    // Valid polygons are unchanged
+    assert_eq!(fixed.make_valid_lite(), fixed);
+
+    // Without a usable exterior the holes are dropped as well
+    let poly = PolygonT::<Point> {srid: None, rings: vec![line_xy(&[(0., 0.), (1., 1.), (1., 1.)]), line_xy(&[(1., 1.), (2., 1.), (2., 2.)])]};
+    assert!(poly.make_valid_lite().rings.is_empty());
+    assert!(PolygonT::<Point>::new().make_valid_lite().rings.is_empty());
+}
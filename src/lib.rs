@@ -7,11 +7,15 @@
 //! - PostGIS type helper
 //! - GCJ02 support (used offically in Mainland China)
 //! - Tiny WKB (TWKB) support
+//! - WKB raster header support
 //!
 //! ```rust,no_run
 //! use postgres::{Client, NoTls};
-//! use postgis::{ewkb, LineString};
+//! use postgis_butmaintained::{ewkb, LineString};
 //!
+//! # #[cfg(not(feature = "postgres"))]
+//! # fn main() {}
+//! # #[cfg(feature = "postgres")]
 //! fn main() {
 //!     let mut client = Client::connect("host=localhost user=postgres", NoTls).unwrap();
 //!     for row in &client.query("SELECT * FROM busline", &[]).unwrap() {
@@ -24,9 +28,14 @@
 //!
 //! Handling NULL values:
 //!
+//! All geometry types implement `FromSql` for the non-NULL case, reading a nullable column
+//! works through the `Option<T>` implementation of `postgres-types`, which returns `None`
+//! for NULL. Reading NULL into a non-`Option` type fails with a `WasNull` error.
+//!
 //! ```rust,no_run
+//! # #[cfg(feature = "postgres")] {
 //! # use postgres::{Client, NoTls};
-//! # use postgis::{ewkb, LineString};
+//! # use postgis_butmaintained::{ewkb, LineString};
 //! # let mut client = Client::connect("host=localhost user=postgres", NoTls).unwrap();
 //! # let rows = client.query("SELECT * FROM busline", &[]).unwrap();
 //! # let row = rows.first().unwrap();
@@ -36,6 +45,7 @@
 //!     Ok(None) => { /* Handle NULL value */ }
 //!     Err(err) => { println!("Error: {}", err) }
 //! }
+//! # }
 //! ```
 
 pub mod error;
@@ -43,5 +53,9 @@ mod types;
 pub use types::{LineString, MultiLineString, MultiPoint, MultiPolygon, Point, Polygon};
 pub mod ewkb;
 pub mod mars;
+pub mod raster;
+#[cfg(feature = "postgres")]
 mod postgis;
+#[cfg(feature = "postgres")]
+pub use postgis::Geography;
 pub mod twkb;
@@ -7,6 +7,9 @@
 
 //! Conversion between GCJ-02 and WGS-84 coordinates.
 
+// The numeric constants are kept verbatim from the reference implementation.
+#![allow(clippy::approx_constant)]
+
 use crate::ewkb;
 
 // https://github.com/Artoria2e5/emq/blob/master/emq/src/Algorithm/Coords/Converter.java
@@ -342,7 +345,7 @@ impl ewkb::Point {
         ewkb::Point::new(x0, y0, Some(4326))
     }
     pub fn to_gcj02(&self) -> (f64, f64) {
-        from_wgs84(self.point.x(), self.point.y())
+        from_wgs84(self.x(), self.y())
     }
 }
 
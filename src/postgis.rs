@@ -5,15 +5,19 @@
 
 use crate::{
 	ewkb::{
-		self, AsEwkbGeometry, AsEwkbGeometryCollection, AsEwkbLineString, AsEwkbMultiLineString,
-		AsEwkbMultiPoint, AsEwkbMultiPolygon, AsEwkbPoint, AsEwkbPolygon, EwkbRead, EwkbWrite,
+		self, AsEwkbCircularString, AsEwkbGeometry, AsEwkbGeometryCollection, AsEwkbLineString,
+		AsEwkbMultiLineString, AsEwkbMultiPoint, AsEwkbMultiPolygon, AsEwkbPoint, AsEwkbPolygon,
+		EwkbRead, EwkbWrite,
 	},
 	twkb::{self, TwkbGeom},
-	types::{LineString, Point, Polygon},
+	types::{
+		Geometry, GeometryCollection, LineString, MultiLineString, MultiPoint, MultiPolygon, Point,
+		Polygon,
+	},
 };
-use bytes::{BufMut, BytesMut};
-use postgres_types::{FromSql, IsNull, ToSql, Type, accepts, to_sql_checked};
-use std::{error::Error, io::Cursor};
+use bytes::BytesMut;
+use postgres_types::{FromSql, FromSqlOwned, IsNull, ToSql, Type, accepts, to_sql_checked};
+use std::{borrow::Cow, error::Error, io::Cursor};
 
 macro_rules! accepts_geography {
 	() => {
@@ -26,23 +30,112 @@ macro_rules! accepts_geography {
 	};
 }
 
+/// A value of a `geography` column, with a missing SRID set to 4326.
+///
+/// PostGIS assumes SRID 4326 for `geography` values without SRID or with SRID 0, this
+/// wrapper decodes them with SRID 4326 instead of `None` or `Some(0)`. Values of other types
+/// like `geometry` are decoded unchanged. Geometries are written as they are.
+///
+/// ```rust,ignore
+/// let Geography(point): Geography<ewkb::Point> = row.get("location");
+/// ```
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct Geography<T>(pub T);
+
+impl<T: FromSqlOwned> FromSql<'_> for Geography<T> {
+	fn from_sql(ty: &Type, raw: &[u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+		T::from_sql(ty, &normalize_geography_srid(ty, raw)).map(Geography)
+	}
+
+	fn accepts(ty: &Type) -> bool {
+		T::accepts(ty)
+	}
+}
+
+impl<T: ToSql> ToSql for Geography<T> {
+	to_sql_checked!();
+
+	fn to_sql(&self, ty: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+		self.0.to_sql(ty, out)
+	}
+
+	fn accepts(ty: &Type) -> bool {
+		T::accepts(ty)
+	}
+}
+
+/// Set a missing or zero SRID in the EWKB header of a `geography` value to 4326.
+fn normalize_geography_srid<'r>(ty: &Type, raw: &'r [u8]) -> Cow<'r, [u8]> {
+	if ty.name() != "geography" || raw.len() < 5 {
+		return Cow::Borrowed(raw);
+	}
+	let is_be = raw[0] == 0;
+	let to_bytes = |v: u32| {
+		if is_be {
+			v.to_be_bytes()
+		} else {
+			v.to_le_bytes()
+		}
+	};
+	let type_bytes = [raw[1], raw[2], raw[3], raw[4]];
+	let type_id = if is_be {
+		u32::from_be_bytes(type_bytes)
+	} else {
+		u32::from_le_bytes(type_bytes)
+	};
+	if type_id & 0x20000000 == 0 {
+		let mut patched = Vec::with_capacity(raw.len() + 4);
+		patched.push(raw[0]);
+		patched.extend_from_slice(&to_bytes(type_id | 0x20000000));
+		patched.extend_from_slice(&to_bytes(4326));
+		patched.extend_from_slice(&raw[5..]);
+		Cow::Owned(patched)
+	} else if raw.len() >= 9 && raw[5..9] == [0; 4] {
+		let mut patched = raw.to_vec();
+		patched[5..9].copy_from_slice(&to_bytes(4326));
+		Cow::Owned(patched)
+	} else {
+		Cow::Borrowed(raw)
+	}
+}
+
 impl ToSql for ewkb::EwkbPoint<'_> {
 	accepts_geography!();
 
 	to_sql_checked!();
 
 	fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
-		self.write_ewkb(&mut out.writer())?;
+		self.write_ewkb_to_bytes_mut(out)?;
 		Ok(IsNull::No)
 	}
 }
 
+/// Decode a little endian 2D point without or with SRID directly, skipping the generic reader.
+///
+/// Returns `None` for any other layout, including an SRID the generic reader would reject.
+fn read_simple_point(raw: &[u8]) -> Option<ewkb::Point> {
+	let f64_at = |i: usize| f64::from_le_bytes(raw[i..i + 8].try_into().unwrap());
+	match raw {
+		[1, 1, 0, 0, 0, ..] if raw.len() == 21 => Some(ewkb::Point::new(f64_at(5), f64_at(13), None)),
+		[1, 1, 0, 0, 0x20, ..] if raw.len() == 25 => {
+			let srid = i32::from_le_bytes(raw[5..9].try_into().unwrap());
+			(0..=ewkb::SRID_MAXIMUM)
+				.contains(&srid)
+				.then(|| ewkb::Point::new(f64_at(9), f64_at(17), Some(srid)))
+		}
+		_ => None,
+	}
+}
+
 macro_rules! impl_sql_for_point_type {
-	($ptype:ident) => {
+	($ptype:ident $(, fast path $fast_path:ident)?) => {
 		impl<'a> FromSql<'a> for ewkb::$ptype {
 			accepts_geography!();
 
 			fn from_sql(ty: &Type, raw: &[u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+				$(if let Some(point) = $fast_path(raw) {
+					return Ok(point);
+				})?
 				let mut rdr = Cursor::new(raw);
 				ewkb::$ptype::read_ewkb(&mut rdr)
 					.map_err(|_| format!("cannot convert {} to {}", ty, stringify!($ptype)).into())
@@ -59,14 +152,14 @@ macro_rules! impl_sql_for_point_type {
 				_: &Type,
 				out: &mut BytesMut,
 			) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
-				self.as_ewkb().write_ewkb(&mut out.writer())?;
+				self.as_ewkb().write_ewkb_to_bytes_mut(out)?;
 				Ok(IsNull::No)
 			}
 		}
 	};
 }
 
-impl_sql_for_point_type!(Point);
+impl_sql_for_point_type!(Point, fast path read_simple_point);
 impl_sql_for_point_type!(PointZ);
 impl_sql_for_point_type!(PointM);
 impl_sql_for_point_type!(PointZM);
@@ -100,7 +193,7 @@ macro_rules! impl_sql_for_geom_type {
 				_: &Type,
 				out: &mut BytesMut,
 			) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
-				self.as_ewkb().write_ewkb(&mut out.writer())?;
+				self.as_ewkb().write_ewkb_to_bytes_mut(out)?;
 				Ok(IsNull::No)
 			}
 		}
@@ -112,6 +205,9 @@ impl_sql_for_geom_type!(PolygonT);
 impl_sql_for_geom_type!(MultiPointT);
 impl_sql_for_geom_type!(MultiLineStringT);
 impl_sql_for_geom_type!(MultiPolygonT);
+impl_sql_for_geom_type!(CircularStringT);
+impl_sql_for_geom_type!(CompoundCurveT);
+impl_sql_for_geom_type!(CurvePolygonT);
 
 macro_rules! impl_sql_for_ewkb_type {
 	($ewkbtype:ident contains points) => {
@@ -129,7 +225,7 @@ macro_rules! impl_sql_for_ewkb_type {
 				_: &Type,
 				out: &mut BytesMut,
 			) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
-				self.write_ewkb(&mut out.writer())?;
+				self.write_ewkb_to_bytes_mut(out)?;
 				Ok(IsNull::No)
 			}
 		}
@@ -151,7 +247,7 @@ macro_rules! impl_sql_for_ewkb_type {
 				_: &Type,
 				out: &mut BytesMut,
 			) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
-				self.write_ewkb(&mut out.writer())?;
+				self.write_ewkb_to_bytes_mut(out)?;
 				Ok(IsNull::No)
 			}
 		}
@@ -175,7 +271,7 @@ macro_rules! impl_sql_for_ewkb_type {
 				_: &Type,
 				out: &mut BytesMut,
 			) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
-				self.write_ewkb(&mut out.writer())?;
+				self.write_ewkb_to_bytes_mut(out)?;
 				Ok(IsNull::No)
 			}
 		}
@@ -188,6 +284,53 @@ impl_sql_for_ewkb_type!(EwkbMultiPoint contains points);
 impl_sql_for_ewkb_type!(EwkbMultiLineString contains LineString);
 impl_sql_for_ewkb_type!(multipoly EwkbMultiPolygon contains Polygon);
 
+macro_rules! impl_sql_for_ewkb_geometry_type {
+	($ewkbtype:ident) => {
+		impl<'a, P, PI, MP, L, LI, ML, Y, YI, MY, G, GI, GC> ToSql
+			for ewkb::$ewkbtype<'a, P, PI, MP, L, LI, ML, Y, YI, MY, G, GI, GC>
+		where
+			P: 'a + Point,
+			PI: 'a + Iterator<Item = &'a P> + ExactSizeIterator<Item = &'a P>,
+			MP: 'a + MultiPoint<'a, ItemType = P, Iter = PI>,
+			L: 'a + LineString<'a, ItemType = P, Iter = PI>,
+			LI: 'a + Iterator<Item = &'a L> + ExactSizeIterator<Item = &'a L>,
+			ML: 'a + MultiLineString<'a, ItemType = L, Iter = LI>,
+			Y: 'a + Polygon<'a, ItemType = L, Iter = LI>,
+			YI: 'a + Iterator<Item = &'a Y> + ExactSizeIterator<Item = &'a Y>,
+			MY: 'a + MultiPolygon<'a, ItemType = Y, Iter = YI>,
+			G: 'a
+				+ Geometry<
+					'a,
+					Point = P,
+					LineString = L,
+					Polygon = Y,
+					MultiPoint = MP,
+					MultiLineString = ML,
+					MultiPolygon = MY,
+					GeometryCollection = GC,
+				>,
+			GI: 'a + Iterator<Item = &'a G> + ExactSizeIterator<Item = &'a G>,
+			GC: 'a + GeometryCollection<'a, ItemType = G, Iter = GI>,
+		{
+			to_sql_checked!();
+
+			accepts_geography!();
+
+			fn to_sql(
+				&self,
+				_: &Type,
+				out: &mut BytesMut,
+			) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+				self.write_ewkb_to_bytes_mut(out)?;
+				Ok(IsNull::No)
+			}
+		}
+	};
+}
+
+impl_sql_for_ewkb_geometry_type!(EwkbGeometry);
+impl_sql_for_ewkb_geometry_type!(EwkbGeometryCollection);
+
 impl<P> FromSql<'_> for ewkb::GeometryT<P>
 where
 	P: Point + EwkbRead,
@@ -215,7 +358,7 @@ macro_rules! impl_geometry_to_sql {
 				_: &Type,
 				out: &mut BytesMut,
 			) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
-				self.as_ewkb().write_ewkb(&mut out.writer())?;
+				self.as_ewkb().write_ewkb_to_bytes_mut(out)?;
 				Ok(IsNull::No)
 			}
 		}
@@ -227,6 +370,65 @@ impl_geometry_to_sql!(ewkb::PointZ);
 impl_geometry_to_sql!(ewkb::PointM);
 impl_geometry_to_sql!(ewkb::PointZM);
 
+impl<P> ToSql for ewkb::EwkbGeometryOwned<P>
+where
+	P: Point + EwkbRead + for<'a> AsEwkbPoint<'a>,
+{
+	to_sql_checked!();
+
+	accepts_geography!();
+
+	fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+		self.write_ewkb_to_bytes_mut(out)?;
+		Ok(IsNull::No)
+	}
+}
+
+impl FromSql<'_> for ewkb::RawGeometry {
+	accepts_geography!();
+
+	fn from_sql(_: &Type, raw: &[u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+		Ok(ewkb::RawGeometry(raw.to_vec()))
+	}
+}
+
+impl ToSql for ewkb::RawGeometry {
+	to_sql_checked!();
+
+	accepts_geography!();
+
+	fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+		out.extend_from_slice(&self.0);
+		Ok(IsNull::No)
+	}
+}
+
+impl FromSql<'_> for ewkb::DynGeometry {
+	accepts_geography!();
+
+	fn from_sql(ty: &Type, raw: &[u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+		let mut rdr = Cursor::new(raw);
+		ewkb::DynGeometry::read_ewkb(&mut rdr)
+			.map_err(|_| format!("cannot convert {} to DynGeometry", ty).into())
+	}
+}
+
+impl ToSql for ewkb::DynGeometry {
+	to_sql_checked!();
+
+	accepts_geography!();
+
+	fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+		match self {
+			ewkb::DynGeometry::Xy(geom) => geom.as_ewkb().write_ewkb_to_bytes_mut(out)?,
+			ewkb::DynGeometry::Xyz(geom) => geom.as_ewkb().write_ewkb_to_bytes_mut(out)?,
+			ewkb::DynGeometry::Xym(geom) => geom.as_ewkb().write_ewkb_to_bytes_mut(out)?,
+			ewkb::DynGeometry::Xyzm(geom) => geom.as_ewkb().write_ewkb_to_bytes_mut(out)?,
+		}
+		Ok(IsNull::No)
+	}
+}
+
 impl<P> FromSql<'_> for ewkb::GeometryCollectionT<P>
 where
 	P: Point + EwkbRead,
@@ -249,7 +451,7 @@ where
 	accepts_geography!();
 
 	fn to_sql(&self, _: &Type, out: &mut BytesMut) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
-		self.as_ewkb().write_ewkb(&mut out.writer())?;
+		self.as_ewkb().write_ewkb_to_bytes_mut(out)?;
 		Ok(IsNull::No)
 	}
 }
@@ -316,10 +518,40 @@ impl FromSql<'_> for twkb::MultiPolygon {
 	}
 }
 
+impl FromSql<'_> for twkb::GeometryCollection {
+	accepts!(BYTEA);
+
+	fn from_sql(ty: &Type, raw: &[u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+		let mut rdr = Cursor::new(raw);
+		twkb::GeometryCollection::read_twkb(&mut rdr)
+			.map_err(|_| format!("cannot convert {} to GeometryCollection", ty).into())
+	}
+}
+
+impl FromSql<'_> for twkb::Geometry {
+	accepts!(BYTEA);
+
+	fn from_sql(ty: &Type, raw: &[u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+		let mut rdr = Cursor::new(raw);
+		twkb::Geometry::read_twkb(&mut rdr)
+			.map_err(|_| format!("cannot convert {} to Geometry", ty).into())
+	}
+}
+
+impl<G: TwkbGeom> FromSql<'_> for twkb::WithPrecision<G> {
+	accepts!(BYTEA);
+
+	fn from_sql(ty: &Type, raw: &[u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+		let mut rdr = Cursor::new(raw);
+		twkb::WithPrecision::read_twkb(&mut rdr)
+			.map_err(|_| format!("cannot convert {} to {}", ty, std::any::type_name::<G>()).into())
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use crate::{
-		ewkb::{self, AsEwkbLineString, AsEwkbPoint},
+		ewkb::{self, AsEwkbGeometry, AsEwkbGeometryCollection, AsEwkbLineString, AsEwkbPoint, EwkbWrite},
 		twkb, types as postgis,
 	};
 	use postgres::{Client, NoTls};
@@ -552,6 +784,10 @@ mod tests {
         let point = result.iter().map(|r| r.get::<_, ewkb::PointZ>(0)).last().unwrap();
         assert_eq!(point, ewkb::PointZ { x: 10.0, y: -20.0, z: 99.0, srid: Some(4326) });
 
+        let result = or_panic!(client.query("SELECT 'POINT(10 -20 99)'::geography(PointZ)", &[]));
+        let point = result.iter().map(|r| r.get::<_, ewkb::PointZ>(0)).last().unwrap();
+        assert_eq!(point, ewkb::PointZ { x: 10.0, y: -20.0, z: 99.0, srid: Some(4326) });
+
         let result = or_panic!(client.query("SELECT 'POINT EMPTY'::geometry", &[]));
         let point = result.iter().map(|r| r.get::<_, ewkb::Point>(0)).last().unwrap();
         assert_eq!(&format!("{:?}", point), "Point { x: NaN, y: NaN, srid: None }");
@@ -639,6 +875,48 @@ mod tests {
 	#[test]
     #[ignore]
     #[rustfmt::skip]
+    fn test_select_curves() {
+        let mut client = connect();
+        let result = or_panic!(client.query("SELECT 'SRID=4326;COMPOUNDCURVE(CIRCULARSTRING(0 0, 1 1, 2 0), (2 0, 3 0))'::geometry", &[]));
+        let geom = result.iter().map(|r| r.get::<_, ewkb::Geometry>(0)).last().unwrap();
+        assert_eq!(format!("{:.0?}", geom), "CompoundCurve(CompoundCurveT { segments: [CircularString(CircularStringT { points: [Point { x: 0, y: 0, srid: None }, Point { x: 1, y: 1, srid: None }, Point { x: 2, y: 0, srid: None }], srid: None }), LineString(LineStringT { points: [Point { x: 2, y: 0, srid: None }, Point { x: 3, y: 0, srid: None }], srid: None })], srid: Some(4326) })");
+
+        let result = or_panic!(client.query("SELECT 'CURVEPOLYGON(CIRCULARSTRING(0 0, 4 0, 4 4, 0 4, 0 0))'::geometry", &[]));
+        let geom = result.iter().map(|r| r.get::<_, ewkb::CurvePolygon>(0)).last().unwrap();
+        assert!(matches!(&geom.rings[0], ewkb::Curve::CircularString(ring) if ring.points.len() == 5));
+    }
+
+	#[test]
+    #[ignore]
+    #[rustfmt::skip]
+    fn test_select_empty() {
+        let mut client = connect();
+        for wkt in ["POINT EMPTY", "LINESTRING EMPTY", "POLYGON EMPTY", "MULTIPOINT EMPTY", "MULTILINESTRING EMPTY",
+                    "MULTIPOLYGON EMPTY", "GEOMETRYCOLLECTION EMPTY", "CIRCULARSTRING EMPTY", "COMPOUNDCURVE EMPTY",
+                    "CURVEPOLYGON EMPTY", "GEOMETRYCOLLECTION (POLYGON EMPTY, MULTIPOINT EMPTY)"] {
+            let result = or_panic!(client.query(&format!("SELECT g, ST_AsEWKB(g) FROM (SELECT '{}'::geometry AS g) t", wkt), &[]));
+            let row = result.last().unwrap();
+            let geom = row.get::<_, ewkb::Geometry>(0);
+            assert_eq!(geom.as_ewkb().to_ewkb_bytes(), row.get::<_, Vec<u8>>(1), "{}", wkt);
+        }
+    }
+
+	#[test]
+    #[ignore]
+    #[rustfmt::skip]
+    fn test_ewkt_empty_multipoint_member() {
+        let mut client = connect();
+        let result = or_panic!(client.query("SELECT 'MULTIPOINT (EMPTY, 1 2)'::geometry", &[]));
+        let multi = result.last().unwrap().get::<_, ewkb::MultiPoint>(0);
+        let ewkt = multi.to_ewkt();
+        let result = or_panic!(client.query("SELECT ST_GeomFromEWKT($1)", &[&ewkt]));
+        let read_back = result.last().unwrap().get::<_, ewkb::MultiPoint>(0);
+        assert_eq!(format!("{:?}", read_back), format!("{:?}", multi), "{}", ewkt);
+    }
+
+	#[test]
+    #[ignore]
+    #[rustfmt::skip]
     fn test_select_geometry() {
         let mut client = connect();
         or_panic!(client.execute("CREATE TEMPORARY TABLE geomtests (geom geometry)", &[]));
@@ -658,6 +936,89 @@ mod tests {
         assert_eq!(format!("{:?}", poly), "Err(Error { kind: FromSql(0), cause: Some(\"cannot convert geometry to PolygonT\") })");
     }
 
+	#[test]
+    #[rustfmt::skip]
+    fn test_from_sql_null() {
+        use postgres_types::{FromSql, Kind, Type, WasNull};
+        let ty = Type::new("geometry".to_string(), 0, Kind::Simple, "public".to_string());
+        let raw = ewkb::Point::new(10.0, -20.0, Some(4326)).as_ewkb().to_ewkb_bytes();
+        macro_rules! check_null {
+            ($($geotype:ty),*) => {$(
+                assert!(matches!(<Option<$geotype>>::from_sql_nullable(&ty, None), Ok(None)));
+                let err = <$geotype>::from_sql_nullable(&ty, None).unwrap_err();
+                assert!(err.is::<WasNull>(), "{}: {}", stringify!($geotype), err);
+            )*};
+        }
+        check_null!(ewkb::Point, ewkb::PointZ, ewkb::PointM, ewkb::PointZM, ewkb::LineString, ewkb::Polygon,
+                    ewkb::MultiPoint, ewkb::MultiLineString, ewkb::MultiPolygon, ewkb::GeometryCollection,
+                    ewkb::CircularString, ewkb::CompoundCurve, ewkb::CurvePolygon, ewkb::Geometry, ewkb::DynGeometry);
+        let point = <Option<ewkb::Point>>::from_sql_nullable(&ty, Some(&raw)).unwrap();
+        assert_eq!(point, Some(ewkb::Point::new(10.0, -20.0, Some(4326))));
+        let geom = <Option<ewkb::Geometry>>::from_sql_nullable(&ty, Some(&raw)).unwrap();
+        assert!(matches!(geom, Some(ewkb::GeometryT::Point(_))));
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn test_point_fast_path() {
+        use super::read_simple_point;
+        use postgres_types::{FromSql, Kind, Type};
+        let ty = Type::new("geometry".to_string(), 0, Kind::Simple, "public".to_string());
+        let geography = Type::new("geography".to_string(), 0, Kind::Simple, "public".to_string());
+        for srid in [None, Some(0), Some(4326)] {
+            let raw = ewkb::Point::new(10.0, -20.0, srid).as_ewkb().to_ewkb_bytes();
+            assert_eq!(read_simple_point(&raw), Some(ewkb::Point::new(10.0, -20.0, srid)));
+            assert_eq!(or_panic!(ewkb::Point::from_sql(&ty, &raw)), ewkb::Point::new(10.0, -20.0, srid));
+        }
+        // The geography SRID is normalized before the fast path
+        let raw = ewkb::Point::new(10.0, -20.0, None).as_ewkb().to_ewkb_bytes();
+        assert_eq!(or_panic!(crate::Geography::<ewkb::Point>::from_sql(&geography, &raw)).0.srid, Some(4326));
+
+        // Other layouts use the generic reader
+        let xdr = [0, 0, 0, 0, 1, 0x40, 0x24, 0, 0, 0, 0, 0, 0, 0xC0, 0x34, 0, 0, 0, 0, 0, 0];
+        assert_eq!(read_simple_point(&xdr), None);
+        assert_eq!(or_panic!(ewkb::Point::from_sql(&ty, &xdr)), ewkb::Point::new(10.0, -20.0, None));
+        let point_z = ewkb::PointZ::new(1.0, 2.0, 3.0, None).as_ewkb().to_ewkb_bytes();
+        assert_eq!(read_simple_point(&point_z), None);
+        assert_eq!(or_panic!(ewkb::Point::from_sql(&ty, &point_z)), ewkb::Point::new(1.0, 2.0, None));
+        let mut bad_srid = ewkb::Point::new(10.0, -20.0, Some(4326)).as_ewkb().to_ewkb_bytes();
+        bad_srid[5..9].copy_from_slice(&4326i32.to_be_bytes());
+        assert_eq!(read_simple_point(&bad_srid), None);
+        assert!(ewkb::Point::from_sql(&ty, &bad_srid).is_err());
+        assert_eq!(read_simple_point(&[1, 1, 0, 0, 0]), None);
+        assert_eq!(read_simple_point(&[]), None);
+    }
+
+    #[test]
+    #[rustfmt::skip]
+    fn test_raw_geometry_sql() {
+        use bytes::BytesMut;
+        use postgres_types::{FromSql, Kind, ToSql, Type};
+        let geography = Type::new("geography".to_string(), 0, Kind::Simple, "public".to_string());
+        // Geography without SRID is passed through unchanged
+        let raw = ewkb::Point::new(10.0, -20.0, None).as_ewkb().to_ewkb_bytes();
+        let geom = or_panic!(ewkb::RawGeometry::from_sql(&geography, &raw));
+        assert_eq!(geom.0, raw);
+        let mut out = BytesMut::new();
+        or_panic!(geom.to_sql(&geography, &mut out));
+        assert_eq!(&out[..], &raw[..]);
+        assert!(!<ewkb::RawGeometry as FromSql>::accepts(&Type::BYTEA));
+    }
+
+	#[test]
+    #[ignore]
+    #[rustfmt::skip]
+    fn test_select_null() {
+        let mut client = connect();
+        let rows = or_panic!(client.query("SELECT NULL::geometry, NULL::geography", &[]));
+        let row = rows.first().unwrap();
+        assert!(row.get::<_, Option<ewkb::Geometry>>(0).is_none());
+        assert!(row.get::<_, Option<ewkb::Polygon>>(1).is_none());
+        assert!(or_panic!(row.try_get::<_, Option<ewkb::MultiPolygon>>(0)).is_none());
+        let err = row.try_get::<_, ewkb::Point>(0).unwrap_err();
+        assert!(std::error::Error::source(&err).is_some_and(|e| e.is::<postgres_types::WasNull>()), "{:?}", err);
+    }
+
 	#[test]
     #[ignore]
     #[rustfmt::skip]
@@ -684,6 +1045,14 @@ mod tests {
         let result = or_panic!(client.query("SELECT ST_AsTWKB('LINESTRING (10 -20, -0 -0.5)'::geometry, 1)", &[]));
         let line = result.iter().map(|r| r.get::<_, twkb::LineString>(0)).last().unwrap();
         assert_eq!(&format!("{:.1?}", line), "LineString { points: [Point { x: 10.0, y: -20.0 }, Point { x: 0.0, y: -0.5 }] }");
+
+        let result = or_panic!(client.query("SELECT ST_AsTWKB('GEOMETRYCOLLECTION (POINT (1 2), LINESTRING (0 0, 1 1))'::geometry)", &[]));
+        let geom = result.iter().map(|r| r.get::<_, twkb::Geometry>(0)).last().unwrap();
+        assert_eq!(&format!("{:.0?}", geom), "GeometryCollection(GeometryCollection { geometries: [Point(Point { x: 1, y: 2 }), LineString(LineString { points: [Point { x: 0, y: 0 }, Point { x: 1, y: 1 }] })], ids: None })");
+
+        let result = or_panic!(client.query("SELECT ST_AsTWKB('LINESTRING (10 -20, -0 -0.5)'::geometry, 1)", &[]));
+        let line = result.iter().map(|r| r.get::<_, twkb::WithPrecision<twkb::LineString>>(0)).last().unwrap();
+        assert_eq!(line.precision, 1);
     }
 
 	#[test]
@@ -713,6 +1082,120 @@ mod tests {
         or_panic!(client.execute("TRUNCATE geomtests", &[]));
     }
 
+	#[test]
+    #[rustfmt::skip]
+    fn test_adapters_send_sync() {
+        fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+        let p = |x, y| ewkb::Point::new(x, y, Some(4326));
+        let point = p(10.0, -20.0);
+        assert_send_sync(&point.as_ewkb());
+        let line = ewkb::LineString {srid: Some(4326), points: vec![p(10.0, -20.0), p(0., -0.5)]};
+        assert_send_sync(&line.as_ewkb());
+        let collection = ewkb::GeometryCollection {srid: Some(4326), geometries: vec![ewkb::GeometryT::LineString(line)]};
+        assert_send_sync(&collection.as_ewkb());
+        let geometry = ewkb::GeometryT::GeometryCollection(collection);
+        assert_send_sync(&geometry.as_ewkb());
+        let owned = geometry.into_ewkb();
+        assert_send_sync(&owned);
+        let _: &(dyn postgres_types::ToSql + Sync) = &owned;
+    }
+
+	#[test]
+    #[rustfmt::skip]
+    fn test_geography_srid() {
+        use crate::{Geography, ewkb::EwkbWrite};
+        use postgres_types::{FromSql, Kind, Type};
+        let geography = Type::new("geography".into(), 0, Kind::Simple, "public".into());
+        let geometry = Type::new("geometry".into(), 0, Kind::Simple, "public".into());
+
+        // SELECT 'POINT(10 -20)'::geometry
+        let raw = ewkb::Point::new(10.0, -20.0, None).as_ewkb().to_ewkb_bytes();
+        let point = or_panic!(Geography::<ewkb::Point>::from_sql(&geography, &raw)).0;
+        assert_eq!(point, ewkb::Point::new(10.0, -20.0, Some(4326)));
+        assert_eq!(or_panic!(ewkb::Point::from_sql(&geometry, &raw)).srid, None);
+        assert_eq!(or_panic!(Geography::<ewkb::Point>::from_sql(&geometry, &raw)).0.srid, None);
+        assert!(Geography::<ewkb::Point>::accepts(&geography) && !Geography::<ewkb::Point>::accepts(&Type::BYTEA));
+
+        // SRID 0 in big endian
+        let raw = [0u8, 0x20, 0, 0, 1, 0, 0, 0, 0, 0x40, 0x24, 0, 0, 0, 0, 0, 0, 0xC0, 0x34, 0, 0, 0, 0, 0, 0];
+        assert_eq!(or_panic!(Geography::<ewkb::Point>::from_sql(&geography, &raw)).0.srid, Some(4326));
+
+        let line = ewkb::LineString {srid: Some(3857), points: vec![ewkb::Point::new(1.0, 2.0, Some(3857))]};
+        let raw = line.as_ewkb().to_ewkb_bytes();
+        assert_eq!(or_panic!(Geography::<ewkb::LineString>::from_sql(&geography, &raw)).0, line);
+        let raw = ewkb::LineString {srid: None, ..line}.as_ewkb().to_ewkb_bytes();
+        let line = or_panic!(Geography::<ewkb::LineString>::from_sql(&geography, &raw)).0;
+        assert_eq!(line.srid, Some(4326));
+        assert_eq!(line.points[0].srid, Some(4326));
+
+        // SELECT 'SRID=4326;POINT(10 -20 100)'::geography, then without SRID and with SRID 0 in big endian
+        let expected = ewkb::PointZ { x: 10.0, y: -20.0, z: 100.0, srid: Some(4326) };
+        for hex in ["01010000A0E6100000000000000000244000000000000034C00000000000005940",
+                    "0101000080000000000000244000000000000034C00000000000005940",
+                    "00A0000001000000004024000000000000C0340000000000004059000000000000"] {
+            let raw: Vec<u8> = (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap()).collect();
+            assert_eq!(or_panic!(Geography::<ewkb::PointZ>::from_sql(&geography, &raw)).0, expected);
+            match or_panic!(Geography::<ewkb::GeometryT<ewkb::PointZ>>::from_sql(&geography, &raw)).0 {
+                ewkb::GeometryT::Point(point) => assert_eq!(point, expected),
+                _ => panic!("wrong geometry type"),
+            }
+            let geom = or_panic!(Geography::<ewkb::DynGeometry>::from_sql(&geography, &raw)).0;
+            assert_eq!(geom.dimension(), ewkb::PointType::PointZ);
+        }
+
+        // Without the wrapper the value is decoded unchanged
+        match or_panic!(ewkb::GeometryT::<ewkb::Point>::from_sql(&geography, &raw)) {
+            ewkb::GeometryT::LineString(line) => assert_eq!(line.srid, None),
+            _ => panic!("wrong geometry type"),
+        }
+    }
+
+	#[test]
+    #[rustfmt::skip]
+    fn test_dyn_geometry() {
+        use postgres_types::{FromSql, Kind, ToSql, Type};
+        let geometry = Type::new("geometry".into(), 0, Kind::Simple, "public".into());
+
+        // SELECT 'SRID=4326;POINT(10 -20)'::geometry, 'POINT(10 -20 100)'::geometry
+        for (hex, dim) in [("0101000020E6100000000000000000244000000000000034C0", ewkb::PointType::Point),
+                           ("0101000080000000000000244000000000000034C00000000000005940", ewkb::PointType::PointZ)] {
+            let raw: Vec<u8> = (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap()).collect();
+            let geom = or_panic!(ewkb::DynGeometry::from_sql(&geometry, &raw));
+            assert_eq!(geom.dimension(), dim);
+            let mut out = bytes::BytesMut::new();
+            or_panic!(geom.to_sql(&geometry, &mut out));
+            assert_eq!(&out[..], &raw[..]);
+        }
+    }
+
+	#[tokio::test]
+    #[ignore]
+    #[rustfmt::skip]
+    async fn test_tokio_insert() {
+        let (client, connection) = or_panic!(tokio_postgres::connect(&env::var("DBCONN").unwrap(), tokio_postgres::NoTls).await);
+        tokio::spawn(connection);
+        or_panic!(client.execute("CREATE TEMPORARY TABLE geomtests (geom geometry)", &[]).await);
+
+        // Borrowing adapters are held across await points inside spawned tasks
+        let p = |x, y| ewkb::Point::new(x, y, Some(4326));
+        let line = ewkb::LineString {srid: Some(4326), points: vec![p(10.0, -20.0), p(0., -0.5)]};
+        let collection = ewkb::GeometryCollection {srid: Some(4326), geometries: vec![ewkb::GeometryT::LineString(line.clone())]};
+        let client = std::sync::Arc::new(client);
+        let task_client = client.clone();
+        let task = tokio::spawn(async move {
+            task_client.execute("INSERT INTO geomtests (geom) VALUES ($1)", &[&line.as_ewkb()]).await?;
+            task_client.execute("INSERT INTO geomtests (geom) VALUES ($1)", &[&collection.as_ewkb()]).await
+        });
+        or_panic!(or_panic!(task.await));
+
+        let rows = or_panic!(client.query("SELECT geom FROM geomtests", &[]).await);
+        let geom = rows[0].get::<_, ewkb::Geometry>(0);
+        assert_eq!(format!("{:.1?}", geom), "LineString(LineStringT { points: [Point { x: 10.0, y: -20.0, srid: Some(4326) }, Point { x: 0.0, y: -0.5, srid: Some(4326) }], srid: Some(4326) })");
+        let collection = rows[1].get::<_, ewkb::GeometryCollection>(0);
+        assert_eq!(collection.geometries.len(), 1);
+    }
+
 	#[test]
     #[ignore]
     #[rustfmt::skip]
@@ -0,0 +1,115 @@
+//! Read the header of PostGIS rasters in [WKB raster](https://github.com/postgis/postgis/blob/master/raster/doc/RFC2-WellKnownBinaryFormat) format.
+//!
+//! Only the metadata is decoded, band data is not read.
+//!
+//! ```rust,no_run
+//! # use postgres::{Client, NoTls};
+//! use postgis_butmaintained::raster::RasterHeader;
+//!
+//! # let mut client = Client::connect("host=localhost user=postgres", NoTls).unwrap();
+//! for row in &client.query("SELECT ST_AsBinary(rast) FROM tiles", &[]).unwrap() {
+//!     let raw: Vec<u8> = row.get(0);
+//!     let header = RasterHeader::read(&raw).unwrap();
+//!     println!("{}x{} pixels, {} bands", header.width, header.height, header.num_bands);
+//! }
+//! ```
+
+use crate::error::Error;
+use crate::ewkb::encoding::{read_f64, read_i32, read_u16};
+use byteorder::ReadBytesExt;
+
+/// Metadata of a raster, as returned by `ST_MetaData`.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct RasterHeader {
+    /// Byte order of the raster, `true` for big endian (XDR).
+    pub is_be: bool,
+    /// Format version, always 0.
+    pub version: u16,
+    pub num_bands: u16,
+    /// Pixel width in georeferenced units.
+    pub scale_x: f64,
+    /// Pixel height in georeferenced units, usually negative.
+    pub scale_y: f64,
+    /// X coordinate of the upper left corner.
+    pub upper_left_x: f64,
+    /// Y coordinate of the upper left corner.
+    pub upper_left_y: f64,
+    pub skew_x: f64,
+    pub skew_y: f64,
+    /// SRID, 0 if unknown.
+    pub srid: i32,
+    /// Width in pixels.
+    pub width: u16,
+    /// Height in pixels.
+    pub height: u16,
+}
+
+impl RasterHeader {
+    /// Size of the header in bytes.
+    pub const SIZE: usize = 61;
+
+    /// Read the header from the start of a WKB raster, e.g. the result of `ST_AsBinary(rast)`
+    /// or the binary representation of a `raster` column.
+    ///
+    /// Trailing band data is ignored. Other versions than 0 are rejected with an error.
+    pub fn read(mut raw: &[u8]) -> Result<Self, Error> {
+        let raw = &mut raw;
+        let is_be = match raw.read_u8()? {
+            0 => true,
+            1 => false,
+            b => return Err(Error::Read(format!("invalid raster byte order {}", b))),
+        };
+        let version = read_u16(raw, is_be)?;
+        if version != 0 {
+            return Err(Error::Read(format!(
+                "unsupported raster version {}",
+                version
+            )));
+        }
+        Ok(RasterHeader {
+            is_be,
+            version,
+            num_bands: read_u16(raw, is_be)?,
+            scale_x: read_f64(raw, is_be)?,
+            scale_y: read_f64(raw, is_be)?,
+            upper_left_x: read_f64(raw, is_be)?,
+            upper_left_y: read_f64(raw, is_be)?,
+            skew_x: read_f64(raw, is_be)?,
+            skew_y: read_f64(raw, is_be)?,
+            srid: read_i32(raw, is_be)?,
+            width: read_u16(raw, is_be)?,
+            height: read_u16(raw, is_be)?,
+        })
+    }
+}
+
+#[cfg(test)]
+use crate::ewkb::hex_to_vec;
+
+#[test]
+#[rustfmt::skip]
+fn test_read_header() {
+    // 10x20 raster with 2 bands, origin (100 200), pixel size 0.5 and SRID 4326, without band data
+    let raw = hex_to_vec("0100000200000000000000E03F000000000000E0BF0000000000005940000000000000694000000000000000000000000000000000E61000000A001400");
+    assert_eq!(raw.len(), RasterHeader::SIZE);
+    let header = RasterHeader::read(&raw).unwrap();
+    assert_eq!(header, RasterHeader {
+        is_be: false, version: 0, num_bands: 2,
+        scale_x: 0.5, scale_y: -0.5, upper_left_x: 100.0, upper_left_y: 200.0, skew_x: 0.0, skew_y: 0.0,
+        srid: 4326, width: 10, height: 20,
+    });
+
+    // Big endian 256x256 raster without bands, origin (-10 10), skew (0.1 0.2) and SRID 3857
+    let mut raw = hex_to_vec("00000000003FF0000000000000BFF0000000000000C02400000000000040240000000000003FB999999999999A3FC999999999999A00000F1101000100");
+    raw.extend_from_slice(&[0x44, 0x00]); // band data
+    let header = RasterHeader::read(&raw).unwrap();
+    assert!(header.is_be);
+    assert_eq!(header.num_bands, 0);
+    assert_eq!((header.upper_left_x, header.upper_left_y), (-10.0, 10.0));
+    assert_eq!((header.skew_x, header.skew_y), (0.1, 0.2));
+    assert_eq!((header.srid, header.width, header.height), (3857, 256, 256));
+
+    assert!(RasterHeader::read(&raw[..60]).is_err());
+    raw[2] = 1;
+    assert_eq!(RasterHeader::read(&raw).unwrap_err().to_string(), r#"Read("unsupported raster version 1")"#);
+}
@@ -1,8 +1,9 @@
 //! Read geometries in [Tiny WKB](https://github.com/TWKB/Specification/blob/master/twkb.md) format.
 //!
 //! ```rust,no_run
+//! # #[cfg(feature = "postgres")] {
 //! # use postgres::{Client, NoTls};
-//! use postgis::{twkb, LineString, ewkb::AsEwkbPoint};
+//! use postgis_butmaintained::{twkb, LineString, ewkb::AsEwkbPoint};
 //!
 //! # let mut client = Client::connect("host=localhost user=postgres", NoTls).unwrap();
 //! for row in &client.query("SELECT ST_AsTWKB(route) FROM busline", &[]).unwrap() {
@@ -10,6 +11,7 @@
 //!     let last_stop = route.points().last().unwrap();
 //!     let _ = client.execute("INSERT INTO stops (stop) VALUES ($1)", &[&last_stop.as_ewkb()]);
 //! }
+//! # }
 //! ```
 
 use crate::{error::Error, ewkb, types as postgis};
@@ -56,6 +58,45 @@ pub struct MultiPolygon {
 	pub ids: Option<Vec<u64>>,
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Clone, Debug)]
+pub struct GeometryCollection {
+	pub geometries: Vec<Geometry>,
+	pub ids: Option<Vec<u64>>,
+}
+
+/// Geometry of any type, as returned by `ST_AsTWKB` for a generic `geometry` column.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Clone, Debug)]
+pub enum Geometry {
+	Point(Point),
+	LineString(LineString),
+	Polygon(Polygon),
+	MultiPoint(MultiPoint),
+	MultiLineString(MultiLineString),
+	MultiPolygon(MultiPolygon),
+	GeometryCollection(GeometryCollection),
+}
+
+/// Geometry with the precision it was encoded with, e.g. `twkb::WithPrecision<twkb::LineString>`.
+///
+/// The coordinates of `geom` are already scaled. `precision` is the number of decimal digits
+/// of the header, negative for rounding to tens, hundreds etc. Z and M precision aren't kept,
+/// as Z and M values are dropped.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(PartialEq, Clone, Debug)]
+pub struct WithPrecision<G> {
+	pub geom: G,
+	pub precision: i8,
+}
+
+impl<G> WithPrecision<G> {
+	/// Distance between representable coordinates, e.g. 0.01 for a precision of 2.
+	pub fn resolution(&self) -> f64 {
+		10f64.powi(-(self.precision as i32))
+	}
+}
+
 #[doc(hidden)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Default, Debug)]
@@ -69,49 +110,13 @@ pub struct TwkbInfo {
 	has_m: bool,
 	prec_z: Option<u8>,
 	prec_m: Option<u8>,
+	// Number of geometry collections enclosing the geometry
+	depth: usize,
 }
 
 pub trait TwkbGeom: fmt::Debug + Sized {
 	fn read_twkb<R: Read>(raw: &mut R) -> Result<Self, Error> {
-		let mut twkb_info: TwkbInfo = Default::default();
-		// type_and_prec     byte
-		// metadata_header   byte
-		// [extended_dims]   byte
-		// [size]            uvarint
-		// [bounds]          bbox
-		let type_and_prec = raw.read_u8()?;
-		twkb_info.geom_type = type_and_prec & 0x0F;
-		twkb_info.precision = decode_zig_zag_64(((type_and_prec & 0xF0) >> 4) as u64) as i8;
-		let metadata_header = raw.read_u8()?;
-		let has_bbox = (metadata_header & 0b0001) != 0;
-		let has_size_attribute = (metadata_header & 0b0010) != 0;
-		twkb_info.has_idlist = (metadata_header & 0b0100) != 0;
-		let has_ext_prec_info = (metadata_header & 0b1000) != 0;
-		twkb_info.is_empty_geom = (metadata_header & 0b10000) != 0;
-		if has_ext_prec_info {
-			let ext_prec_info = raw.read_u8()?;
-			twkb_info.has_z = ext_prec_info & 0b0001 != 0;
-			twkb_info.has_m = ext_prec_info & 0b0010 != 0;
-			twkb_info.prec_z = Some((ext_prec_info & 0x1C) >> 2);
-			twkb_info.prec_m = Some((ext_prec_info & 0xE0) >> 5);
-		}
-		if has_size_attribute {
-			twkb_info.size = Some(read_raw_varint64(raw)?);
-		}
-		if has_bbox {
-			let _xmin = read_int64(raw)?;
-			let _deltax = read_int64(raw)?;
-			let _ymin = read_int64(raw)?;
-			let _deltay = read_int64(raw)?;
-			if twkb_info.has_z {
-				let _zmin = read_int64(raw)?;
-				let _deltaz = read_int64(raw)?;
-			}
-			if twkb_info.has_m {
-				let _mmin = read_int64(raw)?;
-				let _deltam = read_int64(raw)?;
-			}
-		}
+		let twkb_info = read_twkb_info(raw)?;
 		Self::read_twkb_body(raw, &twkb_info)
 	}
 
@@ -158,6 +163,49 @@ pub trait TwkbGeom: fmt::Debug + Sized {
 
 // --- helper functions for reading ---
 
+fn read_twkb_info<R: Read>(raw: &mut R) -> Result<TwkbInfo, Error> {
+	let mut twkb_info: TwkbInfo = Default::default();
+	// type_and_prec     byte
+	// metadata_header   byte
+	// [extended_dims]   byte
+	// [size]            uvarint
+	// [bounds]          bbox
+	let type_and_prec = raw.read_u8()?;
+	twkb_info.geom_type = type_and_prec & 0x0F;
+	twkb_info.precision = decode_zig_zag_64(((type_and_prec & 0xF0) >> 4) as u64) as i8;
+	let metadata_header = raw.read_u8()?;
+	let has_bbox = (metadata_header & 0b0001) != 0;
+	let has_size_attribute = (metadata_header & 0b0010) != 0;
+	twkb_info.has_idlist = (metadata_header & 0b0100) != 0;
+	let has_ext_prec_info = (metadata_header & 0b1000) != 0;
+	twkb_info.is_empty_geom = (metadata_header & 0b10000) != 0;
+	if has_ext_prec_info {
+		let ext_prec_info = raw.read_u8()?;
+		twkb_info.has_z = ext_prec_info & 0b0001 != 0;
+		twkb_info.has_m = ext_prec_info & 0b0010 != 0;
+		twkb_info.prec_z = Some((ext_prec_info & 0x1C) >> 2);
+		twkb_info.prec_m = Some((ext_prec_info & 0xE0) >> 5);
+	}
+	if has_size_attribute {
+		twkb_info.size = Some(read_raw_varint64(raw)?);
+	}
+	if has_bbox {
+		let _xmin = read_int64(raw)?;
+		let _deltax = read_int64(raw)?;
+		let _ymin = read_int64(raw)?;
+		let _deltay = read_int64(raw)?;
+		if twkb_info.has_z {
+			let _zmin = read_int64(raw)?;
+			let _deltaz = read_int64(raw)?;
+		}
+		if twkb_info.has_m {
+			let _mmin = read_int64(raw)?;
+			let _deltam = read_int64(raw)?;
+		}
+	}
+	Ok(twkb_info)
+}
+
 fn read_raw_varint64<R: Read>(raw: &mut R) -> Result<u64, Error> {
 	// from rust-protobuf
 	let mut r: u64 = 0;
@@ -578,10 +626,138 @@ impl<'a> ewkb::AsEwkbMultiPolygon<'a> for MultiPolygon {
 	}
 }
 
+impl TwkbGeom for GeometryCollection {
+	fn read_twkb_body<R: Read>(raw: &mut R, twkb_info: &TwkbInfo) -> Result<Self, Error> {
+		// ngeometries       uvarint
+		// [idlist]          varint[]
+		// geom[0]           twkb
+		// ...
+		// geom[n]           twkb
+		let mut geometries: Vec<Geometry> = Vec::new();
+		let mut ids: Option<Vec<u64>> = None;
+		if twkb_info.depth >= ewkb::MAX_NESTING_DEPTH {
+			return Err(Error::Read("max nesting depth exceeded".to_string()));
+		}
+		if !twkb_info.is_empty_geom {
+			let ngeometries = read_raw_varint64(raw)?;
+			geometries.reserve(ngeometries as usize);
+
+			if twkb_info.has_idlist {
+				let idlist = Self::read_idlist(raw, ngeometries as usize)?;
+				ids = Some(idlist);
+			}
+
+			// Each geometry has its own header, coordinates start again at the origin
+			for _ in 0..ngeometries {
+				let mut info = read_twkb_info(raw)?;
+				info.depth = twkb_info.depth + 1;
+				geometries.push(Geometry::read_twkb_body(raw, &info)?);
+			}
+		}
+		Ok(GeometryCollection { geometries, ids })
+	}
+}
+
+impl TwkbGeom for Geometry {
+	fn read_twkb_body<R: Read>(raw: &mut R, twkb_info: &TwkbInfo) -> Result<Self, Error> {
+		let geom = match twkb_info.geom_type {
+			1 => Geometry::Point(Point::read_twkb_body(raw, twkb_info)?),
+			2 => Geometry::LineString(LineString::read_twkb_body(raw, twkb_info)?),
+			3 => Geometry::Polygon(Polygon::read_twkb_body(raw, twkb_info)?),
+			4 => Geometry::MultiPoint(MultiPoint::read_twkb_body(raw, twkb_info)?),
+			5 => Geometry::MultiLineString(MultiLineString::read_twkb_body(raw, twkb_info)?),
+			6 => Geometry::MultiPolygon(MultiPolygon::read_twkb_body(raw, twkb_info)?),
+			7 => Geometry::GeometryCollection(GeometryCollection::read_twkb_body(raw, twkb_info)?),
+			_ => {
+				return Err(Error::Read(format!(
+					"unsupported TWKB geometry type {}",
+					twkb_info.geom_type
+				)));
+			}
+		};
+		Ok(geom)
+	}
+}
+
+impl<G: TwkbGeom> TwkbGeom for WithPrecision<G> {
+	fn read_twkb_body<R: Read>(raw: &mut R, twkb_info: &TwkbInfo) -> Result<Self, Error> {
+		Ok(WithPrecision {
+			geom: G::read_twkb_body(raw, twkb_info)?,
+			precision: twkb_info.precision,
+		})
+	}
+}
+
+// --- Owned conversion to EWKB types
+
+impl Point {
+	/// Convert into an owned EWKB point without SRID.
+	pub fn into_ewkb(self) -> ewkb::Point {
+		ewkb::Point::new(self.x, self.y, None)
+	}
+}
+
+impl LineString {
+	/// Convert into an owned EWKB line without SRID.
+	pub fn into_ewkb(self) -> ewkb::LineString {
+		self.points.into_iter().map(Point::into_ewkb).collect()
+	}
+}
+
+impl Polygon {
+	/// Convert into an owned EWKB polygon without SRID.
+	pub fn into_ewkb(self) -> ewkb::Polygon {
+		self.rings.into_iter().map(LineString::into_ewkb).collect()
+	}
+}
+
+impl MultiPoint {
+	/// Convert into an owned EWKB multi point without SRID. The `ids` are dropped.
+	pub fn into_ewkb(self) -> ewkb::MultiPoint {
+		self.points.into_iter().map(Point::into_ewkb).collect()
+	}
+}
+
+impl MultiLineString {
+	/// Convert into an owned EWKB multi line without SRID. The `ids` are dropped.
+	pub fn into_ewkb(self) -> ewkb::MultiLineString {
+		self.lines.into_iter().map(LineString::into_ewkb).collect()
+	}
+}
+
+impl MultiPolygon {
+	/// Convert into an owned EWKB multi polygon without SRID. The `ids` are dropped.
+	pub fn into_ewkb(self) -> ewkb::MultiPolygon {
+		self.polygons.into_iter().map(Polygon::into_ewkb).collect()
+	}
+}
+
+impl GeometryCollection {
+	/// Convert into an owned EWKB geometry collection without SRID. The `ids` are dropped.
+	pub fn into_ewkb(self) -> ewkb::GeometryCollection {
+		self.geometries.into_iter().map(Geometry::into_ewkb).collect()
+	}
+}
+
+impl Geometry {
+	/// Convert into an owned EWKB geometry without SRID. The `ids` are dropped.
+	pub fn into_ewkb(self) -> ewkb::Geometry {
+		match self {
+			Geometry::Point(g) => ewkb::Geometry::Point(g.into_ewkb()),
+			Geometry::LineString(g) => ewkb::Geometry::LineString(g.into_ewkb()),
+			Geometry::Polygon(g) => ewkb::Geometry::Polygon(g.into_ewkb()),
+			Geometry::MultiPoint(g) => ewkb::Geometry::MultiPoint(g.into_ewkb()),
+			Geometry::MultiLineString(g) => ewkb::Geometry::MultiLineString(g.into_ewkb()),
+			Geometry::MultiPolygon(g) => ewkb::Geometry::MultiPolygon(g.into_ewkb()),
+			Geometry::GeometryCollection(g) => ewkb::Geometry::GeometryCollection(g.into_ewkb()),
+		}
+	}
+}
+
 #[cfg(test)]
 use ewkb::{
-	AsEwkbLineString, AsEwkbMultiLineString, AsEwkbMultiPoint, AsEwkbMultiPolygon, AsEwkbPoint,
-	AsEwkbPolygon, EwkbWrite,
+	AsEwkbGeometryCollection, AsEwkbLineString, AsEwkbMultiLineString, AsEwkbMultiPoint,
+	AsEwkbMultiPolygon, AsEwkbPoint, AsEwkbPolygon, EwkbWrite,
 };
 
 #[cfg(test)]
@@ -670,6 +846,62 @@ fn test_read_multipolygon() {
     assert_eq!(format!("{:.0?}", polys), "MultiPolygon { polygons: [Polygon { rings: [LineString { points: [Point { x: 0, y: 0 }, Point { x: 2, y: 0 }, Point { x: 2, y: 2 }, Point { x: 0, y: 2 }, Point { x: 0, y: 0 }] }] }, Polygon { rings: [LineString { points: [Point { x: 10, y: 10 }, Point { x: -2, y: 10 }, Point { x: -2, y: -2 }, Point { x: 10, y: -2 }, Point { x: 10, y: 10 }] }] }], ids: None }");
 }
 
+#[test]
+#[rustfmt::skip]
+fn test_read_geometrycollection() {
+    let twkb = hex_to_vec("0700020100020402000200000202"); // SELECT encode(ST_AsTWKB('GEOMETRYCOLLECTION (POINT (1 2), LINESTRING (0 0, 1 1))'::geometry), 'hex')
+    let collection = GeometryCollection::read_twkb(&mut twkb.as_slice()).unwrap();
+    assert_eq!(format!("{:.0?}", collection), "GeometryCollection { geometries: [Point(Point { x: 1, y: 2 }), LineString(LineString { points: [Point { x: 0, y: 0 }, Point { x: 1, y: 1 }] })], ids: None }");
+    assert_eq!(collection.clone().into_ewkb().as_ewkb().to_hex_ewkb(), "0107000000020000000101000000000000000000F03F000000000000004001020000000200000000000000000000000000000000000000000000000000F03F000000000000F03F");
+
+    let geom = Geometry::read_twkb(&mut twkb.as_slice()).unwrap();
+    assert_eq!(geom, Geometry::GeometryCollection(collection));
+
+    let twkb = hex_to_vec("0710"); // SELECT encode(ST_AsTWKB('GEOMETRYCOLLECTION EMPTY'::geometry), 'hex')
+    let collection = GeometryCollection::read_twkb(&mut twkb.as_slice()).unwrap();
+    assert_eq!(collection, GeometryCollection {geometries: vec![], ids: None});
+
+    let twkb = hex_to_vec("05000202142713260200020400"); // SELECT encode(ST_AsTWKB('MULTILINESTRING ((10 -20, 0 -0.5), (0 0, 2 0))'::geometry), 'hex')
+    assert!(matches!(Geometry::read_twkb(&mut twkb.as_slice()).unwrap(), Geometry::MultiLineString(lines) if lines.lines.len() == 2));
+
+    let twkb = hex_to_vec("0800");
+    assert_eq!(Geometry::read_twkb(&mut twkb.as_slice()).unwrap_err().to_string(), r#"Read("unsupported TWKB geometry type 8")"#);
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_max_nesting_depth() {
+    // `depth` geometry collections nested in each other, the innermost one empty
+    let nested = |depth: usize| hex_to_vec(&("070001".repeat(depth - 1) + "0710"));
+    let geom = Geometry::read_twkb(&mut nested(ewkb::MAX_NESTING_DEPTH).as_slice()).unwrap();
+    assert!(matches!(geom, Geometry::GeometryCollection(ref c) if c.geometries.len() == 1));
+    let err = GeometryCollection::read_twkb(&mut nested(ewkb::MAX_NESTING_DEPTH + 1).as_slice()).unwrap_err();
+    assert_eq!(err.to_string(), r#"Read("max nesting depth exceeded")"#);
+    // Deep nesting fails instead of overflowing the stack
+    assert!(Geometry::read_twkb(&mut nested(200_000).as_slice()).is_err());
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_read_with_precision() {
+    let twkb = hex_to_vec("220002c8018f03c7018603"); // SELECT encode(ST_AsTWKB('LINESTRING (10 -20, -0 -0.5)'::geometry, 1), 'hex')
+    let line = WithPrecision::<LineString>::read_twkb(&mut twkb.as_slice()).unwrap();
+    assert_eq!(line.precision, 1);
+    assert_eq!(line.resolution(), 0.1);
+    assert_eq!(line.geom, LineString::read_twkb(&mut twkb.as_slice()).unwrap());
+
+    let twkb = hex_to_vec("11000203"); // SELECT encode(ST_AsTWKB('POINT(11.12 -22.34)'::geometry, -1), 'hex')
+    let point = WithPrecision::<Point>::read_twkb(&mut twkb.as_slice()).unwrap();
+    assert_eq!(point.precision, -1);
+    assert_eq!(point.resolution(), 10.0);
+    assert_eq!(format!("{:.0?}", point.geom), "Point { x: 10, y: -20 }");
+
+    let twkb = hex_to_vec("0700020100020402000200000202"); // SELECT encode(ST_AsTWKB('GEOMETRYCOLLECTION (POINT (1 2), LINESTRING (0 0, 1 1))'::geometry), 'hex')
+    let geom = WithPrecision::<Geometry>::read_twkb(&mut twkb.as_slice()).unwrap();
+    assert_eq!(geom.precision, 0);
+    assert!(matches!(geom.geom, Geometry::GeometryCollection(_)));
+}
+
 #[test]
 #[rustfmt::skip]
 fn test_write_point() {
@@ -755,3 +987,26 @@ mod serde_tests {
 		assert_eq!(line, deserialized);
 	}
 }
+
+#[test]
+#[rustfmt::skip]
+fn test_into_ewkb() {
+    fn assert_send_sync<T: Send + Sync + 'static>(_: &T) {}
+
+    let twkb = hex_to_vec("02000214271326"); // SELECT encode(ST_AsTWKB('LINESTRING (10 -20, 0 -0.5)'::geometry), 'hex')
+    let line = LineString::read_twkb(&mut twkb.as_slice()).unwrap();
+    let expected = line.as_ewkb().to_hex_ewkb();
+    let line = line.into_ewkb();
+    assert_send_sync(&line);
+    assert_eq!(line.as_ewkb().to_hex_ewkb(), expected);
+
+    let twkb = hex_to_vec("060002010500000400040000030303000201010100020002000000"); // SELECT encode(ST_AsTWKB('MULTIPOLYGON (((0 0,2 0,2 2,0 2,0 0)),((10 10,-2 10,-2 -2,10 -2,10 10)))'::geometry), 'hex')
+    let polys = MultiPolygon::read_twkb(&mut twkb.as_slice()).unwrap();
+    let expected = polys.as_ewkb().to_hex_ewkb();
+    assert_eq!(polys.into_ewkb().as_ewkb().to_hex_ewkb(), expected);
+
+    let twkb = hex_to_vec("04000214271326"); // SELECT encode(ST_AsTWKB('MULTIPOINT ((10 -20), (0 -0.5))'::geometry), 'hex')
+    let points = MultiPoint::read_twkb(&mut twkb.as_slice()).unwrap();
+    let expected = points.as_ewkb().to_hex_ewkb();
+    assert_eq!(points.into_ewkb().as_ewkb().to_hex_ewkb(), expected);
+}
@@ -47,6 +47,7 @@ pub trait Geometry<'a>: Send + Sync {
     type MultiLineString: 'a + MultiLineString<'a>;
     type MultiPolygon: 'a + MultiPolygon<'a>;
     type GeometryCollection: 'a + GeometryCollection<'a>;
+    #[allow(clippy::type_complexity)]
     fn as_type(
         &'a self,
     ) -> GeometryType<
@@ -78,9 +79,10 @@ where
     MultiLineString(&'a ML),
     MultiPolygon(&'a MY),
     GeometryCollection(&'a GC),
+    Curve(&'a dyn crate::ewkb::CurveWrite),
 }
 
-pub trait GeometryCollection<'a> {
+pub trait GeometryCollection<'a>: Send + Sync {
     type ItemType: 'a;
     type Iter: Iterator<Item = &'a Self::ItemType>;
     fn geometries(&'a self) -> Self::Iter;